@@ -2,6 +2,7 @@
 use std::{
     alloc::{self, Layout},
     cmp::Ordering,
+    fmt,
     hash::{Hash, Hasher},
     marker::PhantomData,
     mem::{self, ManuallyDrop, offset_of},
@@ -45,6 +46,22 @@ unsafe impl<T: ?Sized + Sync + Send> Send for Arc<T> {}
 unsafe impl<T: ?Sized + Sync + Send> Sync for Arc<T> {}
 
 impl<T> Arc<T> {
+    /// Construct an `Arc<T>`
+    #[inline]
+    pub(crate) fn new(data: T) -> Self {
+        let ptr = Box::into_raw(Box::new(ArcInner {
+            count: atomic::AtomicUsize::new(1),
+            data,
+        }));
+
+        unsafe {
+            Arc {
+                p: ptr::NonNull::new_unchecked(ptr),
+                phantom: PhantomData,
+            }
+        }
+    }
+
     /// Reconstruct the Arc<T> from a raw pointer obtained from into_raw()
     ///
     /// Note: This raw pointer will be offset in the allocation and must be preceded
@@ -144,6 +161,39 @@ impl<T: ?Sized> Deref for Arc<T> {
     }
 }
 
+impl<T: Clone> Arc<T> {
+    /// Makes a mutable reference to the `Arc`, cloning if necessary
+    ///
+    /// This is functionally equivalent to [`Arc::make_mut`][mm] from the standard library.
+    ///
+    /// If this `Arc` is uniquely owned, `make_mut()` will provide a mutable
+    /// reference to the contents. If not, `make_mut()` will create a _new_ `Arc`
+    /// with a copy of the contents, update `this` to point to it, and provide
+    /// a mutable reference to its contents.
+    ///
+    /// This is useful for implementing copy-on-write schemes where you wish to
+    /// avoid copying things if your `Arc` is not shared.
+    ///
+    /// [mm]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html#method.make_mut
+    #[cfg(test)]
+    #[inline]
+    pub(crate) fn make_mut(this: &mut Self) -> &mut T {
+        if !this.is_unique() {
+            // Another pointer exists; clone
+            *this = Arc::new((**this).clone());
+        }
+
+        unsafe {
+            // This unsafety is ok because we're guaranteed that the pointer
+            // returned is the *only* pointer that will ever be returned to T. Our
+            // reference count is guaranteed to be 1 at this point, and we required
+            // the Arc itself to be `mut`, so we're returning the only possible
+            // reference to the inner data.
+            &mut (*this.ptr()).data
+        }
+    }
+}
+
 impl<T: ?Sized> Arc<T> {
     /// Provides mutable access to the contents _if_ the `Arc` is uniquely owned.
     #[inline]
@@ -246,6 +296,12 @@ impl<T: ?Sized + Hash> Hash for Arc<T> {
     }
 }
 
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd)]
 #[repr(C)]
 pub(crate) struct HeaderSlice<H, T: ?Sized> {
@@ -483,3 +539,33 @@ impl<H: Hash, T: Hash> Hash for ThinArc<H, T> {
         (**self).hash(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_make_mut_when_unique_expect_mutated_in_place() {
+        let mut arc = Arc::new(vec![1, 2]);
+        let before = arc.ptr();
+
+        Arc::make_mut(&mut arc).push(3);
+
+        assert!(std::ptr::eq(arc.ptr(), before));
+        assert_eq!(*arc, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_make_mut_when_shared_expect_copy_diverges_from_other_owner() {
+        let mut arc = Arc::new(vec![1, 2]);
+        let other = arc.clone();
+
+        Arc::make_mut(&mut arc).push(3);
+
+        assert!(!Arc::ptr_eq(&arc, &other));
+        assert_eq!(*arc, vec![1, 2, 3]);
+        assert_eq!(*other, vec![1, 2]);
+        assert!(arc.is_unique() && other.is_unique());
+    }
+}
@@ -22,6 +22,22 @@ pub enum DiagnosticKind {
     InvalidNonRegularCharacterInName = 6,
     /// Missing required whitespace between tokens (SafeDocs PDF Compacted Syntax Matrix).
     MissingWhitespaceBeforeToken = 7,
+    /// Array `[` or dictionary `<<` opener without a matching closer.
+    UnmatchedOpeningDelimiter = 8,
+    /// Array `]`, dictionary `>>` or string `)` closer without a matching opener.
+    UnmatchedClosingDelimiter = 9,
+    /// Bytes that do not form any PDF token (ISO 32000-2:2020 §7.2).
+    UnrecognizedToken = 10,
+    /// Hexadecimal string with an odd number of digits; the final digit is assumed to be followed by 0 (ISO 32000-2:2020 §7.3.4.3).
+    OddLengthHexString = 11,
+    /// Cross-reference table entry not laid out as `nnnnnnnnnn ggggg n` in 20 bytes (ISO 32000-2:2020 §7.5.4).
+    MalformedXRefEntry = 12,
+    /// Integer outside the 32-bit range readers must support (ISO 32000-2:2020 Annex C.2, Table C.1).
+    IntegerOverflow = 13,
+    /// File trailer without a `startxref` keyword (ISO 32000-2:2020 §7.5.5).
+    MissingStartXRef = 14,
+    /// `startxref` not followed by a non-negative integer byte offset (ISO 32000-2:2020 §7.5.5).
+    InvalidStartXRefOffset = 15,
 }
 
 impl DiagnosticKind {
@@ -36,6 +52,14 @@ impl DiagnosticKind {
             DiagnosticKind::InvalidHexEscapeInName => "Invalid hex escape in name",
             DiagnosticKind::InvalidNonRegularCharacterInName => "Invalid character in name (needs hex escape)",
             DiagnosticKind::MissingWhitespaceBeforeToken => "Missing whitespace before token",
+            DiagnosticKind::UnmatchedOpeningDelimiter => "Unmatched opening delimiter",
+            DiagnosticKind::UnmatchedClosingDelimiter => "Unmatched closing delimiter",
+            DiagnosticKind::UnrecognizedToken => "Unrecognized token",
+            DiagnosticKind::OddLengthHexString => "Odd number of digits in hex string",
+            DiagnosticKind::MalformedXRefEntry => "Malformed cross-reference entry",
+            DiagnosticKind::IntegerOverflow => "Integer out of range",
+            DiagnosticKind::MissingStartXRef => "Missing startxref",
+            DiagnosticKind::InvalidStartXRefOffset => "Invalid startxref offset",
         }
     }
 }
@@ -52,6 +76,14 @@ impl From<u16> for DiagnosticKind {
             5 => DiagnosticKind::InvalidHexEscapeInName,
             6 => DiagnosticKind::InvalidNonRegularCharacterInName,
             7 => DiagnosticKind::MissingWhitespaceBeforeToken,
+            8 => DiagnosticKind::UnmatchedOpeningDelimiter,
+            9 => DiagnosticKind::UnmatchedClosingDelimiter,
+            10 => DiagnosticKind::UnrecognizedToken,
+            11 => DiagnosticKind::OddLengthHexString,
+            12 => DiagnosticKind::MalformedXRefEntry,
+            13 => DiagnosticKind::IntegerOverflow,
+            14 => DiagnosticKind::MissingStartXRef,
+            15 => DiagnosticKind::InvalidStartXRefOffset,
             _ => DiagnosticKind::Unknown,
         }
     }
@@ -1,35 +1,170 @@
 #![allow(dead_code)]
 
+mod backward;
 mod cursor;
+mod diff;
+mod header;
+mod linearization;
+mod objects;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod stats;
+mod streaming;
+mod validation;
+mod xref;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
-use std::ops::Range;
+use std::{collections::VecDeque, ops::Range};
 
 use crate::{
     DiagnosticKind, DiagnosticSeverity, GreenDiagnostic, GreenNode, GreenNodeElement, GreenToken, GreenTokenElement, GreenTokenWithIntValue,
     GreenTokenWithIntValueAndTrailingTrivia, GreenTokenWithIntValueAndTrivia, GreenTokenWithTrailingTrivia, GreenTokenWithTrivia, GreenTrivia, SyntaxKind,
 };
 
+pub(crate) use self::{
+    diff::{TokenChangeKind, TokenDiff, diff_tokens},
+    header::{PdfHeaderError, PdfVersion},
+    linearization::is_linearized,
+    objects::{StreamLength, find_xref_streams, objstm_object_count, split_objects, stream_length},
+    stats::{LexStats, stats},
+    streaming::StreamingLexer,
+    validation::validate_brackets,
+    xref::XRefEntry,
+};
+
+#[cfg(feature = "parallel")]
+pub(crate) use self::parallel::parallel_tokenize;
+
+/// UTF-8 encoding of U+FEFF.
+const BYTE_ORDER_MARK: &[u8] = b"\xEF\xBB\xBF";
+
+/// How strictly the lexer reports deviations it can recover from.
+///
+/// The token stream is identical in both modes; only diagnostics differ:
+///
+/// | Recoverable deviation                                      | `Strict`                                                 | `Lenient` |
+/// |------------------------------------------------------------|----------------------------------------------------------|-----------|
+/// | No whitespace between a number or keyword and what follows | [`DiagnosticKind::MissingWhitespaceBeforeToken`] error   | none      |
+/// | Unknown escape in a literal string (backslash ignored)     | [`DiagnosticKind::InvalidEscapeInStringLiteral`] warning | none      |
+/// | Odd number of digits in a hex string (final `0` assumed)   | [`DiagnosticKind::OddLengthHexString`] warning           | none      |
+/// | Integer outside the 32-bit range readers must support      | [`DiagnosticKind::IntegerOverflow`] warning              | none      |
+/// | Cross-reference entry without a two-byte end-of-line       | [`DiagnosticKind::MalformedXRefEntry`] warning           | none      |
+///
+/// Unrecoverable input (unbalanced strings, invalid hex digits, unrecognized bytes) is reported in
+/// both modes. A leading byte order mark is handled by [`LexerOptions::skip_byte_order_mark`] and
+/// is not reported in either mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LexerMode {
+    /// Reports every recoverable deviation, for validators.
+    #[default]
+    Strict,
+    /// Recovers from deviations silently, for editors.
+    Lenient,
+}
+
+/// Optional lexer behaviors, fixed for the lifetime of a [`Lexer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// Merges a run of unrecognizable bytes, including stray `)` and `>` delimiters, into a single
+    /// [`SyntaxKind::BadToken`] so garbage input yields one token and one diagnostic rather than a flood.
+    ///
+    /// When disabled, every delimiter ends the bad token. Enabled by default.
+    pub coalesce_bad_tokens: bool,
+    /// Recognizes content stream operators (`BT`, `Tf`, `re`, `f*`, ...) as their operator kinds instead
+    /// of [`SyntaxKind::BadToken`], for lexing decoded content streams rather than document structure.
+    ///
+    /// In this mode `f` and `n` are the fill and end-path operators, not cross-reference entry keywords.
+    /// Inline image data after `ID` is not skipped. Disabled by default.
+    ///
+    /// See: ISO 32000-2:2020, §8.2 Graphics objects, Annex A.2 Table A.1.
+    pub content_stream_operators: bool,
+    /// Treats a UTF-8 byte order mark at the very start of the input as
+    /// [`SyntaxKind::ByteOrderMarkTrivia`], so a following `%PDF-` header is still recognized.
+    ///
+    /// Enabled by default.
+    pub skip_byte_order_mark: bool,
+    /// Treats the bytes after the `stream` keyword up to the next `endstream` as one opaque
+    /// [`SyntaxKind::RawStreamDataToken`], so binary or compressed data is never tokenized.
+    ///
+    /// The end is found by scanning for `endstream`, not by trusting `/Length`. When disabled, stream
+    /// bodies are lexed as ordinary tokens, e.g. to inspect uncompressed content. Enabled by default.
+    pub scan_stream_data: bool,
+    /// Emits each `%` comment as a [`SyntaxKind::CommentToken`] in the token stream, e.g. for a
+    /// comment outline, instead of attaching it as [`SyntaxKind::CommentTrivia`] to a token.
+    ///
+    /// The EOL after the comment stays trivia of the comment token. A well-formed `%PDF-x.y` header
+    /// or `%%EOF` marker is its own token in both modes; a malformed one is a comment. Disabled by default.
+    pub comments_as_tokens: bool,
+    /// Whether recoverable deviations are reported, see [`LexerMode`]. Strict by default.
+    pub mode: LexerMode,
+}
+
+impl Default for LexerOptions {
+    fn default() -> Self {
+        Self {
+            coalesce_bad_tokens: true,
+            content_stream_operators: false,
+            skip_byte_order_mark: true,
+            scan_stream_data: true,
+            comments_as_tokens: false,
+            mode: LexerMode::Strict,
+        }
+    }
+}
+
 // TODO: add normal & stream lexer modes
 // TODO: add skip_trivia option
 /// Tokenizes PDF source code into a stream of tokens with full trivia preservation.
 ///
 /// Scans byte sequences and emits tokens following ISO 32000-2:2020 lexical rules.
 /// Preserves all whitespace and comments as trivia for full-fidelity reconstruction.
+///
+/// # Forward progress
+///
+/// On any input, [`Self::next_token`] makes progress: every token before the end of file consumes
+/// at least one byte, except the [`SyntaxKind::RawStreamDataToken`] of an empty stream body, which
+/// directly follows the `stream` keyword and is followed by `endstream` or the end of file. The full
+/// widths of the tokens up to and including the [`SyntaxKind::EndOfFileToken`] add up to the input
+/// length, so a loop advancing by [`GreenTokenElement::full_width`] until the end of file terminates
+/// after at most one call more than twice the number of bytes.
 pub struct Lexer<'source> {
     pub(super) source: &'source [u8],
     pub(super) position: usize,
     pub(super) lexeme: Option<Range<usize>>, // start=position, end=start+width
     is_raw_stream: bool,
+    options: LexerOptions,
+    /// Tokens already scanned by [`Self::peek_nth`] and not yet returned by [`Self::next_token`].
+    lookahead: VecDeque<GreenTokenElement>,
+    /// The header version found by [`Self::from_pdf`].
+    pdf_version: Option<PdfVersion>,
+    /// Offset table pairs [`Self::new_objstm`] still has to scan as the object stream header.
+    objstm_header_pairs: u32,
+    /// Position in a cross-reference table, see [`Self::scan_xref_entry`].
+    xref_state: XRefState,
+}
+
+/// Progress through a cross-reference table, updated after every token.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum XRefState {
+    /// Outside a cross-reference table.
+    #[default]
+    None,
+    /// After `xref` or the last entry of a subsection, expecting the first object number of a subsection.
+    SubsectionStart,
+    /// After the first object number of a subsection, expecting its entry count.
+    SubsectionCount,
+    /// Inside a subsection, with this many entries left to scan.
+    Entries(u32),
 }
 
 #[derive(Debug)]
 struct TokenInfo<'a> {
     kind: SyntaxKind,
     bytes: &'a [u8],
-    diagnostics: Vec<(DiagnosticSeverity, DiagnosticKind, &'static str)>,
+    /// Diagnostics for the token, each with the range of the token text it applies to, if not all of it.
+    diagnostics: Vec<(DiagnosticSeverity, DiagnosticKind, Option<Range<u32>>)>,
 }
 
 impl<'a> Default for TokenInfo<'a> {
@@ -44,14 +179,54 @@ impl<'a> Default for TokenInfo<'a> {
 
 impl<'source> Lexer<'source> {
     pub fn new(source: &'source [u8]) -> Self {
+        Self::with_options(source, LexerOptions::default())
+    }
+
+    pub fn with_options(source: &'source [u8], options: LexerOptions) -> Self {
         Self {
             source,
             position: 0,
             lexeme: None,
             is_raw_stream: false,
+            options,
+            lookahead: VecDeque::new(),
+            pdf_version: None,
+            objstm_header_pairs: 0,
+            xref_state: XRefState::None,
         }
     }
 
+    /// Creates a lexer for the decoded payload of an object stream holding `object_count` objects.
+    ///
+    /// The payload starts with `object_count` pairs of object number and offset, lexed as one
+    /// [`SyntaxKind::ObjStmHeaderToken`], followed by the bare objects without `obj` and `endobj`
+    /// keywords, which lex as usual. Pass the stream's `/N` entry, see `objstm_object_count`; the
+    /// header cannot be told apart from integer objects without it.
+    ///
+    /// See: ISO 32000-2:2020, §7.5.7 Object streams.
+    pub(crate) fn new_objstm(source: &'source [u8], object_count: u32) -> Self {
+        let mut lexer = Self::new(source);
+        lexer.objstm_header_pairs = object_count;
+        lexer
+    }
+
+    /// Creates a lexer for a file that must start with a `%PDF-1.x` or `%PDF-2.0` header within its first 1024 bytes.
+    ///
+    /// Lexing still starts at byte 0, so any leading bytes and the header itself are tokenized as usual.
+    /// The declared version is available from [`Self::pdf_version`].
+    pub(crate) fn from_pdf(source: &'source [u8]) -> Result<Self, PdfHeaderError> {
+        let version = header::find_pdf_version(source)?;
+        let mut lexer = Self::new(source);
+        lexer.pdf_version = Some(version);
+        Ok(lexer)
+    }
+
+    /// Returns the header version if this lexer was created by [`Self::from_pdf`].
+    #[inline]
+    pub(crate) fn pdf_version(&self) -> Option<PdfVersion> {
+        self.pdf_version
+    }
+
     /// Scans and returns the next token from the source, including its associated trivia.
     ///
     /// The token includes:
@@ -75,10 +250,82 @@ impl<'source> Lexer<'source> {
     ///        leading="  ", trailing=" % comment\n"
     /// ```
     pub fn next_token(&mut self) -> GreenTokenElement {
+        match self.lookahead.pop_front() {
+            Some(token) => token,
+            None => self.scan_next_token(),
+        }
+    }
+
+    /// Returns the token the next [`Self::next_token`] call will return, without consuming it.
+    #[inline]
+    pub fn peek_token(&mut self) -> GreenTokenElement {
+        self.peek_nth(0)
+    }
+
+    /// Returns the token `n` positions ahead (`0` being the next one), without consuming any token.
+    ///
+    /// Tokens up to `n` are scanned once and buffered; later [`Self::next_token`] calls return them
+    /// in order, so trivia and offsets are the same as without peeking. Past the end of the input,
+    /// every position holds a zero-width [`SyntaxKind::EndOfFileToken`].
+    pub fn peek_nth(&mut self, n: usize) -> GreenTokenElement {
+        while self.lookahead.len() <= n {
+            let token = self.scan_next_token();
+            self.lookahead.push_back(token);
+        }
+        self.lookahead[n].clone()
+    }
+
+    /// Consumes the lexer into an iterator over the remaining tokens, each paired with the byte offset
+    /// where its full span, leading trivia included, starts.
+    ///
+    /// Offsets advance by each token's full width, starting from the next token even if tokens were
+    /// peeked. The iteration ends before [`SyntaxKind::EndOfFileToken`], so trivia at the very end of
+    /// the input is not yielded.
+    pub(crate) fn token_offsets(mut self) -> impl Iterator<Item = (usize, GreenTokenElement)> + 'source {
+        let mut offset = self.position - self.lookahead.iter().map(|token| token.full_width() as usize).sum::<usize>();
+        std::iter::from_fn(move || {
+            let token = self.next_token();
+            if token.kind() == SyntaxKind::EndOfFileToken {
+                return None;
+            }
+
+            let start = offset;
+            offset += token.full_width() as usize;
+            Some((start, token))
+        })
+    }
+
+    /// Moves the lexer to byte `position` of the source, as if the tokens before it were already returned.
+    ///
+    /// Buffered lookahead is dropped. `position` must be a token boundary outside stream data, since
+    /// the lexer cannot tell that it resumes inside a stream body.
+    pub(crate) fn seek(&mut self, position: usize) {
+        self.position = position.min(self.source.len());
+        self.lexeme = None;
+        self.is_raw_stream = false;
+        self.xref_state = XRefState::None;
+        self.lookahead.clear();
+    }
+
+    /// Returns `true` if the next token does not depend on the tokens before it, so [`Self::seek`]
+    /// to the current position would resume lexing the same way: outside stream data and outside a
+    /// cross-reference table. Buffered lookahead is not taken into account.
+    pub(crate) fn is_at_rest(&self) -> bool {
+        !self.is_raw_stream && self.xref_state == XRefState::None
+    }
+
+    fn scan_next_token(&mut self) -> GreenTokenElement {
+        let start = self.position;
         let mut token_info: TokenInfo<'source> = TokenInfo::default();
         let leading_trivia = self.scan_trivia(&token_info);
         self.scan_token(&mut token_info);
+        self.xref_state = self.next_xref_state(&token_info);
         let trailing_trivia = self.scan_trivia(&token_info);
+        debug_assert!(
+            self.position > start || matches!(token_info.kind, SyntaxKind::EndOfFileToken | SyntaxKind::RawStreamDataToken),
+            "zero-width {:?} at byte {start} breaks forward progress",
+            token_info.kind
+        );
 
         // Build trivia lists
         let leading = if leading_trivia.is_empty() {
@@ -102,12 +349,42 @@ impl<'source> Lexer<'source> {
         let diagnostics = token_info
             .diagnostics
             .iter()
-            .map(|(severity, kind, message)| GreenDiagnostic::new(*kind, *severity, message))
+            .map(|(severity, kind, range)| match range {
+                Some(range) => GreenDiagnostic::new_with_range(*kind, *severity, kind.as_str(), range.clone()),
+                None => GreenDiagnostic::new(*kind, *severity, kind.as_str()),
+            })
             .collect::<Vec<_>>();
 
         self.create_token_element(token_info.kind, token_info.bytes, leading, trailing, diagnostics)
     }
 
+    /// Scans and returns the next token that is not trivia.
+    ///
+    /// Whitespace is always attached to the surrounding tokens, so this only differs from
+    /// [`Self::next_token`] with [`LexerOptions::comments_as_tokens`], where it skips
+    /// [`SyntaxKind::CommentToken`]s. Parsers should call it when they only care about significant
+    /// tokens, so their code works in both modes.
+    ///
+    /// Skipped tokens are dropped together with their trivia, so the returned tokens no longer
+    /// cover the source: summing their [`GreenTokenElement::full_width`] does not give byte offsets.
+    /// Callers that track positions should use [`Self::next_token`] and skip comments themselves.
+    #[inline]
+    pub fn next_significant_token(&mut self) -> GreenTokenElement {
+        loop {
+            let token = self.next_token();
+            if !matches!(
+                token.kind(),
+                SyntaxKind::EndOfLineTrivia
+                    | SyntaxKind::WhitespaceTrivia
+                    | SyntaxKind::CommentTrivia
+                    | SyntaxKind::CommentToken
+                    | SyntaxKind::ByteOrderMarkTrivia
+            ) {
+                return token;
+            }
+        }
+    }
+
     fn create_token_element(
         &self,
         kind: SyntaxKind,
@@ -158,7 +435,8 @@ impl<'source> Lexer<'source> {
     /// - Numeric literals (integers and reals): `0-9`, `+`, `-`, `.`
     ///
     /// Unknown/unsupported characters are scanned as [`SyntaxKind::BadToken`] and continue until
-    /// a delimiter, whitespace, or EOF is encountered.
+    /// a delimiter, whitespace, or EOF is encountered. Every [`SyntaxKind::BadToken`], including
+    /// malformed numbers and unknown keywords, carries a [`DiagnosticKind::UnrecognizedToken`] error.
     /// When EOF is reached, sets [`SyntaxKind::EndOfFileToken`] with empty bytes.
     fn scan_token(&mut self, token_info: &mut TokenInfo<'source>) {
         let first_byte = match self.peek() {
@@ -172,17 +450,29 @@ impl<'source> Lexer<'source> {
 
         self.start_lexeme();
 
+        // Only the first token of an object stream can be its header.
+        let objstm_header_pairs = std::mem::take(&mut self.objstm_header_pairs);
+
         match first_byte {
             _ if self.is_raw_stream => {
                 // In raw stream mode, everything until 'endstream' is treated as a raw data token
                 self.scan_raw_stream_data(token_info);
             }
+            b'0'..=b'9' if objstm_header_pairs > 0 => {
+                self.scan_objstm_header(token_info, objstm_header_pairs);
+            }
+            b'0'..=b'9' if matches!(self.xref_state, XRefState::Entries(_)) => {
+                self.scan_xref_entry(token_info);
+            }
             b'%' if self.is_valid_pdf_version_token() => {
                 self.scan_pdf_version(token_info);
             }
             b'%' if self.is_eof_token() => {
                 self.scan_eof_marker(token_info);
             }
+            b'%' => {
+                self.scan_comment_token(token_info); // only reached with `comments_as_tokens`
+            }
             b'0'..=b'9' | b'+' | b'-' | b'.' => {
                 self.scan_numeric_literal(token_info);
             }
@@ -218,6 +508,13 @@ impl<'source> Lexer<'source> {
             }
         };
 
+        // Bad tokens come from several scanners (stray bytes, malformed numbers, unknown keywords);
+        // each is reported once here, ahead of any diagnostic its scanner added.
+        if token_info.kind == SyntaxKind::BadToken {
+            let kind = DiagnosticKind::UnrecognizedToken;
+            token_info.diagnostics.insert(0, (DiagnosticSeverity::Error, kind, None));
+        }
+
         self.stop_lexeme();
     }
 
@@ -253,9 +550,12 @@ impl<'source> Lexer<'source> {
                 b'\r' | b'\n' => {
                     trivia.push(self.scan_end_of_line());
                 }
+                0xEF if self.is_byte_order_mark() => {
+                    trivia.push(self.scan_byte_order_mark());
+                }
                 b'%' => {
                     // Check if this is a special token that should be scanned as a token, not trivia
-                    if self.is_valid_pdf_version_token() || self.is_eof_token() {
+                    if self.options.comments_as_tokens || self.is_valid_pdf_version_token() || self.is_eof_token() {
                         break; // Let scan_token handle these
                     }
                     trivia.push(self.scan_comment());
@@ -395,6 +695,18 @@ impl<'source> Lexer<'source> {
         token_info.bytes = &self.source[pos..self.position];
     }
 
+    /// Checks if the input starts with a UTF-8 byte order mark that should be skipped as trivia.
+    fn is_byte_order_mark(&self) -> bool {
+        self.options.skip_byte_order_mark && self.position == 0 && self.matches_sequence(BYTE_ORDER_MARK)
+    }
+
+    /// Scans the UTF-8 byte order mark at the start of the input and returns a trivia element.
+    fn scan_byte_order_mark(&mut self) -> GreenTrivia {
+        let pos = self.position;
+        self.advance_by(BYTE_ORDER_MARK.len());
+        GreenTrivia::new(SyntaxKind::ByteOrderMarkTrivia, &self.source[pos..self.position])
+    }
+
     /// Scans a PDF comment and returns a trivia element.
     ///
     /// Comments in PDF begin with `%` and extend to the end of the line.
@@ -403,6 +715,20 @@ impl<'source> Lexer<'source> {
     ///
     /// See: ISO 32000-2:2020, §7.2.4 Comments.
     fn scan_comment(&mut self) -> GreenTrivia {
+        let comment_bytes = self.scan_comment_bytes();
+        GreenTrivia::new(SyntaxKind::CommentTrivia, comment_bytes)
+    }
+
+    /// Scans a PDF comment as a [`SyntaxKind::CommentToken`], see [`LexerOptions::comments_as_tokens`].
+    ///
+    /// See: ISO 32000-2:2020, §7.2.4 Comments.
+    fn scan_comment_token(&mut self, token_info: &mut TokenInfo<'source>) {
+        token_info.kind = SyntaxKind::CommentToken;
+        token_info.bytes = self.scan_comment_bytes();
+    }
+
+    /// Consumes a comment from its `%` up to, not including, the next EOL and returns its bytes.
+    fn scan_comment_bytes(&mut self) -> &'source [u8] {
         let pos = self.position;
         self.advance(); // consume the '%'
 
@@ -415,8 +741,7 @@ impl<'source> Lexer<'source> {
             }
         }
 
-        let comment_bytes = &self.source[pos..self.position];
-        GreenTrivia::new(SyntaxKind::CommentTrivia, comment_bytes)
+        &self.source[pos..self.position]
     }
 
     /// Scans a numeric literal (integer or real number) and populates token_info.
@@ -468,13 +793,22 @@ impl<'source> Lexer<'source> {
 
         token_info.bytes = self.get_lexeme_bytes();
 
+        if self.options.mode == LexerMode::Strict
+            && token_info.kind == SyntaxKind::NumericLiteralToken
+            && !seen_dot
+            && is_integer_out_of_range(token_info.bytes)
+        {
+            let kind = DiagnosticKind::IntegerOverflow;
+            token_info.diagnostics.push((DiagnosticSeverity::Warning, kind, None));
+        }
+
         // Check if this numeric is immediately followed by a letter.
         // SafeDocs PDF Compacted Syntax Matrix: Integer → Boolean/Name/Null requires whitespace.
         // Emit diagnostic if letter follows without whitespace.
-        if matches!(self.peek(), Some(b'a'..=b'z' | b'A'..=b'Z')) {
+        if self.options.mode == LexerMode::Strict && matches!(self.peek(), Some(b'a'..=b'z' | b'A'..=b'Z')) {
             // TODO: move in parser phase
             let kind = DiagnosticKind::MissingWhitespaceBeforeToken;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
     }
 
@@ -538,8 +872,10 @@ impl<'source> Lexer<'source> {
                 }
                 b'\\' if self.peek_by(1).is_some() => {
                     // Unknown escape: emit warning, consume backslash only; next char handled normally
-                    let kind = DiagnosticKind::InvalidEscapeInStringLiteral;
-                    token_info.diagnostics.push((DiagnosticSeverity::Warning, kind, kind.as_str()));
+                    if self.options.mode == LexerMode::Strict {
+                        let kind = DiagnosticKind::InvalidEscapeInStringLiteral;
+                        token_info.diagnostics.push((DiagnosticSeverity::Warning, kind, None));
+                    }
                     self.advance();
                 }
                 b'\\' if self.peek_by(1).is_none() => {
@@ -569,7 +905,7 @@ impl<'source> Lexer<'source> {
         // If nesting is not zero, the string is unbalanced
         if nesting != 0 {
             let kind = DiagnosticKind::UnbalancedStringLiteral;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
     }
 
@@ -583,16 +919,22 @@ impl<'source> Lexer<'source> {
     /// - `kind`: [`SyntaxKind::HexStringLiteralToken`]
     /// - `bytes`: the complete scanned byte sequence including angle brackets
     ///
+    /// Invalid characters yield one [`DiagnosticKind::InvalidCharacterInHexString`] error whose
+    /// [`crate::GreenDiagnosticData::range`] covers the first of them.
+    ///
     /// See: ISO 32000-2:2020, §7.3.4.3 Hexadecimal strings.
     fn scan_hex_string(&mut self, token_info: &mut TokenInfo<'source>) {
         token_info.kind = SyntaxKind::HexStringLiteralToken;
+        let start = self.position;
         self.advance(); // consume the opening '<'
-        let mut has_invalid_character = false;
+        let mut first_invalid_offset = None;
+        let mut digit_count = 0usize;
         let mut closed = false;
 
         while let Some(byte) = self.peek() {
             match byte {
                 b if is_hexcode(b) => {
+                    digit_count += 1;
                     self.advance(); // consume hex digit
                 }
                 _ if is_whitespace(byte, true) => {
@@ -605,8 +947,8 @@ impl<'source> Lexer<'source> {
                     break;
                 }
                 _ => {
-                    // Invalid character in hex string: mark and consume
-                    has_invalid_character = true;
+                    // Invalid character in hex string: remember the first one and consume
+                    first_invalid_offset.get_or_insert(self.position - start);
                     self.advance();
                 }
             }
@@ -615,14 +957,21 @@ impl<'source> Lexer<'source> {
         token_info.bytes = self.get_lexeme_bytes();
 
         // Emit diagnostics after scanning
-        if has_invalid_character {
+        if let Some(offset) = first_invalid_offset {
             let kind = DiagnosticKind::InvalidCharacterInHexString;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            let offset = offset as u32;
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, Some(offset..offset + 1)));
+        }
+
+        // An odd digit count is valid (the final digit is assumed to be followed by 0); only reported in strict mode.
+        if digit_count % 2 == 1 && self.options.mode == LexerMode::Strict {
+            let kind = DiagnosticKind::OddLengthHexString;
+            token_info.diagnostics.push((DiagnosticSeverity::Warning, kind, None));
         }
 
         if !closed {
             let kind = DiagnosticKind::UnbalancedHexString;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
     }
 
@@ -672,12 +1021,12 @@ impl<'source> Lexer<'source> {
 
         if has_invalid_hex_escape {
             let kind = DiagnosticKind::InvalidHexEscapeInName;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
 
         if has_non_regular_character {
             let kind = DiagnosticKind::InvalidNonRegularCharacterInName;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
     }
 
@@ -708,27 +1057,45 @@ impl<'source> Lexer<'source> {
             }
         }
 
+        if self.options.content_stream_operators {
+            // Operators such as `b*`, `T*`, `W*`, `d0` and `d1` end with a non-letter.
+            match (self.get_lexeme_bytes(), self.peek()) {
+                (_, Some(b'*')) | (b"d", Some(b'0' | b'1')) => {
+                    self.advance();
+                }
+                _ => {}
+            }
+        }
+
         let keyword_bytes = self.get_lexeme_bytes();
+        let operator = match self.options.content_stream_operators {
+            true => content_stream_operator_kind(keyword_bytes),
+            false => None,
+        };
 
         // Match against known keywords
-        token_info.kind = match keyword_bytes {
-            b"true" => SyntaxKind::TrueKeyword,
-            b"false" => SyntaxKind::FalseKeyword,
-            b"null" => SyntaxKind::NullKeyword,
-            b"obj" => SyntaxKind::IndirectObjectKeyword,
-            b"endobj" => SyntaxKind::IndirectEndObjectKeyword,
-            b"R" => SyntaxKind::IndirectReferenceKeyword,
-            b"stream" => {
-                self.is_raw_stream = true; // enter raw stream mode
-                SyntaxKind::StreamKeyword
-            }
-            b"endstream" => SyntaxKind::EndStreamKeyword,
-            b"xref" => SyntaxKind::XRefKeyword,
-            b"f" => SyntaxKind::XRefFreeEntryKeyword,
-            b"n" => SyntaxKind::XRefInUseEntryKeyword,
-            b"trailer" => SyntaxKind::FileTrailerKeyword,
-            b"startxref" => SyntaxKind::StartXRefKeyword,
-            _ => SyntaxKind::BadToken,
+        token_info.kind = if let Some(kind) = operator {
+            kind
+        } else {
+            match keyword_bytes {
+                b"true" => SyntaxKind::TrueKeyword,
+                b"false" => SyntaxKind::FalseKeyword,
+                b"null" => SyntaxKind::NullKeyword,
+                b"obj" => SyntaxKind::IndirectObjectKeyword,
+                b"endobj" => SyntaxKind::IndirectEndObjectKeyword,
+                b"R" => SyntaxKind::IndirectReferenceKeyword,
+                b"stream" => {
+                    self.is_raw_stream = self.options.scan_stream_data; // enter raw stream mode
+                    SyntaxKind::StreamKeyword
+                }
+                b"endstream" => SyntaxKind::EndStreamKeyword,
+                b"xref" => SyntaxKind::XRefKeyword,
+                b"f" => SyntaxKind::XRefFreeEntryKeyword,
+                b"n" => SyntaxKind::XRefInUseEntryKeyword,
+                b"trailer" => SyntaxKind::FileTrailerKeyword,
+                b"startxref" => SyntaxKind::StartXRefKeyword,
+                _ => SyntaxKind::BadToken,
+            }
         };
 
         token_info.bytes = keyword_bytes;
@@ -736,10 +1103,10 @@ impl<'source> Lexer<'source> {
         // Check if this is a keyword immediately followed by a digit, dot, or sign.
         // SafeDocs PDF Compacted Syntax Matrix: Boolean → Integer/Real requires whitespace.
         // Emit diagnostic if numeric start follows without whitespace.
-        if token_info.kind != SyntaxKind::BadToken && matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'+' | b'-')) {
+        if self.options.mode == LexerMode::Strict && token_info.kind != SyntaxKind::BadToken && matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'+' | b'-')) {
             // TODO: move in parser phase
             let kind = DiagnosticKind::MissingWhitespaceBeforeToken;
-            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, kind.as_str()));
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
         }
     }
 
@@ -798,24 +1165,207 @@ impl<'source> Lexer<'source> {
         self.is_raw_stream = false; // exit raw stream mode after scanning
     }
 
+    /// Scans the object number and offset pairs at the start of an object stream as a single
+    /// [`SyntaxKind::ObjStmHeaderToken`], including the whitespace between the integers.
+    ///
+    /// Scanning stops early at anything other than an unsigned integer, so a truncated table
+    /// yields a shorter header and the rest is lexed as usual.
+    ///
+    /// See: ISO 32000-2:2020, §7.5.7 Object streams.
+    fn scan_objstm_header(&mut self, token_info: &mut TokenInfo<'source>, pairs: u32) {
+        let mut integers = 2 * u64::from(pairs);
+
+        loop {
+            while matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.advance();
+            }
+            integers -= 1;
+
+            // Whitespace is part of the header only between integers; after the last one it is trivia.
+            let mut gap = 0;
+            while self.peek_by(gap).is_some_and(|byte| is_whitespace(byte, true)) {
+                gap += 1;
+            }
+            if integers == 0 || gap == 0 || !matches!(self.peek_by(gap), Some(b'0'..=b'9')) {
+                break;
+            }
+            self.advance_by(gap);
+        }
+
+        token_info.kind = SyntaxKind::ObjStmHeaderToken;
+        token_info.bytes = self.get_lexeme_bytes();
+    }
+
+    /// Scans one cross-reference table entry as a [`SyntaxKind::XRefEntryToken`], see [`XRefEntry`].
+    ///
+    /// Entries are scanned after `xref` and a subsection header `start count`, `count` times. An entry
+    /// runs to the end of its line, trailing whitespace excluded, so a malformed one is still a single
+    /// token; an error is reported unless its text is `nnnnnnnnnn ggggg n` or `nnnnnnnnnn ggggg f`. In
+    /// strict mode, a warning is also reported unless the entry ends with exactly a two-byte
+    /// end-of-line marker (` \r`, ` \n` or `\r\n`), making it 20 bytes long.
+    ///
+    /// See: ISO 32000-2:2020, §7.5.4 Cross-reference table.
+    fn scan_xref_entry(&mut self, token_info: &mut TokenInfo<'source>) {
+        let mut line_length = 0;
+        while self.peek_by(line_length).is_some_and(|byte| byte != b'\r' && byte != b'\n') {
+            line_length += 1;
+        }
+        while line_length > 0 && self.peek_by(line_length - 1).is_some_and(|byte| is_whitespace(byte, false)) {
+            line_length -= 1;
+        }
+        self.advance_by(line_length);
+
+        token_info.kind = SyntaxKind::XRefEntryToken;
+        token_info.bytes = self.get_lexeme_bytes();
+
+        if XRefEntry::parse(token_info.bytes).is_none() {
+            let kind = DiagnosticKind::MalformedXRefEntry;
+            token_info.diagnostics.push((DiagnosticSeverity::Error, kind, None));
+            return;
+        }
+
+        let has_two_byte_eol = match (self.peek(), self.peek_by(1)) {
+            (Some(b' '), Some(b'\r')) => self.peek_by(2) != Some(b'\n'),
+            (Some(b' '), Some(b'\n')) | (Some(b'\r'), Some(b'\n')) => true,
+            _ => false,
+        };
+        if !has_two_byte_eol && self.options.mode == LexerMode::Strict {
+            let kind = DiagnosticKind::MalformedXRefEntry;
+            token_info.diagnostics.push((DiagnosticSeverity::Warning, kind, None));
+        }
+    }
+
+    /// Returns the cross-reference table state after the token just scanned.
+    fn next_xref_state(&self, token_info: &TokenInfo<'source>) -> XRefState {
+        match (self.xref_state, token_info.kind) {
+            (_, SyntaxKind::XRefKeyword) => XRefState::SubsectionStart,
+            (XRefState::SubsectionStart, SyntaxKind::NumericLiteralToken) => XRefState::SubsectionCount,
+            (XRefState::SubsectionCount, SyntaxKind::NumericLiteralToken) => {
+                let count = std::str::from_utf8(token_info.bytes).ok().and_then(|count| count.parse::<u32>().ok());
+                match count {
+                    Some(0) => XRefState::SubsectionStart,
+                    Some(count) => XRefState::Entries(count),
+                    None => XRefState::None,
+                }
+            }
+            (XRefState::Entries(1), SyntaxKind::XRefEntryToken) => XRefState::SubsectionStart,
+            (XRefState::Entries(left), SyntaxKind::XRefEntryToken) => XRefState::Entries(left - 1),
+            (state, SyntaxKind::CommentToken) => state,
+            _ => XRefState::None,
+        }
+    }
+
     /// Scans unknown/unsupported characters as a [`SyntaxKind::BadToken`].
     ///
     /// Consumes characters greedily until a delimiter, whitespace, or EOF is encountered.
     /// This ensures that sequences like `@#$` are captured as a single bad token for better
-    /// error reporting and recovery.
+    /// error reporting and recovery. With [`LexerOptions::coalesce_bad_tokens`], stray delimiters
+    /// that cannot start a valid token are swallowed too, so the run only ends where a
+    /// recognizable token (or trivia) begins.
     fn scan_bad_token(&mut self, token_info: &mut TokenInfo<'source>) {
         token_info.kind = SyntaxKind::BadToken;
         self.advance(); // consume the first bad character
 
         while let Some(byte) = self.peek() {
             // Stop at whitespace or delimiters
-            if is_whitespace(byte, true) || is_delimiter(byte, false) {
+            if is_whitespace(byte, true) || (is_delimiter(byte, false) && !(self.options.coalesce_bad_tokens && self.is_stray_delimiter())) {
                 break;
             }
             self.advance(); // consume the bad character
         }
         token_info.bytes = self.get_lexeme_bytes();
     }
+
+    /// Checks if the current byte is a delimiter that would only lex as another bad token:
+    /// an unmatched `)` or a single `>` not forming `>>`.
+    fn is_stray_delimiter(&self) -> bool {
+        match self.peek() {
+            Some(b')') => true,
+            Some(b'>') => self.peek_by(1) != Some(b'>'),
+            _ => false,
+        }
+    }
+}
+
+/// Maps a content stream operator to its kind.
+///
+/// See: ISO 32000-2:2020, Annex A.2 Table A.1 PDF content stream operators.
+fn content_stream_operator_kind(bytes: &[u8]) -> Option<SyntaxKind> {
+    let kind = match bytes {
+        b"b" => SyntaxKind::CloseFillStrokePathOperator,
+        b"B" => SyntaxKind::FillStrokePathOperator,
+        b"b*" => SyntaxKind::CloseFillStrokePathEvenOddOperator,
+        b"B*" => SyntaxKind::FillStrokePathEvenOddOperator,
+        b"BDC" => SyntaxKind::BeginMarkedContentPropertyOperator,
+        b"BI" => SyntaxKind::BeginInlineImageOperator,
+        b"BMC" => SyntaxKind::BeginMarkedContentOperator,
+        b"BT" => SyntaxKind::BeginTextOperator,
+        b"BX" => SyntaxKind::BeginCompatibilityOperator,
+        b"c" => SyntaxKind::CurveToOperator,
+        b"cm" => SyntaxKind::ConcatMatrixOperator,
+        b"CS" => SyntaxKind::SetStrokeColorSpaceOperator,
+        b"cs" => SyntaxKind::SetNonStrokeColorSpaceOperator,
+        b"d" => SyntaxKind::SetDashPatternOperator,
+        b"d0" => SyntaxKind::SetCharWidthOperator,
+        b"d1" => SyntaxKind::SetCacheDeviceOperator,
+        b"Do" => SyntaxKind::InvokeXObjectOperator,
+        b"DP" => SyntaxKind::DefineMarkedContentPropertyOperator,
+        b"EI" => SyntaxKind::EndInlineImageOperator,
+        b"EMC" => SyntaxKind::EndMarkedContentOperator,
+        b"ET" => SyntaxKind::EndTextOperator,
+        b"EX" => SyntaxKind::EndCompatibilityOperator,
+        b"f" => SyntaxKind::FillPathOperator,
+        b"F" => SyntaxKind::FillPathDeprecatedOperator,
+        b"f*" => SyntaxKind::FillPathEvenOddOperator,
+        b"G" => SyntaxKind::SetStrokeGrayOperator,
+        b"g" => SyntaxKind::SetNonStrokeGrayOperator,
+        b"gs" => SyntaxKind::SetGraphicsStateParametersOperator,
+        b"h" => SyntaxKind::CloseSubpathOperator,
+        b"i" => SyntaxKind::SetFlatnessToleranceOperator,
+        b"ID" => SyntaxKind::BeginInlineImageDataOperator,
+        b"j" => SyntaxKind::SetLineJoinOperator,
+        b"J" => SyntaxKind::SetLineCapOperator,
+        b"K" => SyntaxKind::SetStrokeCMYKColorOperator,
+        b"k" => SyntaxKind::SetNonStrokeCMYKColorOperator,
+        b"l" => SyntaxKind::LineToOperator,
+        b"m" => SyntaxKind::MoveToOperator,
+        b"M" => SyntaxKind::SetMiterLimitOperator,
+        b"MP" => SyntaxKind::DefineMarkedContentPointOperator,
+        b"n" => SyntaxKind::EndPathOperator,
+        b"q" => SyntaxKind::SaveGraphicsStateOperator,
+        b"Q" => SyntaxKind::RestoreGraphicsStateOperator,
+        b"re" => SyntaxKind::RectangleOperator,
+        b"RG" => SyntaxKind::SetStrokeRGBColorOperator,
+        b"rg" => SyntaxKind::SetNonStrokeRGBColorOperator,
+        b"ri" => SyntaxKind::SetRenderingIntentOperator,
+        b"s" => SyntaxKind::CloseStrokePathOperator,
+        b"S" => SyntaxKind::StrokePathOperator,
+        b"SC" => SyntaxKind::SetStrokeColorOperator,
+        b"sc" => SyntaxKind::SetNonStrokeColorOperator,
+        b"SCN" => SyntaxKind::SetStrokeColorICCSpecialOperator,
+        b"scn" => SyntaxKind::SetNonStrokeColorICCSpecialOperator,
+        b"sh" => SyntaxKind::ShadeFillOperator,
+        b"T*" => SyntaxKind::TextNextLineOperator,
+        b"Tc" => SyntaxKind::SetCharSpacingOperator,
+        b"Td" => SyntaxKind::MoveTextPositionOperator,
+        b"TD" => SyntaxKind::MoveTextSetLeadingOperator,
+        b"Tf" => SyntaxKind::SetTextFontOperator,
+        b"Tj" => SyntaxKind::ShowTextOperator,
+        b"TJ" => SyntaxKind::ShowTextAdjustedOperator,
+        b"TL" => SyntaxKind::SetTextLeadingOperator,
+        b"Tm" => SyntaxKind::SetTextMatrixOperator,
+        b"Tr" => SyntaxKind::SetTextRenderingModeOperator,
+        b"Ts" => SyntaxKind::SetTextRiseOperator,
+        b"Tw" => SyntaxKind::SetWordSpacingOperator,
+        b"Tz" => SyntaxKind::SetHorizontalScalingOperator,
+        b"v" => SyntaxKind::CurveToInitialReplicatedOperator,
+        b"w" => SyntaxKind::SetLineWidthOperator,
+        b"W" => SyntaxKind::ClipOperator,
+        b"W*" => SyntaxKind::EvenOddClipOperator,
+        b"y" => SyntaxKind::CurveToFinalReplicatedOperator,
+        _ => return None,
+    };
+    Some(kind)
 }
 
 /// Check if a byte is a white-space character.
@@ -837,6 +1387,23 @@ fn is_whitespace(byte: u8, include_eol: bool) -> bool {
     }
 }
 
+/// Returns true when an integer, with an optional sign, lies outside `-2^31..=2^31 - 1`, the range
+/// readers must support (ISO 32000-2:2020 Annex C.2, Table C.1).
+fn is_integer_out_of_range(bytes: &[u8]) -> bool {
+    let (negative, digits) = match bytes.split_first() {
+        Some((b'-', digits)) => (true, digits),
+        Some((b'+', digits)) => (false, digits),
+        _ => (false, bytes),
+    };
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return false; // a real, e.g. `.5`
+    }
+
+    let digits = &digits[digits.iter().take_while(|&&digit| digit == b'0').count()..];
+    let limit = if negative { 1u64 << 31 } else { (1u64 << 31) - 1 };
+    digits.len() > 10 || digits.iter().fold(0u64, |value, &digit| value * 10 + u64::from(digit - b'0')) > limit
+}
+
 /// Returns true when the byte is a hexadecimal digit (`0-9`, `A-F`, `a-f`).
 #[inline]
 fn is_hexcode(byte: u8) -> bool {
@@ -0,0 +1,55 @@
+use super::is_whitespace;
+use crate::{GreenTokenElement, Lexer};
+
+impl<'source> Lexer<'source> {
+    /// Returns the last token whose text ends at or before `offset`, with the offset where its text
+    /// starts, without lexing `source` from the beginning. Returns `None` if no token ends there.
+    ///
+    /// # Heuristic
+    ///
+    /// PDF cannot be lexed backward, so this looks for a point to lex forward from: it steps back
+    /// over whitespace and then over the non-whitespace bytes before `offset`, roughly the token
+    /// wanted, and restarts at the beginning of that line, so a comment on the line is seen from
+    /// its `%`. If no token ends at or before `offset` from there, it restarts one line earlier,
+    /// until the start of the source.
+    ///
+    /// The token kind and text then match lexing from the start as long as the restart line does
+    /// not begin inside a literal string, stream data or a cross-reference table spanning several
+    /// lines; there, the bytes are lexed as ordinary tokens instead. Trivia may also be split
+    /// differently, since whitespace that starts the restart line becomes leading trivia.
+    pub(crate) fn token_ending_at(source: &'source [u8], offset: usize) -> Option<(usize, GreenTokenElement)> {
+        let offset = offset.min(source.len());
+        let mut start = offset;
+        while start > 0 && is_whitespace(source[start - 1], true) {
+            start -= 1;
+        }
+        while start > 0 && !is_whitespace(source[start - 1], true) {
+            start -= 1;
+        }
+
+        let mut restart = line_start(source, start);
+        loop {
+            let mut lexer = Lexer::new(source);
+            lexer.seek(restart);
+            let found = lexer
+                .token_offsets()
+                .map(|(position, token)| (position + token.leading_trivia_width() as usize, token))
+                .take_while(|&(text_start, _)| text_start < offset)
+                .filter(|(text_start, token)| text_start + token.width() as usize <= offset)
+                .last();
+            if found.is_some() || restart == 0 {
+                return found;
+            }
+
+            restart = line_start(source, restart - 1);
+        }
+    }
+}
+
+/// Returns the offset just after the last end-of-line marker before `position`, or 0.
+fn line_start(source: &[u8], position: usize) -> usize {
+    source[..position]
+        .iter()
+        .rposition(|&byte| byte == b'\r' || byte == b'\n')
+        .map_or(0, |eol| eol + 1)
+}
@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use crate::{Lexer, SyntaxKind};
+
+/// How a region of tokens differs between two inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenChangeKind {
+    /// Tokens only present in the new input.
+    Added,
+    /// Tokens only present in the old input.
+    Removed,
+    /// Tokens replaced by different tokens.
+    Changed,
+}
+
+/// A run of differing tokens, with byte ranges into both inputs.
+///
+/// For [`TokenChangeKind::Added`] the old range is empty and marks the insertion point,
+/// and likewise the new range for [`TokenChangeKind::Removed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TokenDiff {
+    pub(crate) kind: TokenChangeKind,
+    pub(crate) old_range: Range<u32>,
+    pub(crate) new_range: Range<u32>,
+}
+
+/// Bound on the edit distance searched for one middle snake before giving up on a region.
+const MAX_SNAKE_SEARCH: isize = 1 << 12;
+
+struct DiffToken {
+    kind: SyntaxKind,
+    bytes: Vec<u8>,
+    span: Range<u32>,
+}
+
+/// Tokenizes both inputs and reports the token runs that differ between them.
+///
+/// Tokens are matched by kind and text, so whitespace and comment changes are ignored unless
+/// `include_trivia` is set, in which case trivia is compared and included in the reported ranges.
+///
+/// This is the lexer-side core intended to back a structural diff for web tooling; the
+/// tokens between the common prefix and suffix are matched with Myers' linear-space algorithm,
+/// which takes O((n + m) · d) time and O(n + m) memory for `d` differing tokens. A region whose
+/// halves are more than [`MAX_SNAKE_SEARCH`] edits apart is reported as a single change rather than
+/// matched, so heavily rewritten inputs stay cheap at the cost of a coarser diff.
+pub(crate) fn diff_tokens(old: &[u8], new: &[u8], include_trivia: bool) -> Vec<TokenDiff> {
+    let old_tokens = lex_for_diff(old, include_trivia);
+    let new_tokens = lex_for_diff(new, include_trivia);
+
+    let prefix = old_tokens.iter().zip(&new_tokens).take_while(|(a, b)| is_same(a, b)).count();
+    let suffix = old_tokens[prefix..]
+        .iter()
+        .rev()
+        .zip(new_tokens[prefix..].iter().rev())
+        .take_while(|(a, b)| is_same(a, b))
+        .count();
+    let old_middle = &old_tokens[prefix..old_tokens.len() - suffix];
+    let new_middle = &new_tokens[prefix..new_tokens.len() - suffix];
+
+    let mut old_matched = vec![false; old_middle.len()];
+    let mut new_matched = vec![false; new_middle.len()];
+    match_common(old_middle, new_middle, &mut old_matched, &mut new_matched);
+
+    let old_end = old_tokens.get(old_tokens.len() - suffix).map_or(old.len() as u32, |token| token.span.start);
+    let new_end = new_tokens.get(new_tokens.len() - suffix).map_or(new.len() as u32, |token| token.span.start);
+    let position_at = |tokens: &[DiffToken], index: usize, end: u32| tokens.get(index).map_or(end, |token| token.span.start);
+
+    let mut diffs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() || j < new_middle.len() {
+        // Matched tokens pair up in order, so two matched tokens at the cursors match each other.
+        if old_matched.get(i) == Some(&true) && new_matched.get(j) == Some(&true) {
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let (old_start, new_start) = (i, j);
+        while i < old_middle.len() && !old_matched[i] {
+            i += 1;
+        }
+        while j < new_middle.len() && !new_matched[j] {
+            j += 1;
+        }
+
+        let old_range = match old_start < i {
+            true => old_middle[old_start].span.start..old_middle[i - 1].span.end,
+            false => {
+                let position = position_at(old_middle, i, old_end);
+                position..position
+            }
+        };
+        let new_range = match new_start < j {
+            true => new_middle[new_start].span.start..new_middle[j - 1].span.end,
+            false => {
+                let position = position_at(new_middle, j, new_end);
+                position..position
+            }
+        };
+        let kind = match (old_start < i, new_start < j) {
+            (false, _) => TokenChangeKind::Added,
+            (_, false) => TokenChangeKind::Removed,
+            _ => TokenChangeKind::Changed,
+        };
+        diffs.push(TokenDiff { kind, old_range, new_range });
+    }
+
+    diffs
+}
+
+#[inline]
+fn is_same(a: &DiffToken, b: &DiffToken) -> bool {
+    a.kind == b.kind && a.bytes == b.bytes
+}
+
+/// Marks the tokens of a longest common subsequence of `old` and `new`.
+///
+/// Splits both sides at the middle snake of a shortest edit script and recurses on the halves,
+/// see Myers, "An O(ND) Difference Algorithm and Its Variations" (1986), §4b.
+fn match_common(mut old: &[DiffToken], mut new: &[DiffToken], mut old_matched: &mut [bool], mut new_matched: &mut [bool]) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| is_same(a, b)).count();
+    old_matched[..prefix].fill(true);
+    new_matched[..prefix].fill(true);
+    (old, new) = (&old[prefix..], &new[prefix..]);
+    (old_matched, new_matched) = (&mut old_matched[prefix..], &mut new_matched[prefix..]);
+
+    let suffix = old.iter().rev().zip(new.iter().rev()).take_while(|(a, b)| is_same(a, b)).count();
+    let (old_len, new_len) = (old.len() - suffix, new.len() - suffix);
+    old_matched[old_len..].fill(true);
+    new_matched[new_len..].fill(true);
+    (old, new) = (&old[..old_len], &new[..new_len]);
+    (old_matched, new_matched) = (&mut old_matched[..old_len], &mut new_matched[..new_len]);
+
+    if old.is_empty() || new.is_empty() {
+        return;
+    }
+
+    let Some((x_start, y_start, x_end, y_end)) = middle_snake(old, new) else {
+        return; // too far apart, left unmatched as one change
+    };
+    old_matched[x_start..x_end].fill(true);
+    new_matched[y_start..y_end].fill(true);
+    let (old_before, old_after) = old_matched.split_at_mut(x_end);
+    let (new_before, new_after) = new_matched.split_at_mut(y_end);
+    match_common(&old[..x_start], &new[..y_start], &mut old_before[..x_start], &mut new_before[..y_start]);
+    match_common(&old[x_end..], &new[y_end..], old_after, new_after);
+}
+
+/// Returns the start and end `(x, y)` of the snake in the middle of a shortest edit script
+/// turning `old` into `new`, found by running the greedy search from both ends until they overlap,
+/// or `None` if they do not within [`MAX_SNAKE_SEARCH`] edits each.
+///
+/// Both inputs must be non-empty and differ in their first and last tokens, so the script
+/// has at least two edits and both halves around the snake are strictly smaller.
+fn middle_snake(old: &[DiffToken], new: &[DiffToken]) -> Option<(usize, usize, usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let delta = n - m;
+    let is_odd = delta % 2 != 0;
+    let max = ((n + m + 1) / 2).min(MAX_SNAKE_SEARCH);
+    let offset = max + 1;
+    // forward[k] is the furthest x on diagonal k = x - y from the start, backward[k] the furthest
+    // x from the end on diagonal k of the reversed inputs, which is diagonal delta - k forward.
+    let mut forward = vec![0isize; (2 * offset + 1) as usize];
+    let mut backward = vec![0isize; (2 * offset + 1) as usize];
+    let at = |k: isize| (k + offset) as usize;
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = match k == -d || (k != d && forward[at(k - 1)] < forward[at(k + 1)]) {
+                true => forward[at(k + 1)],
+                false => forward[at(k - 1)] + 1,
+            };
+            let (x_start, y_start) = (x, x - k);
+            while x < n && x - k < m && is_same(&old[x as usize], &new[(x - k) as usize]) {
+                x += 1;
+            }
+            forward[at(k)] = x;
+            if is_odd && (delta - k).abs() < d && x + backward[at(delta - k)] >= n {
+                return Some((x_start as usize, y_start as usize, x as usize, (x - k) as usize));
+            }
+        }
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = match k == -d || (k != d && backward[at(k - 1)] < backward[at(k + 1)]) {
+                true => backward[at(k + 1)],
+                false => backward[at(k - 1)] + 1,
+            };
+            let (x_start, y_start) = (x, x - k);
+            while x < n && x - k < m && is_same(&old[(n - x - 1) as usize], &new[(m - x + k - 1) as usize]) {
+                x += 1;
+            }
+            backward[at(k)] = x;
+            if !is_odd && (delta - k).abs() <= d && x + forward[at(delta - k)] >= n {
+                return Some(((n - x) as usize, (m - x + k) as usize, (n - x_start) as usize, (m - y_start) as usize));
+            }
+        }
+    }
+
+    None
+}
+
+fn lex_for_diff(source: &[u8], include_trivia: bool) -> Vec<DiffToken> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    let mut position = 0u32;
+    loop {
+        let token = lexer.next_token();
+        let is_end = token.kind() == SyntaxKind::EndOfFileToken;
+        // The end-of-file token only matters for the trivia it carries.
+        if is_end && !(include_trivia && token.full_width() > 0) {
+            break tokens;
+        }
+
+        let (bytes, span) = match include_trivia {
+            true => (token.full_text(), position..position + token.full_width()),
+            false => {
+                let start = position + token.leading_trivia_width();
+                (token.text(), start..start + token.width())
+            }
+        };
+        position += token.full_width();
+
+        tokens.push(DiffToken {
+            kind: token.kind(),
+            bytes,
+            span,
+        });
+        if is_end {
+            break tokens;
+        }
+    }
+}
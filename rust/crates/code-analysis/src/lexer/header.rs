@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// The file header must lie entirely within the first 1024 bytes of the file.
+const HEADER_SEARCH_LIMIT: usize = 1024;
+
+/// At most this many bytes of the offending input are kept in a [`PdfHeaderError`].
+const PREFIX_LIMIT: usize = 32;
+
+/// A PDF version as declared by the `%PDF-x.y` file header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct PdfVersion {
+    pub(crate) major: u8,
+    pub(crate) minor: u8,
+}
+
+/// The input has no supported `%PDF-1.x` or `%PDF-2.0` header within its first 1024 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PdfHeaderError {
+    /// The first line of the invalid `%PDF-` header, or of the input if there is none, truncated to 32 bytes.
+    pub(crate) prefix: Vec<u8>,
+}
+
+impl fmt::Display for PdfHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected a %PDF-1.x or %PDF-2.0 header within the first {HEADER_SEARCH_LIMIT} bytes, found {:?}",
+            String::from_utf8_lossy(&self.prefix)
+        )
+    }
+}
+
+impl std::error::Error for PdfHeaderError {}
+
+/// Returns the version of the first `%PDF-` header in the first 1024 bytes of `source`.
+///
+/// Bytes before the header are permitted, as PDF readers accept them. Only versions 1.0 to 1.7
+/// and 2.0 are supported.
+///
+/// See: ISO 32000-2:2020, §7.5.2 File header.
+pub(super) fn find_pdf_version(source: &[u8]) -> Result<PdfVersion, PdfHeaderError> {
+    let leading = &source[..source.len().min(HEADER_SEARCH_LIMIT)];
+    let start = leading.windows(5).position(|window| window == b"%PDF-");
+
+    let version = start.and_then(|start| match &leading[start + 5..] {
+        [major @ b'0'..=b'9', b'.', minor @ b'0'..=b'9', rest @ ..] if !matches!(rest.first(), Some(b'0'..=b'9' | b'.')) => Some(PdfVersion {
+            major: major - b'0',
+            minor: minor - b'0',
+        }),
+        _ => None,
+    });
+
+    match version {
+        Some(version @ PdfVersion { major: 1, minor: 0..=7 } | version @ PdfVersion { major: 2, minor: 0 }) => Ok(version),
+        _ => {
+            let prefix = &source[start.unwrap_or(0)..];
+            let line_len = prefix.iter().position(|byte| matches!(byte, b'\r' | b'\n')).unwrap_or(prefix.len());
+            Err(PdfHeaderError {
+                prefix: prefix[..line_len.min(PREFIX_LIMIT)].to_vec(),
+            })
+        }
+    }
+}
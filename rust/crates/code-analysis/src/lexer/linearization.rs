@@ -0,0 +1,27 @@
+use crate::{Lexer, SyntaxKind};
+
+/// The linearization parameter dictionary must lie entirely within the first 1024 bytes of the file.
+const LINEARIZATION_DICTIONARY_LIMIT: usize = 1024;
+
+/// Returns `true` if the first indirect object of `source` is a linearization parameter dictionary,
+/// i.e. a dictionary containing the `/Linearized` key.
+///
+/// Only the first 1024 bytes are tokenized, so this is cheap enough to decide on a reading strategy
+/// before loading the rest of the file. It does not validate the hint tables or file length.
+///
+/// See: ISO 32000-2:2020, Annex F.2 Linearized PDF document structure.
+pub(crate) fn is_linearized(source: &[u8]) -> bool {
+    let leading = &source[..source.len().min(LINEARIZATION_DICTIONARY_LIMIT)];
+    let mut lexer = Lexer::new(leading);
+    let mut is_in_first_object = false;
+
+    loop {
+        let token = lexer.next_token();
+        match token.kind() {
+            SyntaxKind::EndOfFileToken | SyntaxKind::IndirectEndObjectKeyword | SyntaxKind::StreamKeyword => return false,
+            SyntaxKind::IndirectObjectKeyword => is_in_first_object = true,
+            SyntaxKind::NameLiteralToken if is_in_first_object && token.text() == b"/Linearized" => return true,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+use crate::{GreenTokenElement, Lexer, SyntaxKind};
+
+/// Splits a PDF body into the byte ranges of its indirect objects, for independent parsing.
+///
+/// Returns `(object number, generation number, range)` for each `N G obj … endobj`, where the
+/// range spans from the object number to the end of `endobj`, trivia excluded. Stream data is
+/// lexed as a single raw token, so `obj`/`endobj` bytes inside a stream do not split it. An
+/// object missing its `endobj` ends at its last token before the next object header or the end
+/// of the input.
+///
+/// See: ISO 32000-2:2020, §7.3.10 Indirect objects.
+pub(crate) fn split_objects(source: &[u8]) -> Vec<(u32, u32, Range<u32>)> {
+    let mut lexer = Lexer::new(source);
+    let mut objects = Vec::new();
+    let mut open: Option<(u32, u32, u32)> = None;
+    // The last two tokens as (value, start) when they are unsigned integers, and the ends of the last three tokens.
+    let mut previous: [Option<(u32, u32)>; 2] = [None, None];
+    let mut ends = [0u32; 3];
+    let mut position = 0u32;
+
+    loop {
+        let token = lexer.next_token();
+        let start = position + token.leading_trivia_width();
+        let end = start + token.width();
+        position += token.full_width();
+
+        match token.kind() {
+            SyntaxKind::EndOfFileToken => break,
+            SyntaxKind::IndirectObjectKeyword => {
+                if let [Some((number, number_start)), Some((generation, _))] = previous {
+                    if let Some((open_number, open_generation, open_start)) = open.take() {
+                        objects.push((open_number, open_generation, open_start..ends[0]));
+                    }
+                    open = Some((number, generation, number_start));
+                }
+            }
+            SyntaxKind::IndirectEndObjectKeyword => {
+                if let Some((number, generation, object_start)) = open.take() {
+                    objects.push((number, generation, object_start..end));
+                }
+            }
+            _ => {}
+        }
+
+        previous = [previous[1], unsigned_integer_value(&token).map(|value| (value, start))];
+        ends = [ends[1], ends[2], end];
+    }
+
+    if let Some((number, generation, object_start)) = open {
+        objects.push((number, generation, object_start..ends[2]));
+    }
+    objects
+}
+
+/// Returns the numbers of the objects whose top-level dictionary declares `/Type /XRef`,
+/// i.e. cross-reference streams, in order of appearance.
+///
+/// Detection only: the stream data still has to be decoded elsewhere to read the entries.
+///
+/// See: ISO 32000-2:2020, §7.5.8 Cross-reference streams.
+pub(crate) fn find_xref_streams(tokens: &[GreenTokenElement]) -> Vec<u32> {
+    let mut xref_streams = Vec::new();
+    let mut object_number: Option<u32> = None;
+    let mut previous: [Option<u32>; 2] = [None, None];
+    let mut dictionary_depth = 0usize;
+    let mut is_type_key = false;
+
+    for token in tokens {
+        match token.kind() {
+            SyntaxKind::IndirectObjectKeyword => {
+                object_number = previous[0].filter(|_| previous[1].is_some());
+                dictionary_depth = 0;
+            }
+            SyntaxKind::IndirectEndObjectKeyword => object_number = None,
+            SyntaxKind::OpenDictToken => dictionary_depth += 1,
+            SyntaxKind::CloseDictToken => dictionary_depth = dictionary_depth.saturating_sub(1),
+            SyntaxKind::NameLiteralToken if is_type_key && dictionary_depth == 1 && token.text() == b"/XRef" => {
+                if let Some(number) = object_number.take() {
+                    xref_streams.push(number);
+                }
+            }
+            _ => {}
+        }
+
+        is_type_key = dictionary_depth == 1 && token.kind() == SyntaxKind::NameLiteralToken && token.text() == b"/Type";
+        previous = [previous[1], unsigned_integer_value(token)];
+    }
+
+    xref_streams
+}
+
+/// Returns the `/N` entry of an object stream's dictionary, i.e. how many compressed objects it holds.
+///
+/// `tokens` are the tokens of a single object; only its first dictionary's top level is inspected. Returns
+/// `None` unless that dictionary declares `/Type /ObjStm` and an unsigned integer `/N`, in either
+/// order. Detection only: the packed objects still have to be decompressed elsewhere.
+///
+/// See: ISO 32000-2:2020, §7.5.7 Object streams.
+pub(crate) fn objstm_object_count(tokens: &[GreenTokenElement]) -> Option<u32> {
+    let mut dictionary_depth = 0usize;
+    let mut array_depth = 0usize;
+    let mut key: Option<Vec<u8>> = None;
+    let mut is_object_stream = false;
+    let mut count = None;
+
+    for token in tokens {
+        let value_key = key.take();
+        // Keys and values inside an array value, e.g. `/Filter [/N ...]`, are not entries of the dictionary.
+        let is_top_level = dictionary_depth == 1 && array_depth == 0;
+        match token.kind() {
+            SyntaxKind::OpenDictToken => dictionary_depth += 1,
+            SyntaxKind::CloseDictToken if dictionary_depth == 1 => break,
+            SyntaxKind::CloseDictToken => dictionary_depth = dictionary_depth.saturating_sub(1),
+            SyntaxKind::OpenBracketToken => array_depth += 1,
+            SyntaxKind::CloseBracketToken => array_depth = array_depth.saturating_sub(1),
+            SyntaxKind::NameLiteralToken if is_top_level => match value_key.as_deref() {
+                Some(b"/Type") => is_object_stream = token.text() == b"/ObjStm",
+                Some(_) => {}
+                None => key = Some(token.text()),
+            },
+            SyntaxKind::NumericLiteralToken if is_top_level && value_key.as_deref() == Some(b"/N") => count = unsigned_integer_value(token),
+            _ => {}
+        }
+    }
+
+    count.filter(|_| is_object_stream)
+}
+
+/// The `/Length` entry of a stream dictionary, as classified by [`stream_length`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamLength {
+    /// A direct non-negative integer: the stream data can be read by length.
+    Direct(u64),
+    /// An indirect reference `obj gen R`: the length has to be resolved first.
+    Indirect(u32, u32),
+    /// No usable `/Length`: the reader has to scan for `endstream`.
+    Missing,
+}
+
+/// Classifies the `/Length` entry of a stream dictionary so the stream body reader can pick a strategy.
+///
+/// `tokens` are the tokens of the dictionary, or of the object up to `stream`; only the first
+/// dictionary's top level is inspected. A `/Length` whose value is neither a non-negative integer
+/// nor an indirect reference is reported as [`StreamLength::Missing`].
+///
+/// See: ISO 32000-2:2020, §7.3.8.2 Stream extent.
+pub(crate) fn stream_length(tokens: &[GreenTokenElement]) -> StreamLength {
+    let mut dictionary_depth = 0usize;
+    let mut key: Option<Vec<u8>> = None;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let value_key = key.take();
+        match token.kind() {
+            SyntaxKind::OpenDictToken => dictionary_depth += 1,
+            SyntaxKind::CloseDictToken if dictionary_depth == 1 => break,
+            SyntaxKind::CloseDictToken => dictionary_depth = dictionary_depth.saturating_sub(1),
+            _ if dictionary_depth == 1 && value_key.as_deref() == Some(b"/Length") => return length_value(&tokens[index..]),
+            SyntaxKind::NameLiteralToken if dictionary_depth == 1 && value_key.is_none() => key = Some(token.text()),
+            _ => {}
+        }
+    }
+
+    StreamLength::Missing
+}
+
+fn length_value(tokens: &[GreenTokenElement]) -> StreamLength {
+    match tokens {
+        [number, generation, reference, ..] if reference.kind() == SyntaxKind::IndirectReferenceKeyword => {
+            match (unsigned_integer_value(number), unsigned_integer_value(generation)) {
+                (Some(number), Some(generation)) => StreamLength::Indirect(number, generation),
+                _ => StreamLength::Missing,
+            }
+        }
+        [length, ..] if length.kind() == SyntaxKind::NumericLiteralToken => std::str::from_utf8(&length.text())
+            .ok()
+            .and_then(|text| text.parse::<u64>().ok())
+            .map_or(StreamLength::Missing, StreamLength::Direct),
+        _ => StreamLength::Missing,
+    }
+}
+
+fn unsigned_integer_value(token: &GreenTokenElement) -> Option<u32> {
+    match token.kind() {
+        SyntaxKind::NumericLiteralToken => std::str::from_utf8(&token.text()).ok().and_then(|text| text.parse::<u32>().ok()),
+        _ => None,
+    }
+}
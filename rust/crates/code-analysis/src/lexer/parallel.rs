@@ -0,0 +1,169 @@
+use std::{num::NonZeroUsize, thread};
+
+use super::{is_delimiter, is_whitespace};
+use crate::{GreenTokenElement, Lexer, SyntaxKind};
+
+/// Inputs are not split into chunks smaller than this, so small files are lexed on the calling thread.
+const MIN_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Tokenizes `source` on up to one thread per available core, returning the same tokens and
+/// offsets as [`Lexer::token_offsets`] over the whole input.
+///
+/// # Split points
+///
+/// [`find_split_points`] makes one cheap byte scan for `endobj` keywords and splits at the first
+/// byte after the trivia that follows one, where the previous token's trailing trivia ends. The
+/// scan skips comments, literal and hex strings, and everything from a `stream` keyword to the
+/// next `endstream`, which is where the lexer ends raw stream data too, so an `endobj` inside
+/// string or stream bytes is never taken for an object boundary.
+///
+/// Each thread lexes its chunk against the whole source from its split point, so lookahead past
+/// the chunk end sees the real bytes, and stops at the first token starting at or past the chunk
+/// end where the lexer is at rest, never right after a `stream` keyword or inside a
+/// cross-reference table. While stitching, a chunk that does not start where
+/// the previous one actually stopped is lexed again from there, so a wrong split point costs
+/// time but never changes the output.
+pub(crate) fn parallel_tokenize(source: &[u8]) -> Vec<(usize, GreenTokenElement)> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    tokenize_in_chunks(source, (source.len() / MIN_CHUNK_SIZE).clamp(1, threads))
+}
+
+pub(super) fn tokenize_in_chunks(source: &[u8], chunk_count: usize) -> Vec<(usize, GreenTokenElement)> {
+    let mut bounds = vec![0];
+    bounds.extend(find_split_points(source, chunk_count));
+    bounds.push(source.len());
+
+    let chunks: Vec<Chunk> = thread::scope(|scope| {
+        let workers: Vec<_> = bounds
+            .windows(2)
+            .map(|chunk| scope.spawn(move || lex_chunk(source, chunk[0], chunk[1])))
+            .collect();
+        workers.into_iter().map(|worker| worker.join().expect("tokenizer thread panicked")).collect()
+    });
+
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    for (chunk, bound) in chunks.into_iter().zip(&bounds[1..]) {
+        let chunk = match chunk.start == position {
+            true => chunk,
+            false => lex_chunk(source, position, *bound),
+        };
+        position = chunk.end;
+        tokens.extend(chunk.tokens);
+    }
+
+    tokens
+}
+
+/// Tokens lexed from `start`, up to the first token boundary at or past the requested end.
+struct Chunk {
+    start: usize,
+    end: usize,
+    tokens: Vec<(usize, GreenTokenElement)>,
+}
+
+fn lex_chunk(source: &[u8], start: usize, end: usize) -> Chunk {
+    let mut lexer = Lexer::new(source);
+    lexer.seek(start);
+
+    let mut tokens = Vec::new();
+    let mut position = start;
+    while position < end || !lexer.is_at_rest() {
+        let token = lexer.next_token();
+        if token.kind() == SyntaxKind::EndOfFileToken {
+            break;
+        }
+
+        let width = token.full_width() as usize;
+        tokens.push((position, token));
+        position += width;
+    }
+
+    Chunk { start, end: position, tokens }
+}
+
+/// Returns up to `chunk_count - 1` ascending offsets splitting `source` into chunks of roughly
+/// equal size, each the start of the token after an `endobj` keyword and its trivia.
+pub(super) fn find_split_points(source: &[u8], chunk_count: usize) -> Vec<usize> {
+    let mut points = Vec::with_capacity(chunk_count.saturating_sub(1));
+    let mut targets = (1..chunk_count).map(|index| index * source.len() / chunk_count);
+    let Some(mut target) = targets.next() else {
+        return points;
+    };
+
+    let mut position = 0;
+    while position < source.len() {
+        position = match source[position] {
+            b'%' => skip_line(source, position),
+            b'(' => skip_literal_string(source, position),
+            b'<' if source.get(position + 1) == Some(&b'<') => position + 2,
+            b'<' => skip_past(source, position, b">"),
+            _ if is_keyword_at(source, position, b"stream") => skip_past(source, position, b"endstream"),
+            _ if is_keyword_at(source, position, b"endobj") => {
+                let split = skip_trivia(source, position + b"endobj".len());
+                if split >= target && split < source.len() {
+                    points.push(split);
+                    match targets.by_ref().find(|&next| next > split) {
+                        Some(next) => target = next,
+                        None => break,
+                    }
+                }
+                split
+            }
+            _ => position + 1,
+        };
+    }
+
+    points
+}
+
+/// Returns `true` if `keyword` starts at `position` as a whole token, not as part of a longer one.
+fn is_keyword_at(source: &[u8], position: usize, keyword: &[u8]) -> bool {
+    let is_boundary = |byte: Option<&u8>| byte.is_none_or(|&byte| is_whitespace(byte, true) || is_delimiter(byte, false));
+    source[position..].starts_with(keyword) && (position == 0 || is_boundary(source.get(position - 1))) && is_boundary(source.get(position + keyword.len()))
+}
+
+/// Skips whitespace, end-of-line markers and comments, but not the `%PDF-` and `%%EOF` markers,
+/// which the lexer scans as tokens.
+fn skip_trivia(source: &[u8], mut position: usize) -> usize {
+    while let Some(&byte) = source.get(position) {
+        position = match byte {
+            _ if is_whitespace(byte, true) => position + 1,
+            b'%' if !source[position..].starts_with(b"%PDF-") && !source[position..].starts_with(b"%%EOF") => skip_line(source, position),
+            _ => break,
+        };
+    }
+
+    position
+}
+
+fn skip_line(source: &[u8], position: usize) -> usize {
+    source[position..]
+        .iter()
+        .position(|&byte| byte == b'\r' || byte == b'\n')
+        .map_or(source.len(), |eol| position + eol)
+}
+
+fn skip_literal_string(source: &[u8], mut position: usize) -> usize {
+    let mut depth = 0usize;
+    while let Some(&byte) = source.get(position) {
+        position += 1;
+        match byte {
+            b'\\' => position += 1,
+            b'(' => depth += 1,
+            b')' if depth == 1 => return position,
+            b')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    source.len()
+}
+
+/// Returns the offset just past the next `needle` after `position`, or the end of `source`.
+fn skip_past(source: &[u8], position: usize, needle: &[u8]) -> usize {
+    source[position + 1..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map_or(source.len(), |found| position + 1 + found + needle.len())
+}
@@ -0,0 +1,86 @@
+use std::{fmt, hash::BuildHasherDefault};
+
+use rustc_hash::FxHasher;
+
+use crate::{GreenNode, GreenNodeElement, GreenTokenElement, Lexer, SyntaxKind};
+
+type HashMap<K, V> = hashbrown::HashMap<K, V, BuildHasherDefault<FxHasher>>;
+
+/// Token counts and sizes of a source, see [`stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LexStats {
+    /// Number of tokens of each kind, the end of file token excluded.
+    pub(crate) counts: HashMap<SyntaxKind, usize>,
+    /// Bytes of token text of each kind, trivia excluded.
+    pub(crate) bytes: HashMap<SyntaxKind, usize>,
+    /// Number of tokens, the end of file token excluded.
+    pub(crate) total_tokens: usize,
+    /// Bytes of whitespace, end-of-line markers and comments.
+    pub(crate) trivia_bytes: usize,
+    /// Number of diagnostics attached to tokens and their trivia.
+    pub(crate) diagnostics: usize,
+    /// Length of the source in bytes.
+    pub(crate) source_bytes: usize,
+}
+
+/// Lexes `source` once and returns how many tokens of each kind it holds and how many bytes they
+/// take, e.g. to see that a file is mostly [`SyntaxKind::RawStreamDataToken`] bytes, or to compare
+/// lexer changes over a corpus.
+pub(crate) fn stats(source: &[u8]) -> LexStats {
+    let mut stats = LexStats {
+        source_bytes: source.len(),
+        ..LexStats::default()
+    };
+    let mut lexer = Lexer::new(source);
+    loop {
+        let token = lexer.next_token();
+        stats.trivia_bytes += (token.leading_trivia_width() + token.trailing_trivia_width()) as usize;
+        stats.diagnostics += diagnostic_count(&token);
+        if token.kind() == SyntaxKind::EndOfFileToken {
+            return stats;
+        }
+
+        *stats.counts.entry(token.kind()).or_default() += 1;
+        *stats.bytes.entry(token.kind()).or_default() += token.width() as usize;
+        stats.total_tokens += 1;
+    }
+}
+
+fn diagnostic_count(token: &GreenTokenElement) -> usize {
+    let trivia_count = |list: Option<GreenNode>| {
+        list.map_or(0, |list| {
+            list.slots()
+                .iter()
+                .map(|slot| match slot {
+                    GreenNodeElement::Trivia(trivia) => trivia.diagnostics().map_or(0, |diagnostics| diagnostics.len()),
+                    _ => 0,
+                })
+                .sum()
+        })
+    };
+    token.diagnostics().map_or(0, |diagnostics| diagnostics.len()) + trivia_count(token.leading_trivia()) + trivia_count(token.trailing_trivia())
+}
+
+/// Writes one row per kind, most frequent first, with its share of the source bytes, then the totals.
+impl fmt::Display for LexStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let share = |bytes: usize| match self.source_bytes {
+            0 => 0.0,
+            total => 100.0 * bytes as f64 / total as f64,
+        };
+
+        let mut kinds: Vec<_> = self.counts.iter().map(|(&kind, &count)| (kind, count, self.bytes[&kind])).collect();
+        kinds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.2.cmp(&a.2)).then_with(|| a.0.name().cmp(b.0.name())));
+
+        writeln!(f, "{:<32} {:>10} {:>12} {:>7}", "kind", "tokens", "bytes", "share")?;
+        for (kind, count, bytes) in kinds {
+            writeln!(f, "{:<32} {count:>10} {bytes:>12} {:>6.1}%", kind.name(), share(bytes))?;
+        }
+        writeln!(f, "{:<32} {:>10} {:>12} {:>6.1}%", "trivia", "", self.trivia_bytes, share(self.trivia_bytes))?;
+        write!(
+            f,
+            "{:<32} {:>10} {:>12}\n{} diagnostics",
+            "total", self.total_tokens, self.source_bytes, self.diagnostics
+        )
+    }
+}
@@ -0,0 +1,134 @@
+use std::io::{self, Read};
+
+use super::XRefState;
+use crate::{GreenTokenElement, Lexer, LexerOptions, SyntaxKind};
+
+/// Default number of bytes requested from the reader per refill.
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default upper bound for bytes held while waiting for a single token to complete.
+const DEFAULT_MAX_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+/// Tokenizes PDF bytes pulled from a [`Read`] source with bounded memory.
+///
+/// Produces the same tokens as [`Lexer`] over the whole input, but only keeps the bytes
+/// of the token currently being scanned.
+///
+/// # Buffering policy
+///
+/// A token is emitted only once at least one byte *after* it (and its trailing trivia) is
+/// buffered, or the reader is exhausted. Until then the lexer cannot tell whether the token
+/// continues (e.g. `12` followed by `3`, or a literal string not yet closed), so the buffer
+/// is refilled and the token rescanned. Each refill reads at least as many bytes as are already
+/// pending, so a token spanning many chunks is rescanned a logarithmic number of times rather
+/// than once per chunk. A single token larger than `max_buffer_size` (most likely raw stream
+/// data) fails with [`io::ErrorKind::InvalidData`] instead of growing the buffer without limit.
+pub(crate) struct StreamingLexer<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    is_reader_done: bool,
+    is_raw_stream: bool,
+    xref_state: XRefState,
+    has_emitted_token: bool,
+    options: LexerOptions,
+    chunk_size: usize,
+    max_buffer_size: usize,
+}
+
+impl<R: Read> StreamingLexer<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self::with_options(reader, LexerOptions::default())
+    }
+
+    pub(crate) fn with_options(reader: R, options: LexerOptions) -> Self {
+        Self {
+            reader,
+            buffer: Vec::with_capacity(DEFAULT_CHUNK_SIZE),
+            start: 0,
+            is_reader_done: false,
+            is_raw_stream: false,
+            xref_state: XRefState::None,
+            has_emitted_token: false,
+            options,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_buffer_size: DEFAULT_MAX_BUFFER_SIZE,
+        }
+    }
+
+    /// Sets how many bytes are requested from the reader per refill.
+    pub(crate) fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the maximum number of bytes a single token (with its trivia) may occupy.
+    pub(crate) fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
+
+    /// Scans and returns the next token, reading from the source as needed.
+    ///
+    /// Returns a [`SyntaxKind::EndOfFileToken`] once the reader is exhausted and all bytes are consumed.
+    pub(crate) fn next_token(&mut self) -> io::Result<GreenTokenElement> {
+        loop {
+            let pending = &self.buffer[self.start..];
+            // Each rescan starts a fresh lexer at offset 0, so only the first one may see a byte order mark.
+            let mut options = self.options;
+            options.skip_byte_order_mark &= !self.has_emitted_token;
+            let mut lexer = Lexer::with_options(pending, options);
+            lexer.is_raw_stream = self.is_raw_stream;
+            lexer.xref_state = self.xref_state;
+            let token = lexer.next_token();
+
+            let is_complete = lexer.position < pending.len() || self.is_reader_done;
+            if is_complete && (token.kind() != SyntaxKind::EndOfFileToken || self.is_reader_done) {
+                self.start += lexer.position;
+                self.is_raw_stream = lexer.is_raw_stream;
+                self.xref_state = lexer.xref_state;
+                self.has_emitted_token = true;
+                return Ok(token);
+            }
+
+            if pending.len() >= self.max_buffer_size {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "PDF token exceeds the maximum buffer size"));
+            }
+
+            self.fill_buffer()?;
+        }
+    }
+
+    /// Discards consumed bytes and appends the next chunk from the reader, or as many bytes as are
+    /// pending if that is more, reading until they are all filled or the reader is exhausted.
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        if self.start > 0 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+
+        let length = self.buffer.len();
+        let chunk_size = self.chunk_size.max(length).min(self.max_buffer_size.saturating_sub(length)).max(1);
+        self.buffer.resize(length + chunk_size, 0);
+
+        let mut filled = length;
+        while filled < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => {
+                    self.is_reader_done = true;
+                    break;
+                }
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.buffer.truncate(filled);
+                    return Err(error);
+                }
+            }
+        }
+
+        self.buffer.truncate(filled);
+        Ok(())
+    }
+}
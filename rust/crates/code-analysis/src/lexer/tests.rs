@@ -1,12 +1,30 @@
+mod backward;
 mod bracket_tokens;
+mod bracket_validation;
+mod comments;
+mod content_stream_operators;
+mod diff;
+mod forward_progress;
 mod generic;
+mod header;
 mod hex_string_token;
 mod keyword_token;
+mod lexer_mode;
+mod linearization;
 mod literal_string_token;
+mod lookahead;
 mod name_literal_token;
 mod numeric_literal_token;
+mod objects;
+mod objstm;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod safedocs_whitespace_rules;
+mod stats;
 mod stream_token;
+mod streaming;
 mod structure_keywords;
+mod token_offsets;
 mod trivia;
-mod utils;
+pub(crate) mod utils;
+mod xref_table;
@@ -0,0 +1,42 @@
+use crate::{Lexer, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+/// The last token whose text ends at or before `offset` when lexing `source` from the start.
+fn forward_token_ending_at(source: &[u8], offset: usize) -> Option<(usize, SyntaxKind, Vec<u8>)> {
+    Lexer::new(source)
+        .token_offsets()
+        .map(|(position, token)| (position + token.leading_trivia_width() as usize, token))
+        .filter(|(text_start, token)| text_start + token.width() as usize <= offset)
+        .last()
+        .map(|(text_start, token)| (text_start, token.kind(), token.text()))
+}
+
+fn backward_token_ending_at(source: &[u8], offset: usize) -> Option<(usize, SyntaxKind, Vec<u8>)> {
+    Lexer::token_ending_at(source, offset).map(|(text_start, token)| (text_start, token.kind(), token.text()))
+}
+
+#[test]
+fn test_token_ending_at_when_every_offset_of_names_and_numbers_expect_same_token_as_forward_lexing() {
+    let source = b"1 0 obj\r\n<< /Type /Page /MediaBox [0 0 612.5 -792]\n% /Fake 99 comment\n   /Parent 2 0 R/Kids[3 0 R]>>\nendobj";
+
+    for offset in 0..=source.len() {
+        assert_eq!(
+            backward_token_ending_at(source, offset),
+            forward_token_ending_at(source, offset),
+            "offset {offset}"
+        );
+    }
+}
+
+#[test]
+fn test_token_ending_at_when_offset_inside_or_right_after_name_expect_token_before_or_name_itself() {
+    let source = b"/Type /Page";
+
+    assert_eq!(backward_token_ending_at(source, 11), Some((6, SyntaxKind::NameLiteralToken, b"/Page".to_vec())));
+    assert_eq!(backward_token_ending_at(source, 8), Some((0, SyntaxKind::NameLiteralToken, b"/Type".to_vec())));
+    assert_eq!(backward_token_ending_at(source, 3), None);
+    assert_eq!(
+        backward_token_ending_at(source, 100),
+        Some((6, SyntaxKind::NameLiteralToken, b"/Page".to_vec()))
+    );
+}
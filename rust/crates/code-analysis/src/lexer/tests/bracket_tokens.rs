@@ -1,5 +1,5 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{Lexer, SyntaxKind, tree};
+use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, SyntaxKind, tree};
 
 #[test]
 fn test_scan_array_open_bracket_expect_open_bracket_token() {
@@ -80,6 +80,7 @@ fn test_scan_single_greater_than_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b">")
         }
     };
@@ -0,0 +1,48 @@
+use std::ops::Range;
+
+use super::utils::lex_all;
+use crate::{DiagnosticKind, GreenTokenElement, Lexer, SyntaxKind, lexer::validate_brackets};
+use pretty_assertions::assert_eq;
+
+fn validate(source: &[u8]) -> Vec<(Range<u32>, DiagnosticKind)> {
+    validate_brackets(&lex_all(Lexer::new(source)))
+        .into_iter()
+        .map(|(span, diagnostic)| (span, diagnostic.kind()))
+        .collect()
+}
+
+#[test]
+fn test_validate_brackets_when_balanced_nesting_expect_no_diagnostics() {
+    assert_eq!(validate(b"<< /Kids [1 0 R [2 0 R]] /Title (a(b)c) >>"), vec![]);
+}
+
+#[test]
+fn test_validate_brackets_when_unclosed_array_expect_unmatched_opener_at_position() {
+    assert_eq!(validate(b"1 [2 3"), vec![(2..3, DiagnosticKind::UnmatchedOpeningDelimiter)]);
+}
+
+#[test]
+fn test_validate_brackets_when_stray_dict_close_expect_unmatched_closer_at_position() {
+    assert_eq!(validate(b"[1] >>"), vec![(4..6, DiagnosticKind::UnmatchedClosingDelimiter)]);
+}
+
+#[test]
+fn test_validate_brackets_when_stray_string_close_expect_unmatched_closer() {
+    assert_eq!(validate(b"(ok) )"), vec![(5..6, DiagnosticKind::UnmatchedClosingDelimiter)]);
+}
+
+#[test]
+fn test_validate_brackets_when_dict_closes_over_open_array_expect_array_reported() {
+    assert_eq!(validate(b"<< /A [1 >>"), vec![(6..7, DiagnosticKind::UnmatchedOpeningDelimiter)]);
+}
+
+#[test]
+fn test_validate_brackets_when_mismatched_closer_expect_closer_reported_and_opener_kept() {
+    assert_eq!(
+        validate(b"[ >> ]\n<<"),
+        vec![
+            (2..4, DiagnosticKind::UnmatchedClosingDelimiter),
+            (7..9, DiagnosticKind::UnmatchedOpeningDelimiter)
+        ]
+    );
+}
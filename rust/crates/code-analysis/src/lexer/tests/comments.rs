@@ -0,0 +1,121 @@
+//! Comment handling with and without `LexerOptions::comments_as_tokens`.
+
+use super::utils::{generate_node_from_lexer, lex_all};
+use crate::{Lexer, LexerOptions, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+const SOURCE: &[u8] = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\r\n1 0 obj % the catalog\n<< >> %no eol\rendobj\n%PDF-1 is not a header\n%%EOF\n";
+
+fn comments_as_tokens() -> LexerOptions {
+    LexerOptions {
+        comments_as_tokens: true,
+        ..Default::default()
+    }
+}
+
+fn kinds(source: &[u8], options: LexerOptions) -> Vec<SyntaxKind> {
+    lex_all(Lexer::with_options(source, options)).iter().map(|token| token.kind()).collect()
+}
+
+#[test]
+fn test_next_token_when_comments_as_trivia_expect_comments_attached_and_full_text_identical() {
+    assert_eq!(
+        kinds(SOURCE, LexerOptions::default()),
+        vec![
+            SyntaxKind::PdfVersionToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::IndirectObjectKeyword,
+            SyntaxKind::OpenDictToken,
+            SyntaxKind::CloseDictToken,
+            SyntaxKind::IndirectEndObjectKeyword,
+            SyntaxKind::EndOfFileMarkerToken,
+        ]
+    );
+
+    let mut lexer = Lexer::new(SOURCE);
+    let header = lexer.next_token();
+    let trailing = header.trailing_trivia().expect("binary comment after the header");
+    let trivia_kinds: Vec<_> = trailing.slots().iter().map(|slot| slot.kind()).collect();
+    assert_eq!(
+        trivia_kinds,
+        vec![SyntaxKind::EndOfLineTrivia, SyntaxKind::CommentTrivia, SyntaxKind::EndOfLineTrivia]
+    );
+
+    assert_eq!(generate_node_from_lexer(&mut Lexer::new(SOURCE)).full_text(), SOURCE);
+}
+
+#[test]
+fn test_next_token_when_comments_as_tokens_expect_comment_tokens_and_full_text_identical() {
+    assert_eq!(
+        kinds(SOURCE, comments_as_tokens()),
+        vec![
+            SyntaxKind::PdfVersionToken,
+            SyntaxKind::CommentToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::IndirectObjectKeyword,
+            SyntaxKind::CommentToken,
+            SyntaxKind::OpenDictToken,
+            SyntaxKind::CloseDictToken,
+            SyntaxKind::CommentToken,
+            SyntaxKind::IndirectEndObjectKeyword,
+            SyntaxKind::CommentToken,
+            SyntaxKind::EndOfFileMarkerToken,
+        ]
+    );
+
+    let mut lexer = Lexer::with_options(SOURCE, comments_as_tokens());
+    lexer.next_token();
+    let comment = lexer.next_token();
+    assert_eq!(comment.text(), b"%\xE2\xE3\xCF\xD3");
+    let trailing = comment.trailing_trivia().expect("EOL after the comment");
+    assert_eq!(trailing.full_text(), b"\r\n");
+
+    assert_eq!(
+        generate_node_from_lexer(&mut Lexer::with_options(SOURCE, comments_as_tokens())).full_text(),
+        SOURCE
+    );
+}
+
+#[test]
+fn test_next_token_when_header_and_eof_marker_expect_same_tokens_in_both_modes() {
+    for options in [LexerOptions::default(), comments_as_tokens()] {
+        let mut lexer = Lexer::with_options(b"%PDF-2.0 %%EOF", options);
+        assert_eq!(lexer.next_token().kind(), SyntaxKind::PdfVersionToken);
+        assert_eq!(lexer.next_token().kind(), SyntaxKind::EndOfFileMarkerToken);
+        assert_eq!(lexer.next_token().kind(), SyntaxKind::EndOfFileToken);
+    }
+
+    assert_eq!(
+        kinds(b"%PDF-1.7abc\n%%EOFx", comments_as_tokens()),
+        vec![SyntaxKind::CommentToken, SyntaxKind::CommentToken]
+    );
+    assert_eq!(kinds(b"%PDF-1.7abc\n%%EOFx", LexerOptions::default()), vec![]);
+}
+
+#[test]
+fn test_next_token_when_comment_inside_xref_table_expect_entries_still_recognized() {
+    let source = b"xref\n0 2 % two entries\n0000000000 65535 f\r\n% the catalog\n0000000017 00000 n\r\ntrailer";
+    assert_eq!(
+        kinds(source, comments_as_tokens()),
+        vec![
+            SyntaxKind::XRefKeyword,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::CommentToken,
+            SyntaxKind::XRefEntryToken,
+            SyntaxKind::CommentToken,
+            SyntaxKind::XRefEntryToken,
+            SyntaxKind::FileTrailerKeyword,
+        ]
+    );
+}
+
+#[test]
+fn test_next_significant_token_when_comments_as_tokens_expect_comment_tokens_skipped() {
+    let mut lexer = Lexer::with_options(b"% leading\n1 % one\n% two\n2", comments_as_tokens());
+    assert_eq!(lexer.next_significant_token().text(), b"1");
+    assert_eq!(lexer.next_significant_token().text(), b"2");
+    assert_eq!(lexer.next_significant_token().kind(), SyntaxKind::EndOfFileToken);
+}
@@ -0,0 +1,58 @@
+use super::utils::lex_all;
+use crate::{Lexer, LexerOptions, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+fn lex_kinds(source: &[u8], content_stream_operators: bool) -> Vec<(SyntaxKind, Vec<u8>)> {
+    let options = LexerOptions {
+        content_stream_operators,
+        ..Default::default()
+    };
+    lex_all(Lexer::with_options(source, options))
+        .into_iter()
+        .map(|token| (token.kind(), token.text()))
+        .collect()
+}
+
+#[test]
+fn test_content_stream_operators_when_text_object_expect_operator_kinds() {
+    assert_eq!(
+        lex_kinds(b"BT /F1 12 Tf (Hi) Tj ET", true),
+        vec![
+            (SyntaxKind::BeginTextOperator, b"BT".to_vec()),
+            (SyntaxKind::NameLiteralToken, b"/F1".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"12".to_vec()),
+            (SyntaxKind::SetTextFontOperator, b"Tf".to_vec()),
+            (SyntaxKind::StringLiteralToken, b"(Hi)".to_vec()),
+            (SyntaxKind::ShowTextOperator, b"Tj".to_vec()),
+            (SyntaxKind::EndTextOperator, b"ET".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn test_content_stream_operators_when_operator_ends_with_non_letter_expect_single_token() {
+    assert_eq!(
+        lex_kinds(b"f* T* 0 0 d0 W* n", true),
+        vec![
+            (SyntaxKind::FillPathEvenOddOperator, b"f*".to_vec()),
+            (SyntaxKind::TextNextLineOperator, b"T*".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"0".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"0".to_vec()),
+            (SyntaxKind::SetCharWidthOperator, b"d0".to_vec()),
+            (SyntaxKind::EvenOddClipOperator, b"W*".to_vec()),
+            (SyntaxKind::EndPathOperator, b"n".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn test_content_stream_operators_when_disabled_expect_document_keywords_only() {
+    assert_eq!(
+        lex_kinds(b"BT n true", false),
+        vec![
+            (SyntaxKind::BadToken, b"BT".to_vec()),
+            (SyntaxKind::XRefInUseEntryKeyword, b"n".to_vec()),
+            (SyntaxKind::TrueKeyword, b"true".to_vec()),
+        ]
+    );
+}
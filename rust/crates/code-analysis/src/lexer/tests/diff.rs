@@ -0,0 +1,71 @@
+use crate::lexer::{TokenChangeKind, TokenDiff, diff_tokens};
+use pretty_assertions::assert_eq;
+
+const OLD: &[u8] = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n2 0 obj\n[1 2 3]\nendobj\n%%EOF\n";
+
+#[test]
+fn test_diff_tokens_when_one_object_differs_expect_single_changed_range() {
+    let new = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n2 0 obj\n[1 5 3]\nendobj\n%%EOF\n";
+    assert_eq!(
+        diff_tokens(OLD, new, false),
+        vec![TokenDiff {
+            kind: TokenChangeKind::Changed,
+            old_range: 56..57,
+            new_range: 56..57,
+        }]
+    );
+}
+
+#[test]
+fn test_diff_tokens_when_only_whitespace_differs_expect_no_differences_unless_trivia_included() {
+    let new = b"%PDF-1.7\n1 0 obj\n<</Type /Catalog>>\nendobj\n2 0 obj\n[1 2 3]\nendobj\n%%EOF\n";
+    assert_eq!(diff_tokens(OLD, new, false), vec![]);
+    assert_eq!(
+        diff_tokens(OLD, new, true),
+        vec![
+            TokenDiff {
+                kind: TokenChangeKind::Changed,
+                old_range: 17..20,
+                new_range: 17..19,
+            },
+            TokenDiff {
+                kind: TokenChangeKind::Changed,
+                old_range: 26..35,
+                new_range: 25..33,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_tokens_when_tokens_inserted_and_removed_expect_added_and_removed_ranges() {
+    assert_eq!(
+        diff_tokens(b"[1 2 3]", b"[0 1 2]", false),
+        vec![
+            TokenDiff {
+                kind: TokenChangeKind::Added,
+                old_range: 1..1,
+                new_range: 1..2,
+            },
+            TokenDiff {
+                kind: TokenChangeKind::Removed,
+                old_range: 5..6,
+                new_range: 6..6,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_tokens_when_every_token_of_large_input_differs_expect_single_changed_range() {
+    let old: Vec<u8> = (0..20_000).flat_map(|i| format!("{i} ").into_bytes()).collect();
+    let new: Vec<u8> = (0..20_000).flat_map(|i| format!("/N{i} ").into_bytes()).collect();
+    assert_eq!(
+        diff_tokens(&old, &new, false),
+        vec![TokenDiff {
+            kind: TokenChangeKind::Changed,
+            old_range: 0..old.len() as u32 - 1,
+            new_range: 0..new.len() as u32 - 1,
+        }]
+    );
+}
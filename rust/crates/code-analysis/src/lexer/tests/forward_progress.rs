@@ -0,0 +1,99 @@
+use super::utils::xorshift;
+use crate::{Lexer, LexerMode, LexerOptions, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+/// Fragments that drive the lexer into its less common states, mixed with raw random bytes.
+const FRAGMENTS: [&[u8]; 24] = [
+    b" ",
+    b"\r\n",
+    b"\r",
+    b"%",
+    b"%PDF-1.7",
+    b"%%EOF",
+    b"(",
+    b")",
+    b"\\",
+    b"<",
+    b">",
+    b"<<",
+    b"/",
+    b"#",
+    b"1",
+    b"-.5e",
+    b"stream\n",
+    b"endstream",
+    b"xref\n0 1\n",
+    b"0000000000 65535 f \n",
+    b"BI /W 1 ID ",
+    b"EI",
+    b"\xEF\xBB\xBF",
+    b"\0",
+];
+
+/// Lexes `source` to the end, asserting the forward-progress contract of [`Lexer::next_token`]:
+/// every token before the end of file is at least one byte wide, except the data of an empty
+/// stream, and the widths add up to the input.
+fn assert_forward_progress(mut lexer: Lexer<'_>, source: &[u8]) {
+    let mut consumed = 0usize;
+    for _ in 0..=2 * source.len() {
+        let token = lexer.next_token();
+        let width = token.full_width() as usize;
+        if token.kind() == SyntaxKind::EndOfFileToken {
+            assert_eq!(consumed + width, source.len(), "widths do not add up for {:?}", String::from_utf8_lossy(source));
+            return;
+        }
+
+        let is_empty_stream_data = token.kind() == SyntaxKind::RawStreamDataToken;
+        assert!(
+            width > 0 || is_empty_stream_data,
+            "zero-width {:?} at byte {consumed} of {:?}",
+            token.kind(),
+            String::from_utf8_lossy(source)
+        );
+        consumed += width;
+    }
+
+    panic!("no end of file after {} tokens for {:?}", 2 * source.len() + 1, String::from_utf8_lossy(source));
+}
+
+#[test]
+fn test_next_token_when_random_input_expect_progress_until_end_of_file_covering_all_bytes() {
+    let option_sets = [
+        LexerOptions::default(),
+        LexerOptions {
+            mode: LexerMode::Lenient,
+            coalesce_bad_tokens: false,
+            ..Default::default()
+        },
+        LexerOptions {
+            content_stream_operators: true,
+            scan_stream_data: false,
+            skip_byte_order_mark: false,
+            ..Default::default()
+        },
+    ];
+
+    let mut next = xorshift(0x9E37_79B9_7F4A_7C15);
+
+    for _ in 0..2000 {
+        let mut source = Vec::new();
+        for _ in 0..next(24) {
+            match next(4) {
+                0 => source.push(next(256) as u8),
+                _ => source.extend_from_slice(FRAGMENTS[next(FRAGMENTS.len())]),
+            }
+        }
+
+        for options in option_sets {
+            assert_forward_progress(Lexer::with_options(&source, options), &source);
+        }
+        assert_forward_progress(Lexer::new_objstm(&source, 2), &source);
+    }
+}
+
+#[test]
+fn test_next_token_when_every_single_byte_expect_one_token_then_end_of_file() {
+    for byte in 0..=u8::MAX {
+        assert_forward_progress(Lexer::new(&[byte]), &[byte]);
+    }
+}
@@ -1,5 +1,5 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{Lexer, SyntaxKind, tree};
+use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, LexerOptions, SyntaxKind, tree};
 
 #[test]
 fn test_scan_token_when_unknown_characters_expect_bad_token() {
@@ -8,6 +8,7 @@ fn test_scan_token_when_unknown_characters_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken) => {
                 trivia(SyntaxKind::WhitespaceTrivia, b" "),
                 text(b"@#$"),
@@ -26,6 +27,7 @@ fn test_scan_token_when_unmatched_closing_paren_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken) => {
                 trivia(SyntaxKind::WhitespaceTrivia, b" "),
                 text(b")"),
@@ -37,6 +39,65 @@ fn test_scan_token_when_unmatched_closing_paren_expect_bad_token() {
     assert_nodes_equal(&actual_node, &expected_node);
 }
 
+#[test]
+fn test_scan_token_when_long_garbage_run_expect_single_bad_token() {
+    let input = b"@#$)>".repeat(20);
+    assert_eq!(input.len(), 100);
+    let mut lexer = Lexer::new(&input);
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, &input)
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
+#[test]
+fn test_scan_token_when_garbage_run_before_valid_token_expect_run_ends_at_token_start() {
+    let mut lexer = Lexer::new(b"@)>>>[");
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b"@)"),
+            (SyntaxKind::CloseDictToken, b">>"),
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b">"),
+            (SyntaxKind::OpenBracketToken, b"[")
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
+#[test]
+fn test_scan_token_when_coalescing_disabled_expect_bad_token_per_stray_delimiter() {
+    let options = LexerOptions {
+        coalesce_bad_tokens: false,
+        ..Default::default()
+    };
+    let mut lexer = Lexer::with_options(b"@))", options);
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b"@"),
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b")"),
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b")")
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
 #[test]
 fn test_scan_pdf_version_expect_pdf_token() {
     let mut lexer = Lexer::new(b"%PDF-1.0\n%PDF-1.1\n%PDF-1.2\n%PDF-1.3\n%PDF-1.4\n%PDF-1.5\n%PDF-1.6\n%PDF-1.7\n%PDF-2.0\n");
@@ -0,0 +1,44 @@
+use crate::{
+    Lexer, SyntaxKind,
+    lexer::{PdfHeaderError, PdfVersion},
+};
+use pretty_assertions::assert_eq;
+
+fn version(source: &[u8]) -> Result<PdfVersion, PdfHeaderError> {
+    Lexer::from_pdf(source).map(|lexer| lexer.pdf_version().unwrap())
+}
+
+#[test]
+fn test_from_pdf_when_header_at_start_expect_version_and_lexing_from_byte_zero() {
+    let mut lexer = Lexer::from_pdf(b"%PDF-1.7\n1 0 obj").unwrap();
+
+    assert_eq!(lexer.pdf_version(), Some(PdfVersion { major: 1, minor: 7 }));
+    assert_eq!(lexer.next_token().kind(), SyntaxKind::PdfVersionToken);
+    assert_eq!(version(b"%PDF-2.0"), Ok(PdfVersion { major: 2, minor: 0 }));
+}
+
+#[test]
+fn test_from_pdf_when_garbage_before_header_within_1024_bytes_expect_version() {
+    let mut source = vec![b'x'; 1000];
+    source.extend_from_slice(b"\n%PDF-1.4\n");
+
+    assert_eq!(version(&source), Ok(PdfVersion { major: 1, minor: 4 }));
+    assert_eq!(Lexer::from_pdf(&source).unwrap().next_token().kind(), SyntaxKind::BadToken);
+}
+
+#[test]
+fn test_from_pdf_when_header_missing_past_limit_or_unsupported_expect_error_with_prefix() {
+    let mut late = vec![b' '; 1020];
+    late.extend_from_slice(b"%PDF-1.7");
+
+    assert_eq!(
+        version(b"GIF89a\x01\x00"),
+        Err(PdfHeaderError {
+            prefix: b"GIF89a\x01\x00".to_vec()
+        })
+    );
+    assert_eq!(version(&late).map_err(|error| error.prefix.len()), Err(32));
+    assert_eq!(version(b"%PDF-3.0\n1 0 obj"), Err(PdfHeaderError { prefix: b"%PDF-3.0".to_vec() }));
+    assert_eq!(version(b"junk\n%PDF-1.12\n"), Err(PdfHeaderError { prefix: b"%PDF-1.12".to_vec() }));
+    assert_eq!(Lexer::new(b"%PDF-1.7").pdf_version(), None);
+}
@@ -1,5 +1,9 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, SyntaxKind, tree};
+use crate::{
+    DiagnosticKind,
+    DiagnosticSeverity::{Error, Warning},
+    Lexer, LexerMode, LexerOptions, SyntaxKind, tree,
+};
 
 #[test]
 fn test_scan_hex_string_when_simple_hex_string_expect_hex_string_literal_token() {
@@ -47,7 +51,7 @@ fn test_scan_hex_string_when_even_number_of_digits_expect_hex_string_literal_tok
 }
 
 #[test]
-fn test_scan_hex_string_when_odd_number_of_digits_expect_hex_string_literal_token() {
+fn test_scan_hex_string_when_odd_number_of_digits_expect_hex_string_literal_token_with_warning() {
     // Example 2 from §7.3.4.3: <901FA> is a 3-byte string (90, 1F, A0)
     // Final digit assumed to be 0
     let mut lexer = Lexer::new(b"<901FA>");
@@ -55,6 +59,7 @@ fn test_scan_hex_string_when_odd_number_of_digits_expect_hex_string_literal_toke
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Warning, DiagnosticKind::OddLengthHexString, "Odd number of digits in hex string"),
             (SyntaxKind::HexStringLiteralToken, b"<901FA>")
         }
     };
@@ -141,13 +146,14 @@ fn test_scan_hex_string_when_two_strings_delimited_expect_two_hex_string_literal
 }
 
 #[test]
-fn test_scan_hex_string_when_single_digit_expect_hex_string_literal_token() {
+fn test_scan_hex_string_when_single_digit_expect_hex_string_literal_token_with_warning() {
     // Single digit should be treated as odd number (trailing 0 assumed)
     let mut lexer = Lexer::new(b"<A>");
     let actual_node = generate_node_from_lexer(&mut lexer);
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Warning, DiagnosticKind::OddLengthHexString, "Odd number of digits in hex string"),
             (SyntaxKind::HexStringLiteralToken, b"<A>")
         }
     };
@@ -204,3 +210,52 @@ fn test_scan_hex_string_when_unclosed_expect_invalid_character_diagnostic() {
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+#[test]
+fn test_scan_hex_string_when_invalid_digits_expect_diagnostic_ranged_over_first_one() {
+    let mut lexer = Lexer::new(b"<48XY>");
+    let token = lexer.next_token();
+
+    assert_eq!(token.kind(), SyntaxKind::HexStringLiteralToken);
+    assert_eq!(token.text(), b"<48XY>");
+    let diagnostics = token.diagnostics().unwrap_or_default();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind(), DiagnosticKind::InvalidCharacterInHexString);
+    assert_eq!(diagnostics[0].message(), "Invalid character in hex string");
+    assert_eq!(diagnostics[0].range(), Some(3..4));
+
+    let mut lexer = Lexer::new(b"[ <48XY> ]");
+    let node = generate_node_from_lexer(&mut lexer);
+    let infos = node.diagnostic_infos();
+    assert_eq!(infos.len(), 1);
+    assert_eq!((infos[0].offset, infos[0].length), (5, 1));
+}
+
+#[test]
+fn test_scan_hex_string_when_odd_digits_separated_by_whitespace_in_lenient_mode_expect_no_diagnostic() {
+    let options = LexerOptions {
+        mode: LexerMode::Lenient,
+        ..Default::default()
+    };
+    let mut lexer = Lexer::with_options(b"<90 1F A>", options);
+    let token = lexer.next_token();
+
+    assert_eq!(token.kind(), SyntaxKind::HexStringLiteralToken);
+    assert_eq!(token.diagnostics(), None);
+}
+
+#[test]
+fn test_hex_decoded_len_when_digits_separated_by_whitespace_expect_digit_pairs() {
+    let cases: [(&[u8], usize); 5] = [(b"<48 65 6C 6C 6F>", 5), (b"<48656C6C6F>", 5), (b"<901FA>", 3), (b"<>", 0), (b"< \n\t>", 0)];
+
+    for (source, expected) in cases {
+        let token = Lexer::new(source).next_token();
+        assert_eq!(token.hex_decoded_len(), Some(expected), "{:?}", String::from_utf8_lossy(source));
+    }
+}
+
+#[test]
+fn test_hex_decoded_len_when_not_hex_string_expect_none() {
+    let token = Lexer::new(b"(Hello)").next_token();
+    assert_eq!(token.hex_decoded_len(), None);
+}
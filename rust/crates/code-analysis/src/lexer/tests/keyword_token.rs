@@ -51,6 +51,7 @@ fn test_scan_keyword_when_uppercase_true_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"TRUE")
         }
     };
@@ -65,6 +66,7 @@ fn test_scan_keyword_when_mixed_case_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"True")
         }
     };
@@ -79,6 +81,7 @@ fn test_scan_keyword_when_unrecognized_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"maybe")
         }
     };
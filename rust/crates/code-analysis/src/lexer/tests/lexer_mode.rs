@@ -0,0 +1,56 @@
+use crate::{DiagnosticKind, Lexer, LexerMode, LexerOptions, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+/// Lexes `source` in `mode`, returning each token's kind and text with the kinds of its diagnostics.
+fn lex_with_mode(source: &[u8], mode: LexerMode) -> Vec<(SyntaxKind, Vec<u8>, Vec<DiagnosticKind>)> {
+    let options = LexerOptions { mode, ..Default::default() };
+    let mut lexer = Lexer::with_options(source, options);
+    std::iter::from_fn(|| Some(lexer.next_token()))
+        .take_while(|token| token.kind() != SyntaxKind::EndOfFileToken)
+        .map(|token| {
+            let diagnostics = token.diagnostics().unwrap_or_default().iter().map(|diagnostic| diagnostic.kind()).collect();
+            (token.kind(), token.text(), diagnostics)
+        })
+        .collect()
+}
+
+fn diagnostic_kinds(tokens: &[(SyntaxKind, Vec<u8>, Vec<DiagnosticKind>)]) -> Vec<DiagnosticKind> {
+    tokens.iter().flat_map(|(_, _, diagnostics)| diagnostics.iter().copied()).collect()
+}
+
+fn without_diagnostics(tokens: &[(SyntaxKind, Vec<u8>, Vec<DiagnosticKind>)]) -> Vec<(SyntaxKind, Vec<u8>)> {
+    tokens.iter().map(|(kind, text, _)| (*kind, text.clone())).collect()
+}
+
+#[test]
+fn test_lexer_mode_when_recoverable_deviation_expect_diagnostic_only_in_strict_mode() {
+    let cases: [(&[u8], DiagnosticKind); 4] = [
+        (b"12true", DiagnosticKind::MissingWhitespaceBeforeToken),
+        (b"null5", DiagnosticKind::MissingWhitespaceBeforeToken),
+        (b"(a\\qb)", DiagnosticKind::InvalidEscapeInStringLiteral),
+        (b"<901FA>", DiagnosticKind::OddLengthHexString),
+    ];
+
+    for (source, expected) in cases {
+        let strict = lex_with_mode(source, LexerMode::Strict);
+        let lenient = lex_with_mode(source, LexerMode::Lenient);
+
+        assert_eq!(diagnostic_kinds(&strict), vec![expected], "strict: {:?}", String::from_utf8_lossy(source));
+        assert_eq!(diagnostic_kinds(&lenient), vec![], "lenient: {:?}", String::from_utf8_lossy(source));
+        assert_eq!(without_diagnostics(&strict), without_diagnostics(&lenient));
+    }
+}
+
+#[test]
+fn test_lexer_mode_when_unrecoverable_or_valid_input_expect_same_diagnostics_in_both_modes() {
+    let cases: [(&[u8], Vec<DiagnosticKind>); 3] = [
+        (b"(unbalanced", vec![DiagnosticKind::UnbalancedStringLiteral]),
+        (b"<901FA3>", vec![]),
+        (b"\xEF\xBB\xBF%PDF-1.7", vec![]),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(diagnostic_kinds(&lex_with_mode(source, LexerMode::Strict)), expected);
+        assert_eq!(diagnostic_kinds(&lex_with_mode(source, LexerMode::Lenient)), expected);
+    }
+}
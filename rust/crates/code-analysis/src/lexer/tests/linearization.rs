@@ -0,0 +1,21 @@
+use crate::lexer::is_linearized;
+
+#[test]
+fn test_is_linearized_when_first_object_has_linearized_key_expect_true() {
+    let source = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n43 0 obj\n<< /Linearized 1 /L 54567 /H [475 157] /O 45 /E 29537 /N 2 /T 54149 >>\nendobj\n";
+    assert!(is_linearized(source));
+}
+
+#[test]
+fn test_is_linearized_when_first_object_is_catalog_expect_false() {
+    let source = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n2 0 obj\n<< /Linearized 1 >>\nendobj\n";
+    assert!(!is_linearized(source));
+}
+
+#[test]
+fn test_is_linearized_when_key_outside_leading_region_expect_false() {
+    let mut source = b"%PDF-1.7\n".to_vec();
+    source.extend(std::iter::repeat_n(b' ', 1100));
+    source.extend_from_slice(b"1 0 obj << /Linearized 1 >> endobj");
+    assert!(!is_linearized(&source));
+}
@@ -108,6 +108,24 @@ fn test_scan_literal_string_when_unclosed_string_expect_unbalanced_diagnostic()
     assert_nodes_equal(&actual_node, &expected_node);
 }
 
+#[test]
+fn test_scan_literal_string_when_nested_string_unterminated_at_eof_expect_single_token_to_eof() {
+    let input = b"1 (outer (inner) (unterminated";
+    let mut lexer = Lexer::new(input);
+    let tokens: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token())).take(3).collect();
+
+    assert_eq!(
+        tokens.iter().map(|token| token.kind()).collect::<Vec<_>>(),
+        [SyntaxKind::NumericLiteralToken, SyntaxKind::StringLiteralToken, SyntaxKind::EndOfFileToken]
+    );
+    assert_eq!(tokens[1].text(), b"(outer (inner) (unterminated");
+    assert_eq!(tokens.iter().map(|token| token.full_width()).sum::<u32>(), input.len() as u32);
+
+    let diagnostics = tokens[1].diagnostics().expect("unterminated string must carry a diagnostic");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind(), DiagnosticKind::UnbalancedStringLiteral);
+}
+
 #[test]
 fn test_scan_literal_string_when_extra_open_paren_expect_unbalanced_diagnostic() {
     let mut lexer = Lexer::new(b"(()");
@@ -354,3 +372,35 @@ continuation)";
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+#[test]
+fn test_scan_literal_string_when_tricky_escapes_and_nesting_expect_single_token_with_expected_diagnostics() {
+    // (input, expected token text, expected diagnostics)
+    let cases: [(&[u8], &[u8], &[DiagnosticKind]); 12] = [
+        (b"(a(b)c) 1", b"(a(b)c)", &[]),
+        (b"(a((b))c)", b"(a((b))c)", &[]),
+        (b"(a\\)b) 1", b"(a\\)b)", &[]),
+        (b"(a\\(b)", b"(a\\(b)", &[]),
+        (b"(\\n\\r\\t\\b\\f\\\\)", b"(\\n\\r\\t\\b\\f\\\\)", &[]),
+        (b"(\\053\\53\\0)", b"(\\053\\53\\0)", &[]),
+        (b"(\\0531)", b"(\\0531)", &[]),
+        (b"(line\\\ncontinued)", b"(line\\\ncontinued)", &[]),
+        (b"(line\\\r\ncontinued)", b"(line\\\r\ncontinued)", &[]),
+        (b"(a\\qb)", b"(a\\qb)", &[DiagnosticKind::InvalidEscapeInStringLiteral]),
+        (
+            b"(unterminated (nested) 1 0 obj",
+            b"(unterminated (nested) 1 0 obj",
+            &[DiagnosticKind::UnbalancedStringLiteral],
+        ),
+        (b"(a\\)", b"(a\\)", &[DiagnosticKind::UnbalancedStringLiteral]),
+    ];
+
+    for (input, expected_text, expected_diagnostics) in cases {
+        let token = Lexer::new(input).next_token();
+        let diagnostics: Vec<_> = token.diagnostics().unwrap_or_default().iter().map(|diagnostic| diagnostic.kind()).collect();
+
+        assert_eq!(token.kind(), SyntaxKind::StringLiteralToken, "input: {:?}", String::from_utf8_lossy(input));
+        assert_eq!(token.text(), expected_text, "input: {:?}", String::from_utf8_lossy(input));
+        assert_eq!(diagnostics, expected_diagnostics, "input: {:?}", String::from_utf8_lossy(input));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{Lexer, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_peek_nth_when_tokens_peeked_expect_next_token_returns_same_sequence() {
+    let source = b"12 0 R % ref\n/Name";
+    let mut plain = Lexer::new(source);
+    let expected: Vec<_> = (0..5).map(|_| plain.next_token()).collect();
+
+    let mut lexer = Lexer::new(source);
+    assert_eq!(lexer.peek_nth(2).kind(), SyntaxKind::IndirectReferenceKeyword);
+    assert_eq!(lexer.peek_token().kind(), SyntaxKind::NumericLiteralToken);
+    let actual: Vec<_> = (0..5).map(|_| lexer.next_token()).collect();
+
+    assert_eq!(
+        actual.iter().map(|token| token.full_text()).collect::<Vec<_>>(),
+        expected.iter().map(|token| token.full_text()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        actual.iter().map(|token| token.kind()).collect::<Vec<_>>(),
+        expected.iter().map(|token| token.kind()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_peek_token_when_called_repeatedly_expect_same_token_without_advancing() {
+    let mut lexer = Lexer::new(b"true false");
+    assert_eq!(lexer.peek_token().kind(), SyntaxKind::TrueKeyword);
+    assert_eq!(lexer.peek_token().kind(), SyntaxKind::TrueKeyword);
+    assert_eq!(lexer.next_token().kind(), SyntaxKind::TrueKeyword);
+    assert_eq!(lexer.peek_token().kind(), SyntaxKind::FalseKeyword);
+}
+
+#[test]
+fn test_peek_nth_when_past_end_of_input_expect_zero_width_end_of_file_tokens() {
+    let mut lexer = Lexer::new(b"null ");
+    let end = lexer.peek_nth(3);
+    assert_eq!((end.kind(), end.full_width()), (SyntaxKind::EndOfFileToken, 0));
+    assert_eq!(lexer.next_token().full_text(), b"null ");
+    for _ in 0..4 {
+        let token = lexer.next_token();
+        assert_eq!((token.kind(), token.full_width()), (SyntaxKind::EndOfFileToken, 0));
+    }
+}
@@ -1,5 +1,6 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
 use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, SyntaxKind, tree};
+use pretty_assertions::assert_eq;
 
 #[test]
 fn test_scan_name_when_simple_name_expect_name_literal_token() {
@@ -238,3 +239,43 @@ fn test_scan_name_when_single_hex_digit_followed_by_non_hex_expect_invalid_hex_e
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+#[test]
+fn test_decoded_name_when_catalog_names_expect_escapes_resolved() {
+    let cases: [(&[u8], &[u8]); 6] = [
+        (b"/Type", b"Type"),
+        (b"/Catalog", b"Catalog"),
+        (b"/Lime#20Green", b"Lime Green"),
+        (b"/A#20B", b"A B"),
+        (b"/paired#28#29parentheses", b"paired()parentheses"),
+        (b"/The_Key_of_F#23_Minor", b"The_Key_of_F#_Minor"),
+    ];
+
+    for (input, expected) in cases {
+        let token = Lexer::new(input).next_token();
+        assert_eq!(token.decoded_name().as_deref(), Some(expected), "input {:?}", String::from_utf8_lossy(input));
+        assert_eq!(token.text(), input);
+    }
+}
+
+#[test]
+fn test_decoded_name_when_malformed_escape_expect_hash_kept_and_diagnostic() {
+    for (input, expected) in [(&b"/A#2"[..], &b"A#2"[..]), (b"/A#G0", b"A#G0")] {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token().decoded_name().as_deref(), Some(expected));
+
+        let actual_node = generate_node_from_lexer(&mut Lexer::new(input));
+        let expected_node = tree! {
+            SyntaxKind::None => {
+                @diagnostic(Error, DiagnosticKind::InvalidHexEscapeInName, "Invalid hex escape in name"),
+                (SyntaxKind::NameLiteralToken, input)
+            }
+        };
+        assert_nodes_equal(&actual_node, &expected_node);
+    }
+}
+
+#[test]
+fn test_decoded_name_when_not_name_token_expect_none() {
+    assert_eq!(Lexer::new(b"(Type)").next_token().decoded_name(), None);
+}
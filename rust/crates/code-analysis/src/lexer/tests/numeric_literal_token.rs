@@ -1,5 +1,6 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{Lexer, SyntaxKind, tree};
+use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, LexerMode, LexerOptions, SyntaxKind, tree};
+use pretty_assertions::assert_eq;
 
 #[test]
 fn test_scan_numeric_literal_when_integer_123_expect_numeric_literal_token() {
@@ -192,6 +193,7 @@ fn test_scan_numeric_literal_when_double_plus_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"++")
         }
     };
@@ -206,6 +208,7 @@ fn test_scan_numeric_literal_when_sign_mid_number_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"+345-36")
         }
     };
@@ -220,6 +223,7 @@ fn test_scan_numeric_literal_when_multiple_decimal_points_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"12.34.56")
         }
     };
@@ -234,9 +238,88 @@ fn test_scan_numeric_literal_when_multiple_decimals_starting_with_point_expect_b
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b".1.2.3")
         }
     };
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+#[test]
+fn test_as_i64_when_pdf_integer_forms_expect_value() {
+    let cases: [(&[u8], Option<i64>); 9] = [
+        (b"42", Some(42)),
+        (b"+17", Some(17)),
+        (b"-98", Some(-98)),
+        (b"0042", Some(42)),
+        (b"-", Some(0)),
+        (b"4.", None),
+        (b"-2.25", None),
+        (b"9223372036854775807", Some(i64::MAX)),
+        (b"9223372036854775808", None),
+    ];
+
+    for (source, expected) in cases {
+        let token = Lexer::new(source).next_token();
+        assert_eq!(token.as_i64(), expected, "{:?}", String::from_utf8_lossy(source));
+    }
+    assert_eq!(Lexer::new(b"-9223372036854775808").next_token().as_i64(), Some(i64::MIN));
+}
+
+#[test]
+fn test_as_f64_when_pdf_real_forms_expect_value() {
+    let cases: [(&[u8], f64); 9] = [
+        (b"42", 42.0),
+        (b"-2.25", -2.25),
+        (b".5", 0.5),
+        (b"4.", 4.0),
+        (b"+.002", 0.002),
+        (b"-.002", -0.002),
+        (b"000.50", 0.5),
+        (b".", 0.0),
+        (b"-", 0.0),
+    ];
+
+    for (source, expected) in cases {
+        let token = Lexer::new(source).next_token();
+        assert_eq!(token.as_f64(), Some(expected), "{:?}", String::from_utf8_lossy(source));
+    }
+}
+
+#[test]
+fn test_as_i64_and_as_f64_when_bad_or_non_numeric_token_expect_none() {
+    for source in [&b"--5"[..], b"1-2", b"12.34.56", b"/Name", b"true"] {
+        let token = Lexer::new(source).next_token();
+        assert_eq!((token.as_i64(), token.as_f64()), (None, None), "{:?}", String::from_utf8_lossy(source));
+    }
+}
+
+#[test]
+fn test_scan_numeric_literal_when_integer_outside_32_bit_range_expect_integer_overflow_warning() {
+    let diagnostic_kinds = |source: &[u8], mode: LexerMode| {
+        let token = Lexer::with_options(source, LexerOptions { mode, ..Default::default() }).next_token();
+        token
+            .diagnostics()
+            .unwrap_or_default()
+            .iter()
+            .map(|diagnostic| diagnostic.kind())
+            .collect::<Vec<_>>()
+    };
+
+    for source in [&b"2147483648"[..], b"-2147483649", b"+00002147483648", b"99999999999999999999999"] {
+        assert_eq!(
+            diagnostic_kinds(source, LexerMode::Strict),
+            vec![DiagnosticKind::IntegerOverflow],
+            "{:?}",
+            String::from_utf8_lossy(source)
+        );
+        assert_eq!(diagnostic_kinds(source, LexerMode::Lenient), vec![], "{:?}", String::from_utf8_lossy(source));
+    }
+    for source in [&b"2147483647"[..], b"-2147483648", b"00000000002147483647", b"2147483648.0"] {
+        assert_eq!(diagnostic_kinds(source, LexerMode::Strict), vec![], "{:?}", String::from_utf8_lossy(source));
+    }
+
+    let token = Lexer::new(b"2147483648").next_token();
+    assert_eq!(token.as_i64(), Some(2_147_483_648));
+}
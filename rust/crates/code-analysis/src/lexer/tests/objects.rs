@@ -0,0 +1,89 @@
+use super::utils::lex_all;
+use crate::{
+    GreenTokenElement, Lexer, SyntaxKind,
+    lexer::{StreamLength, find_xref_streams, objstm_object_count, split_objects, stream_length},
+};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_split_objects_when_multiple_objects_expect_ranges_from_number_to_endobj() {
+    let source = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\nendobj\n12 3 obj [1 2] endobj\n";
+    let objects = split_objects(source);
+
+    assert_eq!(objects, vec![(1, 0, 9..44), (12, 3, 45..66)]);
+    assert_eq!(&source[9..44], b"1 0 obj\n<< /Type /Catalog >>\nendobj");
+    assert_eq!(&source[45..66], b"12 3 obj [1 2] endobj");
+}
+
+#[test]
+fn test_split_objects_when_stream_contains_obj_keywords_expect_stream_kept_whole() {
+    let source = b"4 0 obj\n<< /Length 16 >>\nstream\n5 0 obj endobj\n\nendstream\nendobj\n6 0 obj null endobj";
+    assert_eq!(split_objects(source), vec![(4, 0, 0..64), (6, 0, 65..84)]);
+}
+
+#[test]
+fn test_split_objects_when_endobj_missing_expect_object_ends_before_next_header() {
+    let source = b"1 0 obj (a)\n2 0 obj (b)";
+    assert_eq!(split_objects(source), vec![(1, 0, 0..11), (2, 0, 12..23)]);
+}
+
+#[test]
+fn test_find_xref_streams_when_xref_stream_object_expect_its_number() {
+    let source = b"1 0 obj << /Type /Catalog >> endobj\n15 0 obj\n<< /Type /XRef /Size 16 /W [1 2 1] /Length 4 >>\nstream\nabcd\nendstream\nendobj\n";
+    assert_eq!(find_xref_streams(&lex_all(Lexer::new(source))), vec![15]);
+}
+
+#[test]
+fn test_find_xref_streams_when_type_xref_nested_or_absent_expect_none() {
+    let source = b"2 0 obj << /Sub << /Type /XRef >> /Type /Page >> endobj\n3 0 obj /XRef endobj";
+    assert_eq!(find_xref_streams(&lex_all(Lexer::new(source))), vec![]);
+}
+
+#[test]
+fn test_objstm_object_count_when_object_stream_header_expect_n_entry() {
+    let source = b"7 0 obj\n<< /Type /ObjStm /N 3 /First 18 /Length 120 /Filter /FlateDecode >>\nstream\n";
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(source))), Some(3));
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /N 12 /Type /ObjStm >>"))), Some(12));
+}
+
+#[test]
+fn test_objstm_object_count_when_not_object_stream_or_n_nested_expect_none() {
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Type /XRef /N 3 >>"))), None);
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Type /ObjStm /Sub << /N 3 >> >>"))), None);
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Type /ObjStm /N -3 >>"))), None);
+}
+
+#[test]
+fn test_objstm_object_count_when_keys_inside_array_value_expect_only_top_level_entries() {
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Type /ObjStm /Filter [ /N 3 ] >>"))), None);
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Kids [ /Type /ObjStm ] /N 3 >>"))), None);
+    assert_eq!(objstm_object_count(&lex_all(Lexer::new(b"<< /Filter [ /N 5 ] /N 3 /Type /ObjStm >>"))), Some(3));
+}
+
+#[test]
+fn test_stream_length_when_direct_or_indirect_expect_classified() {
+    assert_eq!(
+        stream_length(&lex_all(Lexer::new(b"<< /Filter /FlateDecode /Length 1024 >>"))),
+        StreamLength::Direct(1024)
+    );
+    assert_eq!(
+        stream_length(&lex_all(Lexer::new(b"<< /Length 12 0 R /Filter /FlateDecode >>"))),
+        StreamLength::Indirect(12, 0)
+    );
+    assert_eq!(
+        stream_length(&lex_all(Lexer::new(b"5 0 obj\n<</Length 8 3 R>>\nstream\n"))),
+        StreamLength::Indirect(8, 3)
+    );
+}
+
+#[test]
+fn test_stream_length_when_absent_nested_or_invalid_expect_missing() {
+    assert_eq!(stream_length(&lex_all(Lexer::new(b"<< /Filter /FlateDecode >>"))), StreamLength::Missing);
+    assert_eq!(
+        stream_length(&lex_all(Lexer::new(b"<< /DecodeParms << /Length 5 >> >>"))),
+        StreamLength::Missing
+    );
+    assert_eq!(stream_length(&lex_all(Lexer::new(b"<< /Length -5 >>"))), StreamLength::Missing);
+    assert_eq!(stream_length(&lex_all(Lexer::new(b"<< /Length /Five >>"))), StreamLength::Missing);
+    assert_eq!(stream_length(&lex_all(Lexer::new(b"<< /Type /Length /N 1 >>"))), StreamLength::Missing);
+}
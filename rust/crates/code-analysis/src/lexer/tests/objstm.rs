@@ -0,0 +1,82 @@
+use super::utils::lex_all;
+use crate::{Lexer, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+fn lex_kinds(lexer: Lexer<'_>) -> Vec<(SyntaxKind, Vec<u8>)> {
+    lex_all(lexer).into_iter().map(|token| (token.kind(), token.text())).collect()
+}
+
+#[test]
+fn test_new_objstm_when_two_packed_objects_expect_header_token_then_bare_objects() {
+    let source = b"11 0 12 18\n<< /Type /Font >> [1 2]";
+    let tokens = lex_kinds(Lexer::new_objstm(source, 2));
+
+    assert_eq!(
+        tokens,
+        vec![
+            (SyntaxKind::ObjStmHeaderToken, b"11 0 12 18".to_vec()),
+            (SyntaxKind::OpenDictToken, b"<<".to_vec()),
+            (SyntaxKind::NameLiteralToken, b"/Type".to_vec()),
+            (SyntaxKind::NameLiteralToken, b"/Font".to_vec()),
+            (SyntaxKind::CloseDictToken, b">>".to_vec()),
+            (SyntaxKind::OpenBracketToken, b"[".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"1".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"2".to_vec()),
+            (SyntaxKind::CloseBracketToken, b"]".to_vec()),
+        ]
+    );
+    assert_eq!(&source[11 + 18..], b"[1 2]");
+}
+
+#[test]
+fn test_new_objstm_when_first_object_is_integer_expect_only_object_count_pairs_in_header() {
+    let tokens = lex_kinds(Lexer::new_objstm(b" 5 0\r\n42", 1));
+    assert_eq!(
+        tokens,
+        vec![
+            (SyntaxKind::ObjStmHeaderToken, b"5 0".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"42".to_vec())
+        ]
+    );
+}
+
+#[test]
+fn test_new_objstm_when_table_truncated_expect_shorter_header_and_rest_lexed() {
+    let tokens = lex_kinds(Lexer::new_objstm(b"7 0 8 (x)", 2));
+    assert_eq!(
+        tokens,
+        vec![
+            (SyntaxKind::ObjStmHeaderToken, b"7 0 8".to_vec()),
+            (SyntaxKind::StringLiteralToken, b"(x)".to_vec())
+        ]
+    );
+}
+
+#[test]
+fn test_new_objstm_when_payload_starts_with_non_integer_expect_no_header_token() {
+    let tokens = lex_kinds(Lexer::new_objstm(b"<< /A 1 2 >>", 1));
+    assert_eq!(
+        tokens,
+        vec![
+            (SyntaxKind::OpenDictToken, b"<<".to_vec()),
+            (SyntaxKind::NameLiteralToken, b"/A".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"1".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"2".to_vec()),
+            (SyntaxKind::CloseDictToken, b">>".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn test_new_objstm_when_object_count_is_u32_max_expect_header_without_overflow() {
+    let tokens = lex_kinds(Lexer::new_objstm(b"1 0 2 5 [3]", u32::MAX));
+    assert_eq!(
+        tokens,
+        vec![
+            (SyntaxKind::ObjStmHeaderToken, b"1 0 2 5".to_vec()),
+            (SyntaxKind::OpenBracketToken, b"[".to_vec()),
+            (SyntaxKind::NumericLiteralToken, b"3".to_vec()),
+            (SyntaxKind::CloseBracketToken, b"]".to_vec()),
+        ]
+    );
+}
@@ -0,0 +1,75 @@
+use crate::{
+    GreenTokenElement, Lexer,
+    lexer::parallel::{find_split_points, parallel_tokenize, tokenize_in_chunks},
+};
+use pretty_assertions::assert_eq;
+
+fn serial(source: &[u8]) -> Vec<(usize, GreenTokenElement)> {
+    Lexer::new(source).token_offsets().collect()
+}
+
+fn object(number: usize, body: &str) -> String {
+    format!("{number} 0 obj\n{body}\nendobj\n")
+}
+
+fn corpus() -> Vec<Vec<u8>> {
+    let tricky = [
+        "<< /Type /Catalog /Pages 2 0 R >>",
+        "(endobj inside a (nested) string \\) endobj)",
+        "<656E646F626A endobj>",
+        "<< /Length 20 >>\nstream\nendobj\n1 0 obj\r\nendobj\nendstream",
+        "[1 2.5 -3 /Name#20A true null] % endobj in a comment\n",
+        "<< /Kids [3 0 R 4 0 R] /Count 2 >>",
+    ];
+    let mut documents = vec![Vec::new(), b"%PDF-1.7\n".to_vec(), b"1 0 obj endobj endobj".to_vec()];
+    for repeat in [1, 7, 40] {
+        let mut document = String::from("%PDF-2.0\n%\u{e2}\u{e3}\n");
+        for number in 0..repeat * tricky.len() {
+            document.push_str(&object(number + 1, tricky[number % tricky.len()]));
+        }
+        document.push_str("trailer\n<< /Root 1 0 R >>\nstartxref\n0\n%%EOF\n");
+        documents.push(document.into_bytes());
+    }
+    documents.push(b"1 0 obj\n<< /Length 5 >>\nstream\nendobj 2 0 obj (unterminated".to_vec());
+
+    documents
+}
+
+#[test]
+fn test_tokenize_in_chunks_when_corpus_expect_identical_to_serial_for_any_chunk_count() {
+    for document in corpus() {
+        let expected = serial(&document);
+        for chunk_count in [1, 2, 3, 5, 16, 200] {
+            assert_eq!(
+                tokenize_in_chunks(&document, chunk_count),
+                expected,
+                "{chunk_count} chunks of {:?}",
+                String::from_utf8_lossy(&document)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parallel_tokenize_when_document_expect_identical_to_serial() {
+    let document = corpus().pop().expect("corpus is not empty");
+    assert_eq!(parallel_tokenize(&document), serial(&document));
+    assert_eq!(parallel_tokenize(b""), vec![]);
+}
+
+#[test]
+fn test_find_split_points_when_endobj_in_strings_streams_and_comments_expect_only_object_boundaries() {
+    let source = b"1 0 obj (endobj) endobj\n% endobj\n2 0 obj\nstream\nendobj\nendstream\nendobj 3 0 obj <endobj> endobj\n4 0 obj";
+    let expected: Vec<usize> = [&b"2 0 obj"[..], b"3 0 obj", b"4 0 obj"]
+        .iter()
+        .map(|needle| source.windows(needle.len()).position(|window| window == *needle).expect("needle is present"))
+        .collect();
+
+    assert_eq!(find_split_points(source, source.len()), expected);
+}
+
+#[test]
+fn test_find_split_points_when_one_chunk_or_no_objects_expect_none() {
+    assert_eq!(find_split_points(b"1 0 obj null endobj 2 0 obj", 1), vec![]);
+    assert_eq!(find_split_points(b"[1 2 3] (endobj)", 4), vec![]);
+}
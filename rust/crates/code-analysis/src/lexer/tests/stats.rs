@@ -0,0 +1,51 @@
+use crate::{SyntaxKind, lexer::stats};
+use pretty_assertions::assert_eq;
+
+const BODY: &[u8] = b"1 0 obj\n<< /Length 5 /Filter /Bad# >>\nstream\nHello\nendstream\nendobj\n";
+
+#[test]
+fn test_stats_when_small_object_with_stream_expect_counts_bytes_and_diagnostics() {
+    let stats = stats(BODY);
+
+    let mut counts: Vec<_> = stats.counts.iter().map(|(kind, count)| (kind.name(), *count)).collect();
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            ("CloseDictToken", 1),
+            ("EndStreamKeyword", 1),
+            ("IndirectEndObjectKeyword", 1),
+            ("IndirectObjectKeyword", 1),
+            ("NameLiteralToken", 3),
+            ("NumericLiteralToken", 3),
+            ("OpenDictToken", 1),
+            ("RawStreamDataToken", 1),
+            ("StreamKeyword", 1),
+        ]
+    );
+    assert_eq!(stats.total_tokens, 13);
+    assert_eq!(stats.bytes[&SyntaxKind::RawStreamDataToken], 5);
+    assert_eq!(stats.bytes[&SyntaxKind::NameLiteralToken], 19);
+    assert_eq!(stats.trivia_bytes, 13);
+    assert_eq!(stats.bytes.values().sum::<usize>() + stats.trivia_bytes, BODY.len());
+    assert_eq!(stats.diagnostics, 1);
+}
+
+#[test]
+fn test_stats_when_displayed_expect_rows_by_frequency_then_totals() {
+    let text = stats(BODY).to_string();
+    let lines: Vec<_> = text.lines().collect();
+
+    assert!(lines[0].starts_with("kind"));
+    assert!(lines[1].starts_with("NameLiteralToken") && lines[1].ends_with("27.9%"), "{text}");
+    assert!(lines[2].starts_with("NumericLiteralToken"), "{text}");
+    assert!(lines[lines.len() - 3].starts_with("trivia"), "{text}");
+    assert_eq!(lines[lines.len() - 1], "1 diagnostics");
+}
+
+#[test]
+fn test_stats_when_empty_source_expect_zero_totals() {
+    let stats = stats(b"");
+    assert_eq!((stats.total_tokens, stats.trivia_bytes, stats.diagnostics), (0, 0, 0));
+    assert!(stats.counts.is_empty());
+}
@@ -1,5 +1,6 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{Lexer, SyntaxKind, tree};
+use crate::{Lexer, LexerOptions, SyntaxKind, tree};
+use pretty_assertions::assert_eq;
 
 /// Tests for PDF stream tokens (RawStreamDataToken)
 ///
@@ -580,3 +581,56 @@ fn test_scan_stream_when_stream_with_no_eol_before_endstream_expect_raw_stream_t
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+// ============================================================================
+// Stream Data Scanning Option
+// ============================================================================
+
+#[test]
+fn test_scan_stream_when_binary_data_looks_like_operators_expect_single_raw_stream_token() {
+    let source = b"stream\r\n<< /Type /XRef >> 1 0 obj (\x00\xFF) BT ET endobj\x1F\x8B\nendstream";
+    let options = LexerOptions {
+        scan_stream_data: true,
+        ..Default::default()
+    };
+    let mut lexer = Lexer::with_options(source, options);
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            (SyntaxKind::StreamKeyword) => {
+                text(b"stream"),
+                trivia(SyntaxKind::EndOfLineTrivia, b"\r\n")
+            },
+            (SyntaxKind::RawStreamDataToken, b"<< /Type /XRef >> 1 0 obj (\x00\xFF) BT ET endobj\x1F\x8B"),
+            (SyntaxKind::EndStreamKeyword) => {
+                trivia(SyntaxKind::EndOfLineTrivia, b"\n"),
+                text(b"endstream")
+            }
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
+#[test]
+fn test_scan_stream_when_stream_data_scanning_disabled_expect_body_lexed_as_tokens() {
+    let options = LexerOptions {
+        scan_stream_data: false,
+        ..Default::default()
+    };
+    let mut lexer = Lexer::with_options(b"stream\n12 (a) endstream", options);
+    let kinds: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token().kind()))
+        .take_while(|kind| *kind != SyntaxKind::EndOfFileToken)
+        .collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::StreamKeyword,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::StringLiteralToken,
+            SyntaxKind::EndStreamKeyword
+        ]
+    );
+}
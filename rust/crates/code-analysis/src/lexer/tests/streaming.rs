@@ -0,0 +1,96 @@
+use std::io::{self, Read};
+
+use crate::{GreenTokenElement, Lexer, SyntaxKind, lexer::StreamingLexer};
+use pretty_assertions::assert_eq;
+
+/// Reader that hands out at most `step` bytes per call, to exercise token splits at every boundary.
+struct TrickleReader<'a> {
+    source: &'a [u8],
+    step: usize,
+}
+
+impl Read for TrickleReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.step.min(buf.len()).min(self.source.len());
+        buf[..count].copy_from_slice(&self.source[..count]);
+        self.source = &self.source[count..];
+        Ok(count)
+    }
+}
+
+fn lex_in_memory(source: &[u8]) -> Vec<(SyntaxKind, Vec<u8>)> {
+    let mut lexer = Lexer::new(source);
+    collect_until_eof(|| Ok(lexer.next_token())).expect("in-memory lexing cannot fail")
+}
+
+fn collect_until_eof(mut next: impl FnMut() -> io::Result<GreenTokenElement>) -> io::Result<Vec<(SyntaxKind, Vec<u8>)>> {
+    let mut tokens = Vec::new();
+    loop {
+        let token = next()?;
+        let kind = token.kind();
+        tokens.push((kind, token.full_text()));
+        if kind == SyntaxKind::EndOfFileToken {
+            return Ok(tokens);
+        }
+    }
+}
+
+const SOURCE: &[u8] = b"%PDF-1.7\n1 0 obj\n<< /Length 12 /Title (Nested (paren) \\) string) /Id <48656C6C6F> >>\nstream\r\nBT 12 Tf ET\nendstream\nendobj\nxref\n0 1\n0000000000 65535 f\r\ntrailer\n<< /Size 1 >>\nstartxref\n123\n%%EOF\n";
+
+#[test]
+fn test_streaming_lexer_when_one_byte_reads_expect_same_tokens_as_in_memory_lexer() {
+    let mut lexer = StreamingLexer::new(TrickleReader { source: SOURCE, step: 1 }).with_chunk_size(1);
+    let actual = collect_until_eof(|| lexer.next_token()).expect("streaming lexing failed");
+
+    assert_eq!(actual, lex_in_memory(SOURCE));
+}
+
+#[test]
+fn test_streaming_lexer_when_various_chunk_sizes_expect_same_tokens_as_in_memory_lexer() {
+    let expected = lex_in_memory(SOURCE);
+
+    for step in [2, 3, 7, 16, 1024] {
+        let mut lexer = StreamingLexer::new(TrickleReader { source: SOURCE, step }).with_chunk_size(5);
+        let actual = collect_until_eof(|| lexer.next_token()).expect("streaming lexing failed");
+        assert_eq!(actual, expected, "mismatch with reader step {step}");
+    }
+}
+
+#[test]
+fn test_streaming_lexer_when_empty_reader_expect_end_of_file_token() {
+    let mut lexer = StreamingLexer::new(io::empty());
+    assert_eq!(lexer.next_token().expect("reading failed").kind(), SyntaxKind::EndOfFileToken);
+}
+
+#[test]
+fn test_streaming_lexer_when_token_exceeds_max_buffer_expect_invalid_data_error() {
+    let source = b"(an unterminated literal string that keeps going";
+    let mut lexer = StreamingLexer::new(TrickleReader { source, step: 4 })
+        .with_chunk_size(4)
+        .with_max_buffer_size(16);
+
+    let error = lexer.next_token().expect_err("expected buffer guard to trip");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_streaming_lexer_when_leading_byte_order_mark_expect_same_tokens_as_in_memory_lexer() {
+    let source = b"\xEF\xBB\xBF%PDF-1.7\n1 0 obj null endobj";
+    let mut lexer = StreamingLexer::new(TrickleReader { source, step: 1 }).with_chunk_size(1);
+    let actual = collect_until_eof(|| lexer.next_token()).expect("streaming lexing failed");
+
+    assert_eq!(actual, lex_in_memory(source));
+}
+
+#[test]
+fn test_streaming_lexer_when_stream_data_spans_many_chunks_expect_same_tokens_as_in_memory_lexer() {
+    let mut source = b"stream\n".to_vec();
+    source.extend(std::iter::repeat_n(b'x', 1 << 20));
+    source.extend(b"\nendstream\n");
+
+    // One-byte chunks would rescan the stream data once per byte without geometric refills.
+    let mut lexer = StreamingLexer::new(TrickleReader { source: &source, step: 4096 }).with_chunk_size(1);
+    let actual = collect_until_eof(|| lexer.next_token()).expect("streaming lexing failed");
+
+    assert_eq!(actual, lex_in_memory(&source));
+}
@@ -1,5 +1,5 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
-use crate::{Lexer, SyntaxKind, tree};
+use crate::{DiagnosticKind, DiagnosticSeverity::Error, Lexer, SyntaxKind, tree};
 
 /// Tests for PDF structure keywords: obj, endobj, R, stream, endstream, xref, f, n, trailer, startxref
 ///
@@ -97,6 +97,55 @@ fn test_scan_keyword_when_indirect_reference_pattern_expect_correct_tokens() {
     assert_nodes_equal(&actual_node, &expected_node);
 }
 
+#[test]
+fn test_scan_keyword_when_reference_components_separated_by_comment_expect_trivia_between_tokens() {
+    let mut lexer = Lexer::new(b"10 % comment\n 0 R");
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            (SyntaxKind::NumericLiteralToken) => {
+                text(b"10"),
+                trivia(SyntaxKind::WhitespaceTrivia, b" "),
+                trivia(SyntaxKind::CommentTrivia, b"% comment"),
+                trivia(SyntaxKind::EndOfLineTrivia, b"\n")
+            },
+            (SyntaxKind::NumericLiteralToken) => {
+                trivia(SyntaxKind::WhitespaceTrivia, b" "),
+                text(b"0"),
+                trivia(SyntaxKind::WhitespaceTrivia, b" ")
+            },
+            (SyntaxKind::IndirectReferenceKeyword, b"R")
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
+#[test]
+fn test_scan_keyword_when_r_followed_by_regular_characters_expect_no_reference_keyword() {
+    // "Rx" is a single regular-character run (§7.2.3), not "R" followed by "x".
+    let mut lexer = Lexer::new(b"10 0 Rx");
+    let actual_node = generate_node_from_lexer(&mut lexer);
+
+    let expected_node = tree! {
+        SyntaxKind::None => {
+            (SyntaxKind::NumericLiteralToken) => {
+                text(b"10"),
+                trivia(SyntaxKind::WhitespaceTrivia, b" ")
+            },
+            (SyntaxKind::NumericLiteralToken) => {
+                text(b"0"),
+                trivia(SyntaxKind::WhitespaceTrivia, b" ")
+            },
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
+            (SyntaxKind::BadToken, b"Rx")
+        }
+    };
+
+    assert_nodes_equal(&actual_node, &expected_node);
+}
+
 // ============================================================================
 // Stream Keywords (§7.3.8)
 // ============================================================================
@@ -215,6 +264,7 @@ fn test_scan_keyword_when_uppercase_obj_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"OBJ")
         }
     };
@@ -229,6 +279,7 @@ fn test_scan_keyword_when_mixed_case_stream_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"Stream")
         }
     };
@@ -244,6 +295,7 @@ fn test_scan_keyword_when_lowercase_r_expect_bad_token() {
 
     let expected_node = tree! {
         SyntaxKind::None => {
+            @diagnostic(Error, DiagnosticKind::UnrecognizedToken, "Unrecognized token"),
             (SyntaxKind::BadToken, b"r")
         }
     };
@@ -0,0 +1,35 @@
+use crate::{Lexer, SyntaxKind};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_token_offsets_when_tokens_with_trivia_expect_full_span_start_offsets_without_eof() {
+    let source = b"  1 0 obj % c\n(a)<<>>  ";
+    let offsets: Vec<_> = Lexer::new(source).token_offsets().map(|(offset, token)| (offset, token.kind())).collect();
+
+    assert_eq!(
+        offsets,
+        vec![
+            (0, SyntaxKind::NumericLiteralToken),
+            (4, SyntaxKind::NumericLiteralToken),
+            (6, SyntaxKind::IndirectObjectKeyword),
+            (14, SyntaxKind::StringLiteralToken),
+            (17, SyntaxKind::OpenDictToken),
+            (19, SyntaxKind::CloseDictToken),
+        ]
+    );
+}
+
+#[test]
+fn test_token_offsets_when_tokens_consumed_and_peeked_expect_offsets_from_next_token() {
+    let mut lexer = Lexer::new(b"true false null");
+    lexer.next_token();
+    lexer.peek_nth(1);
+
+    let offsets: Vec<_> = lexer.token_offsets().map(|(offset, token)| (offset, token.text())).collect();
+    assert_eq!(offsets, vec![(5, b"false".to_vec()), (11, b"null".to_vec())]);
+}
+
+#[test]
+fn test_token_offsets_when_empty_input_expect_no_tokens() {
+    assert_eq!(Lexer::new(b"  \n").token_offsets().count(), 0);
+}
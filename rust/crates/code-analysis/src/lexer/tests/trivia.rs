@@ -1,5 +1,6 @@
 use super::utils::{assert_nodes_equal, generate_node_from_lexer};
 use crate::{Lexer, SyntaxKind, tree};
+use pretty_assertions::assert_eq;
 
 #[test]
 fn test_scan_trivia_when_single_space_expect_whitespace_trivia() {
@@ -160,3 +161,66 @@ fn test_scan_trivia_when_mixed_eol_sequences_expect_separate_eol_trivia() {
 
     assert_nodes_equal(&actual_node, &expected_node);
 }
+
+#[test]
+fn test_scan_trivia_when_token_followed_by_crlf_expect_single_trailing_eol_trivia() {
+    let mut lexer = Lexer::new(b"009\r\n345");
+    let token = lexer.next_token();
+
+    let trailing_trivia = token.trailing_trivia().expect("Expected trailing trivia");
+    assert_eq!(trailing_trivia.slot_count(), 1);
+    assert_eq!(trailing_trivia.full_width(), 2);
+
+    let piece = trailing_trivia.slot(0).expect("Expected a trivia piece");
+    assert_eq!(piece.kind(), SyntaxKind::EndOfLineTrivia);
+    assert_eq!(piece.full_text(), b"\r\n");
+}
+
+#[test]
+fn test_next_significant_token_when_trivia_between_tokens_expect_same_sequence_as_next_token() {
+    let source = b"  1 0 obj % comment\r\n<< /A [true] >>\nendobj ";
+    let collect = |next: fn(&mut Lexer<'static>) -> crate::GreenTokenElement| {
+        let mut lexer = Lexer::new(source);
+        std::iter::from_fn(|| Some(next(&mut lexer)))
+            .map(|token| (token.kind(), token.text()))
+            .take_while(|(kind, _)| *kind != SyntaxKind::EndOfFileToken)
+            .collect::<Vec<_>>()
+    };
+
+    let significant = collect(Lexer::next_significant_token);
+    assert_eq!(significant, collect(Lexer::next_token));
+    assert_eq!(significant.first(), Some(&(SyntaxKind::NumericLiteralToken, b"1".to_vec())));
+    assert_eq!(significant.len(), 10);
+}
+
+#[test]
+fn test_scan_trivia_when_leading_byte_order_mark_expect_trivia_and_recognized_version() {
+    let mut lexer = Lexer::new(b"\xEF\xBB\xBF%PDF-1.7\n");
+    let token = lexer.next_token();
+
+    assert_eq!(token.kind(), SyntaxKind::PdfVersionToken);
+    assert_eq!(token.text(), b"%PDF-1.7");
+    assert_eq!(
+        token.leading_trivia().map(|trivia| trivia.slot(0).map(|piece| piece.kind())),
+        Some(Some(SyntaxKind::ByteOrderMarkTrivia))
+    );
+    assert_eq!(token.leading_trivia_width(), 3);
+    assert_eq!(token.full_width(), 12);
+}
+
+#[test]
+fn test_scan_trivia_when_byte_order_mark_skipping_disabled_expect_bad_token() {
+    let options = crate::LexerOptions {
+        skip_byte_order_mark: false,
+        ..Default::default()
+    };
+    let mut lexer = Lexer::with_options(b"\xEF\xBB\xBF%PDF-1.7", options);
+    assert_eq!(lexer.next_token().kind(), SyntaxKind::BadToken);
+}
+
+#[test]
+fn test_scan_trivia_when_byte_order_mark_after_start_expect_bad_token() {
+    let mut lexer = Lexer::new(b"1 \xEF\xBB\xBF");
+    assert_eq!(lexer.next_token().kind(), SyntaxKind::NumericLiteralToken);
+    assert_eq!(lexer.next_token().kind(), SyntaxKind::BadToken);
+}
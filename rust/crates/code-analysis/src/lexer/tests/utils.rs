@@ -1,4 +1,4 @@
-use crate::{GreenNode, GreenNodeElement, Lexer, SyntaxKind};
+use crate::{GreenNode, GreenNodeBuilder, GreenNodeElement, GreenTokenElement, Lexer, SyntaxKind};
 use pretty_assertions::assert_eq;
 
 /// Asserts that two green nodes have identical token streams and diagnostics.
@@ -30,6 +30,13 @@ pub fn assert_nodes_equal(actual: &GreenNode, expected: &GreenNode) {
     assert_eq!(actual.diagnostics(), expected.diagnostics());
 }
 
+/// Collects the tokens of `lexer` up to, but not including, the end of file token.
+pub fn lex_all(mut lexer: Lexer<'_>) -> Vec<GreenTokenElement> {
+    std::iter::from_fn(|| Some(lexer.next_token()))
+        .take_while(|token| token.kind() != SyntaxKind::EndOfFileToken)
+        .collect()
+}
+
 /// Rebuilds a lexer node from emitted tokens while preserving token-level diagnostics.
 pub fn generate_node_from_lexer(lexer: &mut Lexer) -> GreenNode {
     const MAX_TOKENS: usize = 999;
@@ -43,6 +50,19 @@ pub fn generate_node_from_lexer(lexer: &mut Lexer) -> GreenNode {
         println!("Lexer appears stuck: collected {} tokens (limit: {})", tokens.len(), MAX_TOKENS);
     }
 
-    let slots = tokens.into_iter().map(GreenNodeElement::Token).collect::<Vec<_>>();
-    GreenNode::new(SyntaxKind::None, slots)
+    let mut builder = GreenNodeBuilder::new();
+    tokens.into_iter().for_each(|token| builder.token(token));
+    builder.finish_wrapped(SyntaxKind::None).0
+}
+
+/// Returns a seeded xorshift64 generator of numbers below its `bound` argument, so randomized
+/// tests reproduce their failures.
+pub fn xorshift(seed: u64) -> impl FnMut(usize) -> usize {
+    let mut state = seed;
+    move |bound| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    }
 }
@@ -0,0 +1,155 @@
+//! Tests for cross-reference table entries scanned as `XRefEntryToken`
+//!
+//! See: ISO 32000-2:2020, §7.5.4 Cross-Reference Table
+
+use super::utils::lex_all;
+use crate::{DiagnosticKind, DiagnosticSeverity, Lexer, LexerMode, LexerOptions, SyntaxKind, XRefEntry};
+use pretty_assertions::assert_eq;
+
+/// A token's kind and text with the severity and kind of its diagnostics.
+type LexedToken = (SyntaxKind, Vec<u8>, Vec<(DiagnosticSeverity, DiagnosticKind)>);
+
+fn lex(source: &[u8], mode: LexerMode) -> Vec<LexedToken> {
+    let options = LexerOptions { mode, ..Default::default() };
+    lex_all(Lexer::with_options(source, options))
+        .into_iter()
+        .map(|token| {
+            let diagnostics = token
+                .diagnostics()
+                .unwrap_or_default()
+                .iter()
+                .map(|diagnostic| (diagnostic.severity(), diagnostic.kind()))
+                .collect();
+            (token.kind(), token.text(), diagnostics)
+        })
+        .collect()
+}
+
+fn entries(source: &[u8]) -> Vec<Option<XRefEntry>> {
+    lex_all(Lexer::new(source))
+        .into_iter()
+        .filter(|token| token.kind() == SyntaxKind::XRefEntryToken)
+        .map(|token| token.xref_entry())
+        .collect()
+}
+
+#[test]
+fn test_scan_xref_entry_when_two_subsections_expect_entry_tokens() {
+    let source = b"xref\n0 2\n0000000000 65535 f \n0000000017 00000 n \n5 1\n0000000123 00002 n\r\ntrailer";
+
+    let kinds: Vec<_> = lex(source, LexerMode::Strict)
+        .into_iter()
+        .map(|(kind, text, diagnostics)| (kind, text, diagnostics.len()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (SyntaxKind::XRefKeyword, b"xref".to_vec(), 0),
+            (SyntaxKind::NumericLiteralToken, b"0".to_vec(), 0),
+            (SyntaxKind::NumericLiteralToken, b"2".to_vec(), 0),
+            (SyntaxKind::XRefEntryToken, b"0000000000 65535 f".to_vec(), 0),
+            (SyntaxKind::XRefEntryToken, b"0000000017 00000 n".to_vec(), 0),
+            (SyntaxKind::NumericLiteralToken, b"5".to_vec(), 0),
+            (SyntaxKind::NumericLiteralToken, b"1".to_vec(), 0),
+            (SyntaxKind::XRefEntryToken, b"0000000123 00002 n".to_vec(), 0),
+            (SyntaxKind::FileTrailerKeyword, b"trailer".to_vec(), 0),
+        ]
+    );
+
+    assert_eq!(
+        entries(source),
+        vec![
+            Some(XRefEntry {
+                offset: 0,
+                generation: 65535,
+                in_use: false
+            }),
+            Some(XRefEntry {
+                offset: 17,
+                generation: 0,
+                in_use: true
+            }),
+            Some(XRefEntry {
+                offset: 123,
+                generation: 2,
+                in_use: true
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_scan_xref_entry_when_numbers_outside_table_expect_numeric_tokens() {
+    let kinds: Vec<_> = lex(b"0000000017 00000 n\n", LexerMode::Strict).into_iter().map(|(kind, _, _)| kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::XRefInUseEntryKeyword
+        ]
+    );
+}
+
+#[test]
+fn test_scan_xref_entry_when_malformed_entry_expect_error_in_both_modes() {
+    let cases: [&[u8]; 3] = [
+        b"xref\n0 1\n000000017 00000 n \ntrailer",
+        b"xref\n0 1\n0000000017 00000 x \ntrailer",
+        b"xref\n0 1\n0000000017 99999 n \ntrailer",
+    ];
+
+    for source in cases {
+        for mode in [LexerMode::Strict, LexerMode::Lenient] {
+            let tokens = lex(source, mode);
+            assert_eq!(tokens[3].0, SyntaxKind::XRefEntryToken, "{:?}", String::from_utf8_lossy(source));
+            assert_eq!(tokens[3].2, vec![(DiagnosticSeverity::Error, DiagnosticKind::MalformedXRefEntry)]);
+            assert_eq!(tokens[4].0, SyntaxKind::FileTrailerKeyword);
+        }
+        assert_eq!(entries(source), vec![None]);
+    }
+}
+
+#[test]
+fn test_scan_xref_entry_when_end_of_line_not_two_bytes_expect_warning_only_in_strict_mode() {
+    let cases: [&[u8]; 3] = [
+        b"xref\n0 1\n0000000017 00000 n\ntrailer",
+        b"xref\n0 1\n0000000017 00000 n \r\ntrailer",
+        b"xref\n0 1\n0000000017 00000 n  \ntrailer",
+    ];
+
+    for source in cases {
+        let strict = lex(source, LexerMode::Strict);
+        assert_eq!(strict[3].1, b"0000000017 00000 n".to_vec(), "{:?}", String::from_utf8_lossy(source));
+        assert_eq!(strict[3].2, vec![(DiagnosticSeverity::Warning, DiagnosticKind::MalformedXRefEntry)]);
+        assert_eq!(lex(source, LexerMode::Lenient)[3].2, vec![]);
+        assert_eq!(
+            entries(source),
+            vec![Some(XRefEntry {
+                offset: 17,
+                generation: 0,
+                in_use: true
+            })]
+        );
+    }
+}
+
+#[test]
+fn test_scan_xref_entry_when_empty_subsection_expect_next_subsection_header() {
+    let kinds: Vec<_> = lex(b"xref\n0 0\n3 1\n0000000017 00000 n \ntrailer", LexerMode::Strict)
+        .into_iter()
+        .map(|(kind, _, _)| kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::XRefKeyword,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::NumericLiteralToken,
+            SyntaxKind::XRefEntryToken,
+            SyntaxKind::FileTrailerKeyword,
+        ]
+    );
+}
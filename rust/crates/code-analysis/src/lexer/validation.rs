@@ -0,0 +1,65 @@
+use std::ops::Range;
+
+use crate::{DiagnosticKind, DiagnosticSeverity, GreenDiagnostic, GreenTokenElement, SyntaxKind};
+
+/// Checks that array `[`/`]`, dictionary `<<`/`>>` and literal string `(`/`)` delimiters are balanced
+/// across a lexed token stream.
+///
+/// This is a lightweight structural pass that catches the most common PDF corruption
+/// (truncated or spliced objects) without running the parser. Returned spans are absolute
+/// byte ranges of the offending token text, ordered by position.
+///
+/// Unbalanced parentheses inside a literal string are already reported by the lexer on the
+/// string token itself, so only stray `)` tokens are reported here.
+///
+/// See: ISO 32000-2:2020, §7.3.4.2 Literal strings, §7.3.6 Array objects, §7.3.7 Dictionary objects.
+pub(crate) fn validate_brackets(tokens: &[GreenTokenElement]) -> Vec<(Range<u32>, GreenDiagnostic)> {
+    let mut diagnostics = Vec::new();
+    let mut open_delimiters: Vec<(SyntaxKind, Range<u32>)> = Vec::new();
+    let mut position = 0u32;
+
+    for token in tokens {
+        let start = position + token.leading_trivia_width();
+        let span = start..start + token.width();
+        position += token.full_width();
+
+        let opener = match token.kind() {
+            SyntaxKind::OpenBracketToken | SyntaxKind::OpenDictToken => {
+                open_delimiters.push((token.kind(), span));
+                continue;
+            }
+            SyntaxKind::CloseBracketToken => SyntaxKind::OpenBracketToken,
+            SyntaxKind::CloseDictToken => SyntaxKind::OpenDictToken,
+            SyntaxKind::BadToken if token.text().first() == Some(&b')') => {
+                diagnostics.push((span, unmatched_closing_delimiter()));
+                continue;
+            }
+            _ => continue,
+        };
+
+        // A closer matching an outer opener implicitly closes everything opened after it.
+        match open_delimiters.iter().rposition(|(kind, _)| *kind == opener) {
+            Some(index) => {
+                diagnostics.extend(open_delimiters.drain(index + 1..).map(|(_, span)| (span, unmatched_opening_delimiter())));
+                open_delimiters.pop();
+            }
+            None => diagnostics.push((span, unmatched_closing_delimiter())),
+        }
+    }
+
+    diagnostics.extend(open_delimiters.into_iter().map(|(_, span)| (span, unmatched_opening_delimiter())));
+    diagnostics.sort_by_key(|(span, _)| span.start);
+    diagnostics
+}
+
+#[inline]
+fn unmatched_opening_delimiter() -> GreenDiagnostic {
+    let kind = DiagnosticKind::UnmatchedOpeningDelimiter;
+    GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str())
+}
+
+#[inline]
+fn unmatched_closing_delimiter() -> GreenDiagnostic {
+    let kind = DiagnosticKind::UnmatchedClosingDelimiter;
+    GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str())
+}
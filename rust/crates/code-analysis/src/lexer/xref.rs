@@ -0,0 +1,50 @@
+/// The fields of a cross-reference table entry `nnnnnnnnnn ggggg n`, see [`SyntaxKind::XRefEntryToken`].
+///
+/// See: ISO 32000-2:2020, §7.5.4 Cross-reference table.
+///
+/// [`SyntaxKind::XRefEntryToken`]: crate::SyntaxKind::XRefEntryToken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct XRefEntry {
+    /// Byte offset of the object for an in-use entry, or the number of the next free object for a free one.
+    pub(crate) offset: u64,
+    /// Generation number of the object.
+    pub(crate) generation: u16,
+    /// `true` for an `n` (in-use) entry, `false` for an `f` (free) entry.
+    pub(crate) in_use: bool,
+}
+
+impl XRefEntry {
+    /// Parses entry text of exactly 10 offset digits, a space, 5 generation digits, a space and `n` or `f`.
+    ///
+    /// Returns `None` for any other layout, including a generation number above `u16::MAX`.
+    pub(crate) fn parse(text: &[u8]) -> Option<XRefEntry> {
+        let [offset @ .., b' '] = text.get(..11)? else {
+            return None;
+        };
+        let [generation @ .., b' ', flag] = text.get(11..)? else {
+            return None;
+        };
+        if generation.len() != 5 {
+            return None;
+        }
+
+        let in_use = match flag {
+            b'n' => true,
+            b'f' => false,
+            _ => return None,
+        };
+        Some(XRefEntry {
+            offset: parse_digits(offset)?,
+            generation: u16::try_from(parse_digits(generation)?).ok()?,
+            in_use,
+        })
+    }
+}
+
+#[inline]
+fn parse_digits(digits: &[u8]) -> Option<u64> {
+    digits.iter().try_fold(0u64, |value, &digit| match digit {
+        b'0'..=b'9' => Some(value * 10 + u64::from(digit - b'0')),
+        _ => None,
+    })
+}
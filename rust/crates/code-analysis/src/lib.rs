@@ -4,25 +4,27 @@
 mod arc;
 mod diagnostic_kind;
 mod lexer;
+mod line_index;
 mod parser;
 mod syntax;
 mod syntax_kind;
 
 pub use crate::diagnostic_kind::DiagnosticKind;
+pub use crate::line_index::{LineIndex, PositionEncoding};
 pub use crate::syntax_kind::SyntaxKind;
 
 pub(crate) use crate::{
-    lexer::Lexer,
+    lexer::{Lexer, LexerMode, LexerOptions, XRefEntry},
     parser::Parser,
     syntax::{
-        DiagnosticSeverity, FileTrailerStartXrefSyntax, FileTrailerSyntax, GreenArrayElementExpressionSyntax, GreenArrayExpressionSyntax,
+        DiagnosticSeverity, EolStyle, FileTrailerStartXrefSyntax, FileTrailerSyntax, GreenArrayElementExpressionSyntax, GreenArrayExpressionSyntax,
         GreenCompatibilityExpressionSyntax, GreenCst, GreenDiagnostic, GreenDiagnosticData, GreenDictionaryElementSyntax, GreenDictionaryExpressionSyntax,
         GreenDirectObjectExpressionSyntax, GreenDirectObjectOrIndirectReferenceExpressionSyntax, GreenExpressionSyntax, GreenFlags,
         GreenIndirectBodyExpressionSyntax, GreenIndirectObjectHeaderExpressionSyntax, GreenIndirectReferenceExpressionSyntax, GreenInlineImageSyntax,
-        GreenListSyntax, GreenLiteralExpressionSyntax, GreenMarkedContentSyntax, GreenNode, GreenNodeData, GreenNodeElement, GreenNodeElementRef,
-        GreenNodeSyntax, GreenPdfDocumentElementSyntax, GreenPdfDocumentSyntax, GreenPdfVersionSyntax, GreenStreamBodySyntax, GreenStreamExpressionSyntax,
-        GreenStreamOperatorOperandExpressionSyntax, GreenStreamRawDataSyntax, GreenSyntaxFactory, GreenTextObjectSyntax, GreenToken, GreenTokenData,
-        GreenTokenElement, GreenTokenElementRef, GreenTokenWithFloatValue, GreenTokenWithFloatValueAndTrailingTrivia,
+        GreenListSyntax, GreenLiteralExpressionSyntax, GreenMarkedContentSyntax, GreenNode, GreenNodeBuilder, GreenNodeData, GreenNodeElement,
+        GreenNodeElementRef, GreenNodeSyntax, GreenPdfDocumentElementSyntax, GreenPdfDocumentSyntax, GreenPdfVersionSyntax, GreenStreamBodySyntax,
+        GreenStreamExpressionSyntax, GreenStreamOperatorOperandExpressionSyntax, GreenStreamRawDataSyntax, GreenSyntaxFactory, GreenTextObjectSyntax,
+        GreenToken, GreenTokenData, GreenTokenElement, GreenTokenElementRef, GreenTokenWithFloatValue, GreenTokenWithFloatValueAndTrailingTrivia,
         GreenTokenWithFloatValueAndTrailingTriviaData, GreenTokenWithFloatValueAndTrivia, GreenTokenWithFloatValueAndTriviaData, GreenTokenWithFloatValueData,
         GreenTokenWithIntValue, GreenTokenWithIntValueAndTrailingTrivia, GreenTokenWithIntValueAndTrailingTriviaData, GreenTokenWithIntValueAndTrivia,
         GreenTokenWithIntValueAndTriviaData, GreenTokenWithIntValueData, GreenTokenWithStringValue, GreenTokenWithStringValueAndTrailingTrivia,
@@ -34,4 +36,7 @@ pub(crate) use crate::{
     },
 };
 
-pub use crate::syntax::{SyntaxNode, SyntaxToken, SyntaxTokenValueRef, SyntaxTrivia};
+pub use crate::syntax::{SyntaxNode, SyntaxToken, SyntaxTokenValueRef, SyntaxTrivia, TextEdit};
+
+#[cfg(feature = "countme")]
+pub use crate::syntax::allocation_report;
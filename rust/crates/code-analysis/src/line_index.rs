@@ -0,0 +1,412 @@
+//! Line start table for mapping byte offsets to lines.
+//!
+//! PDF recognizes CR, LF and CR+LF as end-of-line markers, with CR+LF counting as a
+//! single line break (ISO 32000-2:2020, §7.2.3). The index records where each line starts
+//! and which marker ended the previous line, so it can be patched after an edit without
+//! keeping the document bytes around. Columns are byte offsets within the line; the
+//! `*_with_encoding` methods take the document bytes to convert them to UTF-16 code units,
+//! the default position encoding of the Language Server Protocol.
+
+use std::ops::Range;
+
+/// End-of-line marker that terminates a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineEnding {
+    Lf,
+    Cr,
+    CrLf,
+}
+
+impl LineEnding {
+    #[inline]
+    fn len(self) -> u32 {
+        match self {
+            LineEnding::Lf | LineEnding::Cr => 1,
+            LineEnding::CrLf => 2,
+        }
+    }
+}
+
+/// Unit in which columns are counted, as negotiated with an LSP client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionEncoding {
+    /// Bytes of the UTF-8 encoding, the same as the plain byte columns.
+    Utf8,
+    /// UTF-16 code units, so a character outside the Basic Multilingual Plane counts as two.
+    #[default]
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Picks `utf-8` if the client offers it in its `general.positionEncodings` capability, else
+    /// `utf-16`, which every client must support.
+    pub fn negotiate(offered: &[&str]) -> PositionEncoding {
+        match offered.contains(&PositionEncoding::Utf8.as_str()) {
+            true => PositionEncoding::Utf8,
+            false => PositionEncoding::Utf16,
+        }
+    }
+
+    /// Returns the `PositionEncodingKind` value naming this encoding.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PositionEncoding::Utf8 => "utf-8",
+            PositionEncoding::Utf16 => "utf-16",
+        }
+    }
+}
+
+/// Byte offsets of line starts in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Offset of the first byte of each line; always starts with `0`.
+    line_starts: Vec<u32>,
+    /// `line_endings[i]` is the marker between line `i` and line `i + 1`.
+    line_endings: Vec<LineEnding>,
+    len: u32,
+}
+
+impl LineIndex {
+    /// Builds the index for `bytes`.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut index = Self {
+            line_starts: vec![0],
+            line_endings: Vec::new(),
+            len: bytes.len() as u32,
+        };
+        for (start, ending) in scan_line_endings(bytes) {
+            index.line_starts.push(start);
+            index.line_endings.push(ending);
+        }
+        index
+    }
+
+    /// Total length in bytes of the indexed document.
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the indexed document is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of lines; an empty document or one without line breaks has one line.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte range of line `line`, including its end-of-line marker, or `None` past the last line.
+    pub fn line_range(&self, line: u32) -> Option<Range<u32>> {
+        let start = *self.line_starts.get(line as usize)?;
+        let end = self.line_starts.get(line as usize + 1).copied().unwrap_or(self.len);
+        Some(start..end)
+    }
+
+    /// Returns the zero-based line and byte column of `offset`.
+    ///
+    /// Offsets inside an end-of-line marker belong to the line it ends, and `self.len()` maps to
+    /// the end of the last line. Offsets past the end are clamped to it.
+    pub fn line_col(&self, offset: u32) -> (u32, u32) {
+        let offset = offset.min(self.len);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        (line as u32, offset - self.line_starts[line])
+    }
+
+    /// Returns the byte offset of byte column `col` on line `line`, the inverse of [`Self::line_col`].
+    ///
+    /// Returns `None` if `line` is past the last line or `col` is past the end of that line,
+    /// end-of-line marker included; the end of the last line is the end of the document.
+    pub fn offset(&self, line: u32, col: u32) -> Option<u32> {
+        let range = self.line_range(line)?;
+        let offset = range.start.checked_add(col)?;
+        let is_last_line = line as usize + 1 == self.line_starts.len();
+        (offset < range.end || (is_last_line && offset == self.len)).then_some(offset)
+    }
+
+    /// Returns the line and column of `offset` like [`Self::line_col`], with the column counted in
+    /// `encoding` over `text`, the indexed document.
+    ///
+    /// Bytes that are not valid UTF-8, common in PDF strings and streams, count as one UTF-16
+    /// code unit each; so do the bytes before `offset` of a character it splits.
+    pub fn line_col_with_encoding(&self, text: &[u8], offset: u32, encoding: PositionEncoding) -> (u32, u32) {
+        debug_assert_eq!(text.len() as u32, self.len, "text must be the indexed document");
+        let (line, col) = self.line_col(offset);
+        match encoding {
+            PositionEncoding::Utf8 => (line, col),
+            PositionEncoding::Utf16 => {
+                let start = self.line_starts[line as usize] as usize;
+                (line, utf16_len(&text[start..start + col as usize]))
+            }
+        }
+    }
+
+    /// Returns the byte offset of column `col`, counted in `encoding` over `text`, on line `line`;
+    /// the inverse of [`Self::line_col_with_encoding`].
+    ///
+    /// A UTF-16 column inside a surrogate pair is rounded down to the start of its character.
+    /// Returns `None` where [`Self::offset`] would for the resulting byte column.
+    pub fn offset_with_encoding(&self, text: &[u8], line: u32, col: u32, encoding: PositionEncoding) -> Option<u32> {
+        debug_assert_eq!(text.len() as u32, self.len, "text must be the indexed document");
+        let col = match encoding {
+            PositionEncoding::Utf8 => col,
+            PositionEncoding::Utf16 => {
+                let range = self.line_range(line)?;
+                utf16_col_to_byte_col(&text[range.start as usize..range.end as usize], col)?
+            }
+        };
+        self.offset(line, col)
+    }
+
+    /// Updates the index after the bytes in `range` were replaced by `new_text`.
+    ///
+    /// Only the edited region is rescanned, widened by one byte on each side when that byte is a
+    /// CR before or an LF after the edit, since the edit can join or split a CR+LF pair there.
+    /// Line starts after the edit are shifted by the length difference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or reversed.
+    pub fn apply_edit(&mut self, range: Range<u32>, new_text: &[u8]) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "Edit range {range:?} out of bounds for length {}",
+            self.len
+        );
+
+        let joins_before = range.start > 0 && self.eol_byte_at(range.start - 1) == Some(b'\r');
+        let joins_after = self.eol_byte_at(range.end) == Some(b'\n');
+        let window_start = range.start - joins_before as u32;
+        let old_window_end = range.end + joins_after as u32;
+
+        let mut window = Vec::with_capacity(new_text.len() + 2);
+        if joins_before {
+            window.push(b'\r');
+        }
+        window.extend_from_slice(new_text);
+        if joins_after {
+            window.push(b'\n');
+        }
+
+        // Line starts in `(window_start, old_window_end]` were produced by markers inside the window.
+        let first_removed = self.line_starts.partition_point(|&start| start <= window_start);
+        let end_removed = self.line_starts.partition_point(|&start| start <= old_window_end);
+
+        let inserted = scan_line_endings(&window).map(|(start, ending)| (window_start + start, ending));
+        let (inserted_starts, inserted_endings): (Vec<u32>, Vec<LineEnding>) = inserted.unzip();
+
+        let delta = new_text.len() as i64 - range.len() as i64;
+        for start in &mut self.line_starts[end_removed..] {
+            *start = (*start as i64 + delta) as u32;
+        }
+
+        self.line_starts.splice(first_removed..end_removed, inserted_starts);
+        self.line_endings.splice(first_removed - 1..end_removed - 1, inserted_endings);
+        self.len = (self.len as i64 + delta) as u32;
+    }
+
+    /// Returns the byte at `offset` if it belongs to an end-of-line marker (`\r` or `\n`).
+    fn eol_byte_at(&self, offset: u32) -> Option<u8> {
+        // The marker covering `offset`, if any, ends right before the first line start after it.
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let next_start = *self.line_starts.get(line)?;
+        let ending = self.line_endings[line - 1];
+        let marker_start = next_start - ending.len();
+        match (ending, offset.checked_sub(marker_start)?) {
+            (LineEnding::Lf, 0) => Some(b'\n'),
+            (LineEnding::Cr, 0) | (LineEnding::CrLf, 0) => Some(b'\r'),
+            (LineEnding::CrLf, 1) => Some(b'\n'),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the number of UTF-16 code units in `bytes`, counting each invalid UTF-8 byte as one.
+fn utf16_len(bytes: &[u8]) -> u32 {
+    bytes
+        .utf8_chunks()
+        .map(|chunk| chunk.valid().chars().map(char::len_utf16).sum::<usize>() + chunk.invalid().len())
+        .sum::<usize>() as u32
+}
+
+/// Returns the byte column of UTF-16 column `col` in `line`, or `None` if `col` is past its end.
+fn utf16_col_to_byte_col(line: &[u8], col: u32) -> Option<u32> {
+    let (mut units, mut bytes) = (0u32, 0u32);
+    for chunk in line.utf8_chunks() {
+        let invalid = chunk.invalid().iter().map(|_| (1, 1));
+        for (char_units, char_bytes) in chunk.valid().chars().map(|c| (c.len_utf16() as u32, c.len_utf8() as u32)).chain(invalid) {
+            if units + char_units > col {
+                return Some(bytes);
+            }
+            units += char_units;
+            bytes += char_bytes;
+        }
+    }
+
+    (units == col).then_some(bytes)
+}
+
+/// Yields the start offset of every line after the first, with the marker that precedes it.
+fn scan_line_endings(bytes: &[u8]) -> impl Iterator<Item = (u32, LineEnding)> + '_ {
+    let mut position = 0usize;
+    std::iter::from_fn(move || {
+        while position < bytes.len() {
+            let byte = bytes[position];
+            position += 1;
+            match byte {
+                b'\n' => return Some((position as u32, LineEnding::Lf)),
+                b'\r' if bytes.get(position) == Some(&b'\n') => {
+                    position += 1;
+                    return Some((position as u32, LineEnding::CrLf));
+                }
+                b'\r' => return Some((position as u32, LineEnding::Cr)),
+                _ => {}
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn apply(text: &mut Vec<u8>, index: &mut LineIndex, range: Range<u32>, new_text: &[u8]) {
+        text.splice(range.start as usize..range.end as usize, new_text.iter().copied());
+        index.apply_edit(range, new_text);
+        assert_eq!(
+            *index,
+            LineIndex::new(text),
+            "index diverged after edit, text = {:?}",
+            String::from_utf8_lossy(text)
+        );
+    }
+
+    #[test]
+    fn test_new_when_mixed_line_endings_expect_crlf_counted_once() {
+        let index = LineIndex::new(b"a\r\nb\rc\nd");
+        assert_eq!(index.line_starts, vec![0, 3, 5, 7]);
+        assert_eq!(index.line_count(), 4);
+    }
+
+    #[test]
+    fn test_line_col_when_crlf_lone_cr_and_trailing_newline_expect_marker_on_line_it_ends() {
+        let index = LineIndex::new(b"ab\r\nc\rd\n");
+        let positions: Vec<_> = (0..=index.len()).map(|offset| index.line_col(offset)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (2, 0), (2, 1), (3, 0)]);
+        assert_eq!(index.line_col(100), (3, 0));
+    }
+
+    #[test]
+    fn test_offset_when_line_or_column_out_of_range_expect_none() {
+        let index = LineIndex::new(b"ab\r\nc");
+        assert_eq!(index.offset(0, 3), Some(3));
+        assert_eq!(index.offset(0, 4), None);
+        assert_eq!(index.offset(1, 1), Some(5));
+        assert_eq!(index.offset(1, 2), None);
+        assert_eq!(index.offset(2, 0), None);
+    }
+
+    #[test]
+    fn test_offset_when_every_position_mapped_to_line_col_expect_round_trip() {
+        for text in [&b""[..], b"\n", b"a\r\nb\rc\nd", b"x\r\r\n\n", b"1 0 obj\r\nendobj\r"] {
+            let index = LineIndex::new(text);
+            for offset in 0..=index.len() {
+                let (line, col) = index.line_col(offset);
+                assert_eq!(index.offset(line, col), Some(offset), "offset {offset} of {:?}", String::from_utf8_lossy(text));
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_col_with_encoding_when_multibyte_and_astral_characters_expect_utf16_units() {
+        let text = "(h\u{e9}llo\u{1F600}) /N\n% \u{1F600} x".as_bytes();
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.line_col_with_encoding(text, 13, PositionEncoding::Utf8), (0, 13));
+        assert_eq!(index.line_col_with_encoding(text, 13, PositionEncoding::Utf16), (0, 10));
+        assert_eq!(index.line_col_with_encoding(text, 22, PositionEncoding::Utf16), (1, 4));
+        assert_eq!(index.offset_with_encoding(text, 1, 4, PositionEncoding::Utf16), Some(22));
+        assert_eq!(index.offset_with_encoding(text, 1, 3, PositionEncoding::Utf16), Some(18));
+        assert_eq!(index.offset_with_encoding(text, 1, 8, PositionEncoding::Utf16), None);
+    }
+
+    #[test]
+    fn test_offset_with_encoding_when_every_position_mapped_expect_round_trip_in_both_encodings() {
+        let texts: [&[u8]; 3] = [b"%\xF0\x9F\x98\x80\r\n(\xC3\xA9\xFF)\r", b"(\xFF\xFE) /A\n", "\u{1F600}\u{1F600}".as_bytes()];
+        for text in texts {
+            let index = LineIndex::new(text);
+            let boundaries = (0..=index.len()).filter(|&offset| text.get(offset as usize).is_none_or(|byte| !(0x80..0xC0).contains(byte)));
+            for offset in boundaries {
+                for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16] {
+                    let (line, col) = index.line_col_with_encoding(text, offset, encoding);
+                    assert_eq!(
+                        index.offset_with_encoding(text, line, col, encoding),
+                        Some(offset),
+                        "{encoding:?} offset {offset}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_negotiate_when_client_offers_utf8_expect_utf8_else_utf16() {
+        assert_eq!(PositionEncoding::negotiate(&["utf-16", "utf-8"]), PositionEncoding::Utf8);
+        assert_eq!(PositionEncoding::negotiate(&["utf-32", "utf-16"]), PositionEncoding::Utf16);
+        assert_eq!(PositionEncoding::negotiate(&[]), PositionEncoding::Utf16);
+    }
+
+    #[test]
+    fn test_apply_edit_when_series_of_edits_expect_same_as_fresh_index() {
+        let mut text = b"1 0 obj\r\n<< /A 1 >>\nendobj\r".to_vec();
+        let mut index = LineIndex::new(&text);
+
+        apply(&mut text, &mut index, 0..0, b"%PDF-1.7\n");
+        apply(&mut text, &mut index, 12..16, b"");
+        apply(&mut text, &mut index, 14..14, b"\n2 0 obj\r");
+        let length = text.len() as u32;
+        apply(&mut text, &mut index, 0..length, b"");
+        apply(&mut text, &mut index, 0..0, b"x\ny");
+    }
+
+    #[test]
+    fn test_apply_edit_when_inserting_lf_after_cr_expect_lines_merge_into_crlf() {
+        let mut text = b"a\rb".to_vec();
+        let mut index = LineIndex::new(&text);
+
+        apply(&mut text, &mut index, 2..2, b"\n");
+        assert_eq!(index.line_count(), 2);
+    }
+
+    #[test]
+    fn test_apply_edit_when_inserting_cr_before_lf_expect_lines_merge_into_crlf() {
+        let mut text = b"a\nb".to_vec();
+        let mut index = LineIndex::new(&text);
+
+        apply(&mut text, &mut index, 1..1, b"\r");
+        assert_eq!(index.line_count(), 2);
+    }
+
+    #[test]
+    fn test_apply_edit_when_splitting_crlf_expect_two_line_breaks() {
+        let mut text = b"a\r\nb".to_vec();
+        let mut index = LineIndex::new(&text);
+
+        apply(&mut text, &mut index, 2..2, b"x");
+        assert_eq!(index.line_count(), 3);
+    }
+
+    #[test]
+    fn test_apply_edit_when_deleting_between_cr_and_lf_expect_single_crlf() {
+        let mut text = b"a\rxyz\nb".to_vec();
+        let mut index = LineIndex::new(&text);
+
+        apply(&mut text, &mut index, 2..5, b"");
+        assert_eq!(index.line_count(), 2);
+    }
+}
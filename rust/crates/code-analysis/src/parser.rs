@@ -3,6 +3,9 @@
 use crate::{GreenTokenElement, Lexer};
 
 mod cursor;
+mod pdf_structure;
+
+pub(crate) use pdf_structure::parse_trailer;
 
 pub(crate) struct Parser<'source> {
     pub(super) lexer: Lexer<'source>,
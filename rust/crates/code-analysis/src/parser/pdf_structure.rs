@@ -0,0 +1,205 @@
+//! Parsing of the document-level structure that follows the objects of a PDF body.
+
+use std::iter::Peekable;
+
+use crate::{DiagnosticKind, DiagnosticSeverity, GreenDiagnostic, GreenNode, GreenNodeBuilder, GreenNodeElement, GreenTokenElement, SyntaxKind};
+
+/// Groups the tokens of a file trailer, `trailer << … >> startxref <offset> %%EOF`, into a
+/// [`SyntaxKind::FileTrailerExpression`] node.
+///
+/// The node holds the `trailer` keyword, a [`SyntaxKind::DictionaryExpression`] and a
+/// [`SyntaxKind::FileTrailerStartXrefExpression`] with the `startxref` keyword, the byte offset
+/// token and the `%%EOF` marker, the shapes of `FileTrailerSyntax` and `FileTrailerStartXrefSyntax`.
+/// Until an object parser exists, the dictionary entries are kept as a flat list of tokens,
+/// nested dictionaries included. An unterminated dictionary ends before `startxref` or `%%EOF`,
+/// with a missing `>>`.
+///
+/// Absent parts are missing tokens: a file whose cross-reference data is a stream has no
+/// `trailer` dictionary, and `%%EOF` is tolerated missing. A missing `startxref` is reported as
+/// [`DiagnosticKind::MissingStartXRef`], and an offset that is not a non-negative integer as
+/// [`DiagnosticKind::InvalidStartXRefOffset`]; both are attached to the start xref node.
+/// Parsing stops after `%%EOF` or at the first unexpected token. Missing tokens of fixed-text
+/// kinds still report their text, so the node's width is not the number of bytes consumed.
+///
+/// See: ISO 32000-2:2020, §7.5.5 File trailer.
+pub(crate) fn parse_trailer(tokens: &[GreenTokenElement]) -> GreenNode {
+    let mut tokens = tokens.iter().filter(|token| token.kind() != SyntaxKind::EndOfFileToken).peekable();
+    let mut builder = GreenNodeBuilder::new();
+    builder.start_node(SyntaxKind::FileTrailerExpression);
+    builder.token(expect(&mut tokens, SyntaxKind::FileTrailerKeyword));
+
+    builder.start_node(SyntaxKind::DictionaryExpression);
+    let has_dictionary = tokens.peek().is_some_and(|token| token.kind() == SyntaxKind::OpenDictToken);
+    builder.token(expect(&mut tokens, SyntaxKind::OpenDictToken));
+    builder.start_node(SyntaxKind::List);
+    let mut depth = 0usize;
+    // `startxref` and `%%EOF` end the entries at any depth, so an unclosed nested dictionary cannot swallow them.
+    let is_entry = |token: &&GreenTokenElement, depth: usize| match token.kind() {
+        SyntaxKind::StartXRefKeyword | SyntaxKind::EndOfFileMarkerToken => false,
+        SyntaxKind::CloseDictToken => depth > 0,
+        _ => true,
+    };
+    while has_dictionary && let Some(token) = tokens.next_if(|token| is_entry(token, depth)) {
+        match token.kind() {
+            SyntaxKind::OpenDictToken => depth += 1,
+            SyntaxKind::CloseDictToken => depth -= 1,
+            _ => {}
+        }
+        builder.token(token.clone());
+    }
+    builder.finish_node();
+    builder.token(expect(&mut tokens, SyntaxKind::CloseDictToken));
+    builder.finish_node();
+
+    // The builder only records ranged diagnostics, so the start xref node is built directly to carry its own.
+    let mut diagnostics = Vec::new();
+    let has_start_xref = tokens.peek().is_some_and(|token| token.kind() == SyntaxKind::StartXRefKeyword);
+    let start_xref_token = expect(&mut tokens, SyntaxKind::StartXRefKeyword);
+    let offset = match tokens.next_if(|token| has_start_xref && token.kind() == SyntaxKind::NumericLiteralToken) {
+        Some(offset) => {
+            if offset.as_i64().is_none_or(|offset| offset < 0) {
+                diagnostics.push(diagnostic(DiagnosticKind::InvalidStartXRefOffset));
+            }
+            offset.clone()
+        }
+        None => {
+            let kind = match has_start_xref {
+                true => DiagnosticKind::InvalidStartXRefOffset,
+                false => DiagnosticKind::MissingStartXRef,
+            };
+            diagnostics.push(diagnostic(kind));
+            GreenTokenElement::create_missing(SyntaxKind::NumericLiteralToken)
+        }
+    };
+    let end_of_file_token = expect(&mut tokens, SyntaxKind::EndOfFileMarkerToken);
+    let slots: [GreenNodeElement; 3] = [start_xref_token.into(), offset.into(), end_of_file_token.into()];
+    builder.add_node(GreenNode::new_with_diagnostic(SyntaxKind::FileTrailerStartXrefExpression, slots, diagnostics));
+
+    builder.finish_node();
+    builder.finish()
+}
+
+/// Returns the next token if it is of `kind`, or a missing token of `kind` without consuming anything.
+fn expect<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a GreenTokenElement>>, kind: SyntaxKind) -> GreenTokenElement {
+    match tokens.next_if(|token| token.kind() == kind) {
+        Some(token) => token.clone(),
+        None => GreenTokenElement::create_missing(kind),
+    }
+}
+
+#[inline]
+fn diagnostic(kind: DiagnosticKind) -> GreenDiagnostic {
+    GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileTrailerStartXrefSyntax, FileTrailerSyntax, GreenCst, Lexer, lexer::tests::utils::lex_all};
+    use pretty_assertions::assert_eq;
+
+    fn start_xref(trailer: &GreenNode) -> GreenNode {
+        match trailer.slot(2) {
+            Some(GreenNodeElement::Node(node)) => node.clone(),
+            slot => panic!("expected the start xref node, got {slot:?}"),
+        }
+    }
+
+    fn diagnostic_kinds(node: &GreenNode) -> Vec<DiagnosticKind> {
+        node.diagnostics().unwrap_or_default().iter().map(|diagnostic| diagnostic.kind()).collect()
+    }
+
+    #[test]
+    fn test_parse_trailer_when_minimal_valid_trailer_expect_dictionary_and_start_xref_offset() {
+        let source = b"trailer\n<< /Size 6 /Root 1 0 R /Info << /Nested true >> >>\nstartxref\n1234\n%%EOF\n";
+        let trailer = parse_trailer(&lex_all(Lexer::new(source)));
+
+        assert_eq!(trailer.kind(), SyntaxKind::FileTrailerExpression);
+        assert_eq!(trailer.full_text(), source);
+        let syntax = FileTrailerSyntax::cast(trailer.clone()).expect("trailer shape");
+        assert!(syntax.body().is_some());
+        let entries = match trailer.slot(1).and_then(|dictionary| dictionary.as_node()?.slot(1).cloned()) {
+            Some(GreenNodeElement::Node(entries)) => entries,
+            slot => panic!("expected the dictionary entries, got {slot:?}"),
+        };
+        assert_eq!(entries.slot_count(), 11);
+
+        let start_xref = start_xref(&trailer);
+        assert_eq!(diagnostic_kinds(&start_xref), vec![]);
+        let offset = FileTrailerStartXrefSyntax::cast(start_xref)
+            .and_then(|start_xref| start_xref.xref_offset())
+            .expect("offset token");
+        assert_eq!(offset.as_i64(), Some(1234));
+        assert!(!trailer.contains_diagnostics());
+    }
+
+    #[test]
+    fn test_parse_trailer_when_xref_stream_file_without_trailer_dictionary_expect_missing_tokens_only() {
+        let trailer = parse_trailer(&lex_all(Lexer::new(b"startxref 17 %%EOF")));
+
+        assert_eq!(start_xref(&trailer).full_text(), b"startxref 17 %%EOF");
+        assert!(matches!(trailer.slot(0), Some(GreenNodeElement::Token(token)) if token.is_missing()));
+        assert_eq!(diagnostic_kinds(&start_xref(&trailer)), vec![]);
+    }
+
+    #[test]
+    fn test_parse_trailer_when_start_xref_missing_expect_missing_start_xref_diagnostic() {
+        let trailer = parse_trailer(&lex_all(Lexer::new(b"trailer << /Size 1 >>\n%%EOF")));
+
+        let start_xref = start_xref(&trailer);
+        assert_eq!(diagnostic_kinds(&start_xref), vec![DiagnosticKind::MissingStartXRef]);
+        assert!(matches!(start_xref.slot(0), Some(GreenNodeElement::Token(token)) if token.is_missing()));
+        assert!(matches!(start_xref.slot(1), Some(GreenNodeElement::Token(token)) if token.is_missing()));
+        assert!(matches!(start_xref.slot(2), Some(GreenNodeElement::Token(token)) if !token.is_missing()));
+    }
+
+    #[test]
+    fn test_parse_trailer_when_dictionary_unterminated_expect_missing_close_and_start_xref_kept() {
+        let trailer = parse_trailer(&lex_all(Lexer::new(b"trailer << /Size 1 startxref 5 %%EOF")));
+
+        let dictionary = match trailer.slot(1) {
+            Some(GreenNodeElement::Node(dictionary)) => dictionary.clone(),
+            slot => panic!("expected the dictionary, got {slot:?}"),
+        };
+        assert_eq!(dictionary.slot(1).and_then(|entries| Some(entries.as_node()?.slot_count())), Some(2));
+        assert!(matches!(dictionary.slot(2), Some(GreenNodeElement::Token(token)) if token.is_missing()));
+
+        let start_xref = start_xref(&trailer);
+        assert_eq!(diagnostic_kinds(&start_xref), vec![]);
+        assert_eq!(start_xref.full_text(), b"startxref 5 %%EOF");
+    }
+
+    #[test]
+    fn test_parse_trailer_when_nested_dictionary_unterminated_expect_entries_end_before_start_xref() {
+        let trailer = parse_trailer(&lex_all(Lexer::new(b"trailer << /A << startxref 1 %%EOF")));
+
+        let dictionary = match trailer.slot(1) {
+            Some(GreenNodeElement::Node(dictionary)) => dictionary.clone(),
+            slot => panic!("expected the dictionary, got {slot:?}"),
+        };
+        assert_eq!(dictionary.slot(1).and_then(|entries| Some(entries.as_node()?.slot_count())), Some(2));
+        assert!(matches!(dictionary.slot(2), Some(GreenNodeElement::Token(token)) if token.is_missing()));
+
+        let start_xref = start_xref(&trailer);
+        assert_eq!(diagnostic_kinds(&start_xref), vec![]);
+        assert_eq!(start_xref.full_text(), b"startxref 1 %%EOF");
+    }
+
+    #[test]
+    fn test_parse_trailer_when_offset_not_a_non_negative_integer_expect_invalid_offset_diagnostic() {
+        for source in [
+            &b"trailer << >> startxref -5 %%EOF"[..],
+            b"trailer << >> startxref 12.5 %%EOF",
+            b"trailer << >> startxref %%EOF",
+        ] {
+            let trailer = parse_trailer(&lex_all(Lexer::new(source)));
+            assert_eq!(trailer.full_text(), source);
+            assert_eq!(
+                diagnostic_kinds(&start_xref(&trailer)),
+                vec![DiagnosticKind::InvalidStartXRefOffset],
+                "{:?}",
+                String::from_utf8_lossy(source)
+            );
+        }
+    }
+}
@@ -4,12 +4,12 @@ pub(crate) mod green;
 pub(crate) mod red;
 
 pub(crate) use self::green::{
-    DiagnosticSeverity, FileTrailerStartXrefSyntax, FileTrailerSyntax, GreenArrayElementExpressionSyntax, GreenArrayExpressionSyntax,
+    DiagnosticSeverity, EolStyle, FileTrailerStartXrefSyntax, FileTrailerSyntax, GreenArrayElementExpressionSyntax, GreenArrayExpressionSyntax,
     GreenCompatibilityExpressionSyntax, GreenCst, GreenDiagnostic, GreenDiagnosticData, GreenDictionaryElementSyntax, GreenDictionaryExpressionSyntax,
     GreenDirectObjectExpressionSyntax, GreenDirectObjectOrIndirectReferenceExpressionSyntax, GreenExpressionSyntax, GreenFlags,
     GreenIndirectBodyExpressionSyntax, GreenIndirectObjectHeaderExpressionSyntax, GreenIndirectReferenceExpressionSyntax, GreenInlineImageSyntax,
-    GreenListSyntax, GreenLiteralExpressionSyntax, GreenMarkedContentSyntax, GreenNode, GreenNodeData, GreenNodeElement, GreenNodeElementRef, GreenNodeSyntax,
-    GreenPdfDocumentElementSyntax, GreenPdfDocumentSyntax, GreenPdfVersionSyntax, GreenStreamBodySyntax, GreenStreamExpressionSyntax,
+    GreenListSyntax, GreenLiteralExpressionSyntax, GreenMarkedContentSyntax, GreenNode, GreenNodeBuilder, GreenNodeData, GreenNodeElement, GreenNodeElementRef,
+    GreenNodeSyntax, GreenPdfDocumentElementSyntax, GreenPdfDocumentSyntax, GreenPdfVersionSyntax, GreenStreamBodySyntax, GreenStreamExpressionSyntax,
     GreenStreamOperatorOperandExpressionSyntax, GreenStreamRawDataSyntax, GreenSyntaxFactory, GreenTextObjectSyntax, GreenToken, GreenTokenData,
     GreenTokenElement, GreenTokenElementRef, GreenTokenWithFloatValue, GreenTokenWithFloatValueAndTrailingTrivia,
     GreenTokenWithFloatValueAndTrailingTriviaData, GreenTokenWithFloatValueAndTrivia, GreenTokenWithFloatValueAndTriviaData, GreenTokenWithFloatValueData,
@@ -22,4 +22,7 @@ pub(crate) use self::green::{
     GreenXRefTableExpressionSyntax,
 };
 
-pub use self::red::{SyntaxNode, SyntaxToken, SyntaxTokenValueRef, SyntaxTrivia};
+pub use self::red::{SyntaxNode, SyntaxToken, SyntaxTokenValueRef, SyntaxTrivia, TextEdit};
+
+#[cfg(feature = "countme")]
+pub use self::green::allocation_report;
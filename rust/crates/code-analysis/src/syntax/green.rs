@@ -1,4 +1,8 @@
+#[cfg(feature = "countme")]
+mod allocation;
+mod builder;
 mod diagnostic;
+mod diagnostic_info;
 mod diagnostics;
 mod factory;
 mod flags;
@@ -6,16 +10,23 @@ mod node;
 mod node_element;
 mod node_type;
 mod nodes;
+mod normalize;
+mod reparse;
+mod store;
 mod tokens;
 #[cfg(test)]
 pub(crate) mod tree;
+mod tree_diff;
 mod trivia;
+mod visitor;
 
 pub(crate) use self::{
+    builder::{BuilderError, Checkpoint, GreenNodeBuilder},
     diagnostic::{DiagnosticSeverity, GreenDiagnostic, GreenDiagnosticData},
+    diagnostic_info::DiagnosticInfo,
     factory::GreenSyntaxFactory,
     flags::GreenFlags,
-    node::{GreenNode, GreenNodeData},
+    node::{EolStyle, GreenNode, GreenNodeData},
     node_element::{GreenNodeElement, GreenNodeElementRef},
     node_type::NodeOrTokenOrTrivia,
     nodes::{
@@ -27,6 +38,8 @@ pub(crate) use self::{
         GreenStreamBodySyntax, GreenStreamExpressionSyntax, GreenStreamOperatorOperandExpressionSyntax, GreenStreamRawDataSyntax, GreenTextObjectSyntax,
         GreenTrait, GreenXRefEntryExpressionSyntax, GreenXRefSectionSyntax, GreenXRefSubSectionSyntax, GreenXRefTableExpressionSyntax,
     },
+    normalize::NormalizeOptions,
+    store::{StoreStats, SubtreeStore, TriviaInterner},
     tokens::{
         GreenToken, GreenTokenData, GreenTokenElement, GreenTokenElementRef, GreenTokenWithFloatValue, GreenTokenWithFloatValueAndTrailingTrivia,
         GreenTokenWithFloatValueAndTrailingTriviaData, GreenTokenWithFloatValueAndTrivia, GreenTokenWithFloatValueAndTriviaData, GreenTokenWithFloatValueData,
@@ -37,5 +50,10 @@ pub(crate) use self::{
         GreenTokenWithValue, GreenTokenWithValueAndTrailingTrivia, GreenTokenWithValueAndTrailingTriviaData, GreenTokenWithValueAndTrivia,
         GreenTokenWithValueAndTriviaData, GreenTokenWithValueData, TokenType,
     },
+    tree_diff::{TreeChange, TreeChangeKind},
     trivia::{GreenTrivia, GreenTriviaData},
+    visitor::GreenVisitor,
 };
+
+#[cfg(feature = "countme")]
+pub use self::allocation::allocation_report;
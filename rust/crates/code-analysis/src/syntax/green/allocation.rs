@@ -0,0 +1,91 @@
+//! Live allocation counts for green tree values.
+//!
+//! Every green head embeds a `countme::Count`, which is zero-sized and free unless
+//! counting is compiled in. This module only exists with the `countme` feature, which
+//! turns on `countme/enable`; counts are then recorded once `countme::enable(true)` is called.
+
+use std::fmt::Write;
+
+use countme::Counts;
+
+use crate::{
+    GreenDiagnostic, GreenNode, GreenToken, GreenTokenWithTrailingTrivia, GreenTokenWithTrivia, GreenTokenWithValue, GreenTokenWithValueAndTrailingTrivia,
+    GreenTokenWithValueAndTrivia, GreenTrivia,
+};
+
+/// Returns a human-readable table of live, peak and total green allocations.
+///
+/// Useful for spotting memory growth in long-running sessions: a `live` count that keeps
+/// climbing after trees are dropped points to a leak.
+pub fn allocation_report() -> String {
+    let entries: [(&str, Counts); 4] = [
+        ("nodes", countme::get::<GreenNode>()),
+        ("tokens", token_counts()),
+        ("trivia", countme::get::<GreenTrivia>()),
+        ("diagnostics", countme::get::<GreenDiagnostic>()),
+    ];
+
+    let mut report = String::with_capacity(64 * (entries.len() + 1));
+    let _ = writeln!(report, "{:<12} {:>12} {:>12} {:>12}", "kind", "live", "max_live", "total");
+    for (name, counts) in entries {
+        let _ = writeln!(report, "{:<12} {:>12} {:>12} {:>12}", name, counts.live, counts.max_live, counts.total);
+    }
+    report
+}
+
+/// Sums the counts of every token representation.
+///
+/// `max_live` is summed too, so it is an upper bound rather than an exact historical peak.
+fn token_counts() -> Counts {
+    [
+        countme::get::<GreenToken>(),
+        countme::get::<GreenTokenWithTrivia>(),
+        countme::get::<GreenTokenWithTrailingTrivia>(),
+        countme::get::<GreenTokenWithValue<()>>(),
+        countme::get::<GreenTokenWithValueAndTrivia<()>>(),
+        countme::get::<GreenTokenWithValueAndTrailingTrivia<()>>(),
+    ]
+    .into_iter()
+    .fold(Counts::default(), |mut sum, counts| {
+        sum.live += counts.live;
+        sum.max_live += counts.max_live;
+        sum.total += counts.total;
+        sum
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNodeElement, SyntaxKind};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_allocation_report_when_called_expect_row_per_category() {
+        let report = allocation_report();
+        let names: Vec<&str> = report.lines().skip(1).filter_map(|line| line.split_whitespace().next()).collect();
+
+        assert_eq!(names, vec!["nodes", "tokens", "trivia", "diagnostics"]);
+    }
+
+    #[test]
+    fn test_allocation_report_when_trees_dropped_expect_fewer_live_nodes() {
+        countme::enable(true);
+
+        // Counts are process-wide, so drop far more nodes than concurrently running tests create.
+        let before = countme::get::<GreenNode>();
+        let nodes: Vec<GreenNode> = (0..100_000)
+            .map(|_| {
+                let token: GreenNodeElement = GreenToken::new(SyntaxKind::NullKeyword).into();
+                GreenNode::new(SyntaxKind::DirectObjectExpression, vec![token])
+            })
+            .collect();
+        let while_alive = countme::get::<GreenNode>();
+        drop(nodes);
+        let after_drop = countme::get::<GreenNode>();
+
+        assert!(while_alive.total >= before.total + 100_000);
+        assert!(after_drop.live < while_alive.live);
+        assert!(allocation_report().lines().nth(1).is_some_and(|row| row.starts_with("nodes")));
+    }
+}
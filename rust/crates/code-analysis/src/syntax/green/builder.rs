@@ -0,0 +1,719 @@
+//! Incremental construction of green trees.
+//!
+//! The builder keeps a flat stack of finished children plus a stack of open nodes,
+//! so a producer (parser, test helper, fragment lexer) can emit nodes and tokens in
+//! source order without knowing the final slot counts upfront.
+
+use std::{fmt, ops::Range};
+
+use crate::{
+    DiagnosticKind, DiagnosticSeverity, GreenDiagnostic, GreenNode, GreenNodeElement, GreenTrivia, SyntaxKind,
+    arc::Arc,
+    syntax::green::{DiagnosticInfo, TriviaInterner},
+};
+
+/// Builds a [`GreenNode`] tree from a sequence of `start_node`/`token`/`finish_node` calls.
+#[derive(Default, Debug)]
+pub(crate) struct GreenNodeBuilder {
+    /// Open nodes: kind and index of their first child in `children`.
+    parents: Vec<(SyntaxKind, usize)>,
+    children: Vec<GreenNodeElement>,
+    /// Diagnostics with explicit ranges, as absolute offsets from the start of the tree.
+    ranged_diagnostics: Vec<(Range<u32>, GreenDiagnostic)>,
+    /// Interner for [`Self::new_trivia`], shared with other builders.
+    trivia_interner: Option<Arc<TriviaInterner>>,
+    #[cfg(feature = "instrumentation")]
+    node_hook: Option<NodeHook>,
+}
+
+/// A position in the builder, taken with [`GreenNodeBuilder::checkpoint`].
+///
+/// Later used to wrap the elements added since in a node ([`GreenNodeBuilder::start_node_at`]) or
+/// to discard them ([`GreenNodeBuilder::rollback`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+    children: usize,
+    parents: usize,
+    /// First child of the node current at the checkpoint, telling it apart from a later sibling at the same depth.
+    first_child: usize,
+    ranged_diagnostics: usize,
+}
+
+/// Why [`GreenNodeBuilder::try_finish`] could not complete a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BuilderError {
+    /// Nodes were started but not finished; their kinds, outermost first.
+    UnfinishedNodes(Vec<SyntaxKind>),
+    /// Nothing was added to the builder.
+    EmptyTree,
+    /// More than one top-level element was added; their number.
+    MultipleRoots(usize),
+    /// The only top-level element is a token or trivia, of this kind, rather than a node.
+    RootNotNode(SyntaxKind),
+}
+
+impl fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuilderError::UnfinishedNodes(kinds) => write!(f, "{} unfinished node(s), outermost first: {kinds:?}", kinds.len()),
+            BuilderError::EmptyTree => f.write_str("no root node was built"),
+            BuilderError::MultipleRoots(count) => write!(f, "expected one root node, found {count} top-level elements"),
+            BuilderError::RootNotNode(kind) => write!(f, "expected the root to be a node, found {kind:?}"),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+#[cfg(feature = "instrumentation")]
+struct NodeHook(Box<dyn FnMut(SyntaxKind, usize)>);
+
+#[cfg(feature = "instrumentation")]
+impl std::fmt::Debug for NodeHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("NodeHook")
+    }
+}
+
+impl GreenNodeBuilder {
+    /// Creates an empty builder.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty builder that interns its trivia through `interner`, so builders sharing
+    /// one, e.g. across the documents of a batch, create each distinct trivia only once.
+    ///
+    /// Only trivia created by the builder ([`Self::new_trivia`], [`Self::trivia`]) is interned;
+    /// trivia already attached to tokens, such as the lexer's, is kept as is.
+    #[inline]
+    pub(crate) fn with_shared_cache(interner: Arc<TriviaInterner>) -> Self {
+        Self {
+            trivia_interner: Some(interner),
+            ..Self::default()
+        }
+    }
+
+    /// Returns trivia of `kind` with `text`, interned if the builder has a shared cache, for use
+    /// in token trivia lists or with [`Self::trivia`].
+    #[inline]
+    pub(crate) fn new_trivia(&self, kind: SyntaxKind, text: &[u8]) -> GreenTrivia {
+        match &self.trivia_interner {
+            Some(interner) => interner.intern(kind, text),
+            None => GreenTrivia::new(kind, text),
+        }
+    }
+
+    /// Appends trivia of `kind` with `text` to the current node, see [`Self::new_trivia`].
+    #[inline]
+    pub(crate) fn trivia(&mut self, kind: SyntaxKind, text: &[u8]) {
+        let trivia = self.new_trivia(kind, text);
+        self.children.push(trivia.into());
+    }
+
+    /// Opens a new node of `kind`; subsequent elements become its children until [`Self::finish_node`].
+    #[inline]
+    pub(crate) fn start_node(&mut self, kind: SyntaxKind) {
+        self.parents.push((kind, self.children.len()));
+    }
+
+    /// Appends a token (or any prebuilt element) to the current node.
+    #[inline]
+    pub(crate) fn token(&mut self, token: impl Into<GreenNodeElement>) {
+        self.children.push(token.into());
+    }
+
+    /// Appends an already built subtree, e.g. one reused by an incremental reparse, to the current node.
+    ///
+    /// The node is added as is, so it keeps its identity; the builder keeps no cache of its own,
+    /// so intern it through a [`crate::syntax::green::SubtreeStore`] first for it to be shared.
+    #[inline]
+    pub(crate) fn add_node(&mut self, node: GreenNode) {
+        self.children.push(node.into());
+    }
+
+    /// Closes the most recently opened node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no node is open.
+    pub(crate) fn finish_node(&mut self) {
+        let (kind, first_child) = self.parents.pop().expect("finish_node called without a matching start_node");
+        #[cfg(feature = "instrumentation")]
+        if let Some(NodeHook(hook)) = &mut self.node_hook {
+            hook(kind, self.children.len() - first_child);
+        }
+        let node = GreenNode::new(kind, self.children.drain(first_child..).collect::<Vec<_>>());
+        self.children.push(node.into());
+    }
+
+    /// Returns a checkpoint at the current position, inside the current node.
+    #[inline]
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            children: self.children.len(),
+            parents: self.parents.len(),
+            first_child: self.current_first_child(),
+            ranged_diagnostics: self.ranged_diagnostics.len(),
+        }
+    }
+
+    /// Opens a node of `kind` whose children start with the elements added since `checkpoint`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node that was current at `checkpoint` has been finished since.
+    pub(crate) fn start_node_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        self.assert_checkpoint_in_current_node(checkpoint, "start_node_at");
+        self.parents.truncate(checkpoint.parents);
+        self.parents.push((kind, checkpoint.children));
+    }
+
+    /// Discards everything added since `checkpoint`: elements, nodes opened since (finished or not)
+    /// and diagnostics recorded by [`Self::diagnostic_at`].
+    ///
+    /// Meant for speculative parsing: take a checkpoint, try an alternative, and roll back if it fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node that was current at `checkpoint` has been finished since, as rolling back
+    /// past the node's first child would discard children of an enclosing node.
+    pub(crate) fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.assert_checkpoint_in_current_node(checkpoint, "rollback");
+        self.parents.truncate(checkpoint.parents);
+        self.children.truncate(checkpoint.children);
+        self.ranged_diagnostics.truncate(checkpoint.ranged_diagnostics);
+    }
+
+    /// Checks that the node current at `checkpoint` is still open, so that its first child is not past the checkpoint.
+    fn assert_checkpoint_in_current_node(&self, checkpoint: Checkpoint, operation: &str) {
+        let is_open = checkpoint.parents <= self.parents.len()
+            && checkpoint.children <= self.children.len()
+            && self.parents[..checkpoint.parents]
+                .last()
+                .is_none_or(|&(_, first_child)| first_child == checkpoint.first_child)
+            && self.parents[checkpoint.parents..]
+                .iter()
+                .all(|&(_, first_child)| first_child >= checkpoint.children);
+        assert!(is_open, "{operation} called with a checkpoint from a node that was finished since");
+    }
+
+    /// Registers `hook` to be called on every [`Self::finish_node`] with the node's kind and child count,
+    /// replacing any previous hook.
+    ///
+    /// Meant for profiling which productions dominate tree construction. Only available with the
+    /// `instrumentation` feature, so builds without it pay nothing.
+    #[cfg(feature = "instrumentation")]
+    pub(crate) fn on_node<F: FnMut(SyntaxKind, usize) + 'static>(&mut self, hook: F) {
+        self.node_hook = Some(NodeHook(Box::new(hook)));
+    }
+
+    /// Returns how many children have been added to the current node so far, or to the
+    /// top level when no node is open.
+    #[inline]
+    pub(crate) fn current_child_count(&self) -> usize {
+        self.children.len() - self.current_first_child()
+    }
+
+    /// Returns the kind of the last child added to the current node, or `None` if it has none yet.
+    #[inline]
+    pub(crate) fn last_child_kind(&self) -> Option<SyntaxKind> {
+        self.children[self.current_first_child()..].last().map(|child| child.kind())
+    }
+
+    #[inline]
+    fn current_first_child(&self) -> usize {
+        self.parents.last().map_or(0, |&(_, first_child)| first_child)
+    }
+
+    /// Records an error diagnostic covering `length` bytes at `start`, relative to the start of the current node.
+    ///
+    /// Green diagnostics carry no absolute position, so ranged diagnostics are kept aside and
+    /// returned as absolute ranges by [`Self::finish_with_diagnostics`]. The range is not checked
+    /// against the node, which may still grow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no node is open.
+    pub(crate) fn diagnostic_at(&mut self, start: u32, length: u32, kind: DiagnosticKind) {
+        let &(_, first_child) = self.parents.last().expect("diagnostic_at called without an open node");
+        let node_start: u32 = self.children[..first_child].iter().map(|child| child.full_width()).sum();
+        let start = node_start + start;
+        let diagnostic = GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str());
+        self.ranged_diagnostics.push((start..start + length, diagnostic));
+    }
+
+    /// Closes the current node like [`Self::finish_node`] and records an error diagnostic spanning it,
+    /// leading and trailing trivia excluded, as [`GreenNode::diagnostic_infos`] reports node diagnostics.
+    ///
+    /// Doing both in one call keeps the range in step with the node's final width, which a separate
+    /// [`Self::diagnostic_at`] issued before the last children were added would not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no node is open.
+    pub(crate) fn finish_node_with_diagnostic(&mut self, kind: DiagnosticKind) {
+        let &(_, first_child) = self.parents.last().expect("finish_node_with_diagnostic called without an open node");
+        let node_start: u32 = self.children[..first_child].iter().map(|child| child.full_width()).sum();
+        self.finish_node();
+
+        let node = self.children.last().expect("finish_node pushed the node");
+        let start = node_start + node.leading_trivia_width();
+        let diagnostic = GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str());
+        self.ranged_diagnostics.push((start..start + node.width(), diagnostic));
+    }
+
+    /// Completes the tree like [`Self::finish`], also returning the diagnostics recorded by
+    /// [`Self::diagnostic_at`] in recording order.
+    pub(crate) fn finish_with_diagnostics(mut self) -> (GreenNode, Vec<(Range<u32>, GreenDiagnostic)>) {
+        let diagnostics = std::mem::take(&mut self.ranged_diagnostics);
+        (self.finish(), diagnostics)
+    }
+
+    /// Completes the tree and returns its single root node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if nodes are still open or the builder does not hold exactly one root node, see
+    /// [`Self::try_finish`].
+    pub(crate) fn finish(self) -> GreenNode {
+        let (node, _) = self.try_finish().expect("finish called on an incomplete tree");
+        node
+    }
+
+    /// Completes the tree like [`Self::finish`], returning the diagnostics recorded by
+    /// [`Self::diagnostic_at`] in recording order, or why the calls did not form a single tree.
+    pub(crate) fn try_finish(mut self) -> Result<(GreenNode, Vec<DiagnosticInfo>), BuilderError> {
+        if !self.parents.is_empty() {
+            return Err(BuilderError::UnfinishedNodes(self.parents.iter().map(|&(kind, _)| kind).collect()));
+        }
+
+        match self.children.len() {
+            0 => return Err(BuilderError::EmptyTree),
+            1 => {}
+            count => return Err(BuilderError::MultipleRoots(count)),
+        }
+
+        let node = match self.children.pop() {
+            Some(GreenNodeElement::Node(node)) => node,
+            Some(element) => return Err(BuilderError::RootNotNode(element.kind())),
+            None => unreachable!("exactly one child is left"),
+        };
+        let diagnostics = self
+            .ranged_diagnostics
+            .into_iter()
+            .map(|(range, diagnostic)| DiagnosticInfo::new(range.start, range.end - range.start, diagnostic))
+            .collect();
+        Ok((node, diagnostics))
+    }
+
+    /// Completes the tree, wrapping the top-level elements under a synthetic `root_kind` node
+    /// unless they already form a single root node, and returns it with the diagnostics recorded
+    /// by [`Self::diagnostic_at`] like [`Self::try_finish`].
+    ///
+    /// Useful for fragments (e.g. a bare token stream) that have no natural enclosing node.
+    /// The synthetic root starts at offset 0, so the diagnostic ranges are the same either way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if nodes are still open.
+    pub(crate) fn finish_wrapped(mut self, root_kind: SyntaxKind) -> (GreenNode, Vec<DiagnosticInfo>) {
+        assert!(self.parents.is_empty(), "finish_wrapped called with unfinished nodes");
+        if !matches!(self.children.as_slice(), [GreenNodeElement::Node(_)]) {
+            let root = GreenNode::new(root_kind, std::mem::take(&mut self.children));
+            self.children.push(root.into());
+        }
+
+        self.try_finish().expect("a single root node is left")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNodeData, GreenToken, GreenTriviaData, Lexer};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_finish_when_nested_nodes_expect_tree_in_source_order() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+
+        let node = builder.finish();
+        assert_eq!(node.kind(), SyntaxKind::ArrayExpression);
+        assert_eq!(node.slot_count(), 3);
+        assert_eq!(node.slot(1).map(|slot| slot.kind()), Some(SyntaxKind::NullLiteralExpression));
+        assert_eq!(node.full_text(), b"[null]");
+    }
+
+    #[test]
+    fn test_add_node_when_subtree_spliced_expect_same_tree_as_built_from_scratch() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+        let from_scratch = builder.finish();
+
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+        let subtree = builder.finish();
+
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.add_node(subtree.clone());
+        assert_eq!(builder.current_child_count(), 2);
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+        let spliced = builder.finish();
+
+        assert_eq!(spliced, from_scratch);
+        assert_eq!(spliced.full_text(), b"[null]");
+        assert!(matches!(spliced.slot(1), Some(GreenNodeElement::Node(node)) if std::ptr::eq::<GreenNodeData>(&**node, &*subtree)));
+    }
+
+    #[test]
+    fn test_with_shared_cache_when_two_builders_add_same_trivia_expect_pointer_equal_trivia() {
+        let interner = TriviaInterner::new_shared();
+        let build = |text: &[u8]| {
+            let mut builder = GreenNodeBuilder::with_shared_cache(Arc::clone(&interner));
+            builder.trivia(SyntaxKind::WhitespaceTrivia, text);
+            builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+            builder.finish_wrapped(SyntaxKind::None).0
+        };
+        let trivia = |node: &GreenNode| match node.slot(0) {
+            Some(GreenNodeElement::Trivia(trivia)) => trivia.clone(),
+            slot => panic!("expected trivia, got {slot:?}"),
+        };
+
+        let (first, second, other) = (build(b" "), build(b" "), build(b"\t"));
+        let (first, second, other) = (trivia(&first), trivia(&second), trivia(&other));
+
+        assert!(std::ptr::eq::<GreenTriviaData>(&*first, &*second));
+        assert!(!std::ptr::eq::<GreenTriviaData>(&*first, &*other));
+        assert_eq!(interner.len(), 2);
+        let unshared = GreenNodeBuilder::new().new_trivia(SyntaxKind::WhitespaceTrivia, b" ");
+        assert!(!std::ptr::eq::<GreenTriviaData>(&*first, &*unshared));
+    }
+
+    #[test]
+    fn test_finish_wrapped_when_no_children_expect_empty_root() {
+        let (node, diagnostics) = GreenNodeBuilder::new().finish_wrapped(SyntaxKind::None);
+        assert_eq!(node.kind(), SyntaxKind::None);
+        assert_eq!(node.slot_count(), 0);
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn test_finish_wrapped_when_single_root_node_expect_node_unwrapped() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+
+        let (node, _) = builder.finish_wrapped(SyntaxKind::None);
+        assert_eq!(node.kind(), SyntaxKind::NullLiteralExpression);
+        assert_eq!(node.slot_count(), 1);
+    }
+
+    #[test]
+    fn test_finish_wrapped_when_single_top_level_token_expect_wrapped() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+
+        let (node, _) = builder.finish_wrapped(SyntaxKind::None);
+        assert_eq!(node.kind(), SyntaxKind::None);
+        assert_eq!(node.slot(0).map(|slot| slot.kind()), Some(SyntaxKind::NullKeyword));
+    }
+
+    #[test]
+    fn test_finish_wrapped_when_multiple_top_level_children_expect_wrapped_in_order() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.token(GreenToken::new(SyntaxKind::TrueKeyword));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+
+        let (node, _) = builder.finish_wrapped(SyntaxKind::None);
+        assert_eq!(node.kind(), SyntaxKind::None);
+        assert_eq!(node.slot_count(), 2);
+        assert_eq!(node.full_text(), b"truenull");
+    }
+
+    #[test]
+    fn test_finish_wrapped_when_ranged_diagnostic_recorded_expect_diagnostic_returned() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.token(GreenToken::new(SyntaxKind::TrueKeyword));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.diagnostic_at(1, 2, DiagnosticKind::UnrecognizedToken);
+        builder.finish_node();
+
+        let (node, diagnostics) = builder.finish_wrapped(SyntaxKind::None);
+        assert_eq!(node.kind(), SyntaxKind::None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].offset, diagnostics[0].length), (5, 2));
+        assert_eq!(diagnostics[0].diagnostic.kind(), DiagnosticKind::UnrecognizedToken);
+    }
+
+    #[test]
+    fn test_start_node_at_when_checkpoint_before_token_expect_token_wrapped() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        let checkpoint = builder.checkpoint();
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.start_node_at(checkpoint, SyntaxKind::NullLiteralExpression);
+        builder.finish_node();
+        builder.finish_node();
+
+        let node = builder.finish();
+        assert_eq!(node.slot(0).map(|slot| slot.kind()), Some(SyntaxKind::NullLiteralExpression));
+        assert_eq!(node.full_text(), b"null");
+    }
+
+    #[test]
+    fn test_rollback_when_alternative_fails_expect_children_nodes_and_diagnostics_discarded() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        let checkpoint = builder.checkpoint();
+
+        builder.start_node(SyntaxKind::DictionaryExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenDictToken));
+        builder.diagnostic_at(0, 2, DiagnosticKind::UnbalancedHexString);
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.rollback(checkpoint);
+
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+        let (node, diagnostics) = builder.finish_with_diagnostics();
+
+        assert_eq!(node.full_text(), b"[]");
+        assert_eq!(node.slot_count(), 2);
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "rollback called with a checkpoint from a node that was finished since")]
+    fn test_rollback_when_checkpoint_node_finished_expect_panic() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        let checkpoint = builder.checkpoint();
+        builder.finish_node();
+        builder.rollback(checkpoint);
+    }
+
+    #[test]
+    #[should_panic(expected = "rollback called with a checkpoint from a node that was finished since")]
+    fn test_rollback_when_checkpoint_node_finished_and_sibling_open_expect_panic() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        let checkpoint = builder.checkpoint();
+        builder.finish_node();
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.rollback(checkpoint);
+    }
+
+    #[test]
+    fn test_current_child_count_when_nested_nodes_expect_counts_for_current_frame() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        assert_eq!(
+            (builder.current_child_count(), builder.last_child_kind()),
+            (1, Some(SyntaxKind::OpenBracketToken))
+        );
+
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        assert_eq!((builder.current_child_count(), builder.last_child_kind()), (0, None));
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        assert_eq!((builder.current_child_count(), builder.last_child_kind()), (1, Some(SyntaxKind::NullKeyword)));
+        builder.finish_node();
+
+        assert_eq!(
+            (builder.current_child_count(), builder.last_child_kind()),
+            (2, Some(SyntaxKind::NullLiteralExpression))
+        );
+        builder.finish_node();
+        assert_eq!(
+            (builder.current_child_count(), builder.last_child_kind()),
+            (1, Some(SyntaxKind::ArrayExpression))
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_at_when_inside_nested_node_expect_range_relative_to_that_node() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.diagnostic_at(1, 2, DiagnosticKind::UnrecognizedToken);
+        builder.finish_node();
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+
+        let (node, diagnostics) = builder.finish_with_diagnostics();
+        assert_eq!(node.full_text(), b"[null]");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, 2..4);
+        assert_eq!(diagnostics[0].1.kind(), DiagnosticKind::UnrecognizedToken);
+    }
+
+    #[test]
+    fn test_finish_node_with_diagnostic_when_nested_node_expect_range_equal_to_node_span() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.token(GreenToken::new(SyntaxKind::TrueKeyword));
+        builder.finish_node_with_diagnostic(DiagnosticKind::UnrecognizedToken);
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+
+        let (node, diagnostics) = builder.finish_with_diagnostics();
+        assert_eq!(node.full_text(), b"[[true]");
+        assert_eq!(node.slot(1).map(|slot| slot.kind()), Some(SyntaxKind::ArrayExpression));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, 1..6);
+        assert_eq!(diagnostics[0].1.kind(), DiagnosticKind::UnrecognizedToken);
+    }
+
+    #[test]
+    fn test_finish_node_with_diagnostic_when_outer_trivia_expect_range_without_trivia() {
+        let mut lexer = Lexer::new(b"[ true false ]");
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(lexer.next_token());
+        builder.start_node(SyntaxKind::None);
+        builder.token(lexer.next_token());
+        builder.token(lexer.next_token());
+        builder.finish_node_with_diagnostic(DiagnosticKind::UnrecognizedToken);
+        builder.token(lexer.next_token());
+        builder.finish_node();
+
+        let (node, diagnostics) = builder.finish_with_diagnostics();
+        assert_eq!(node.full_text(), b"[ true false ]");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, 2..12);
+    }
+
+    #[test]
+    #[cfg(feature = "instrumentation")]
+    fn test_on_node_when_nodes_finished_expect_hook_called_once_per_node_with_kind_and_child_count() {
+        let calls = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut builder = GreenNodeBuilder::new();
+        let recorded = calls.clone();
+        builder.on_node(move |kind, child_count| recorded.borrow_mut().push((kind, child_count)));
+
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+        builder.finish_node();
+        builder.token(GreenToken::new(SyntaxKind::CloseBracketToken));
+        builder.finish_node();
+        builder.finish();
+
+        assert_eq!(*calls.borrow(), vec![(SyntaxKind::NullLiteralExpression, 1), (SyntaxKind::ArrayExpression, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "without an open node")]
+    fn test_diagnostic_at_when_no_open_node_expect_panic() {
+        GreenNodeBuilder::new().diagnostic_at(0, 1, DiagnosticKind::UnrecognizedToken);
+    }
+
+    #[test]
+    #[should_panic(expected = "unfinished nodes")]
+    fn test_finish_wrapped_when_node_left_open_expect_panic() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.finish_wrapped(SyntaxKind::None);
+    }
+
+    #[test]
+    fn test_try_finish_when_single_root_expect_node_and_ranged_diagnostics() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.token(GreenToken::new(SyntaxKind::OpenBracketToken));
+        builder.diagnostic_at(0, 1, DiagnosticKind::UnmatchedOpeningDelimiter);
+        builder.finish_node();
+
+        let (node, diagnostics) = builder.try_finish().expect("balanced builder");
+        assert_eq!(node.full_text(), b"[");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].offset, diagnostics[0].length), (0, 1));
+        assert_eq!(diagnostics[0].diagnostic.kind(), DiagnosticKind::UnmatchedOpeningDelimiter);
+    }
+
+    #[test]
+    fn test_try_finish_when_nodes_left_open_expect_unfinished_nodes_outermost_first() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.start_node(SyntaxKind::NullLiteralExpression);
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+
+        let error = builder.try_finish().expect_err("nodes are open");
+        assert_eq!(
+            error,
+            BuilderError::UnfinishedNodes(vec![SyntaxKind::ArrayExpression, SyntaxKind::NullLiteralExpression])
+        );
+        assert_eq!(
+            error.to_string(),
+            "2 unfinished node(s), outermost first: [ArrayExpression, NullLiteralExpression]"
+        );
+    }
+
+    #[test]
+    fn test_try_finish_when_empty_builder_expect_empty_tree() {
+        assert_eq!(GreenNodeBuilder::new().try_finish().expect_err("nothing was built"), BuilderError::EmptyTree);
+    }
+
+    #[test]
+    fn test_try_finish_when_several_top_level_elements_expect_multiple_roots() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.token(GreenToken::new(SyntaxKind::TrueKeyword));
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+
+        assert_eq!(builder.try_finish().expect_err("two roots"), BuilderError::MultipleRoots(2));
+    }
+
+    #[test]
+    fn test_try_finish_when_root_is_token_expect_root_not_node() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.token(GreenToken::new(SyntaxKind::NullKeyword));
+
+        assert_eq!(
+            builder.try_finish().expect_err("token root"),
+            BuilderError::RootNotNode(SyntaxKind::NullKeyword)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "finish called on an incomplete tree: UnfinishedNodes([ArrayExpression])")]
+    fn test_finish_when_node_left_open_expect_panic_with_error() {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::ArrayExpression);
+        builder.finish();
+    }
+}
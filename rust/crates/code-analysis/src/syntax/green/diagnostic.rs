@@ -2,7 +2,8 @@ use std::{
     borrow::Borrow,
     fmt,
     mem::{self, ManuallyDrop},
-    ops, ptr,
+    ops::{self, Range},
+    ptr,
 };
 
 use crate::arc::{Arc, HeaderSlice, ThinArc};
@@ -27,6 +28,7 @@ pub(crate) enum DiagnosticSeverity {
 struct GreenDiagnosticHead {
     kind: DiagnosticKind,         // 2 bytes (`repr(u16)`)
     severity: DiagnosticSeverity, // 1 byte (`repr(u8)`)
+    range: Option<(u32, u32)>,    // 12 bytes (start and end, relative to the element's text)
     _c: Count<GreenDiagnostic>,   // 0 bytes
 }
 
@@ -66,11 +68,18 @@ impl GreenDiagnosticData {
     pub fn message_len(&self) -> u32 {
         self.message().len() as u32
     }
+
+    /// Part of the text of the element this diagnostic is attached to, relative to the start of
+    /// that text, or `None` if it applies to the whole element.
+    #[inline]
+    pub fn range(&self) -> Option<Range<u32>> {
+        self.data.header.range.map(|(start, end)| start..end)
+    }
 }
 
 impl PartialEq for GreenDiagnosticData {
     fn eq(&self, other: &Self) -> bool {
-        self.kind() == other.kind() && self.severity() == other.severity() && self.message() == other.message()
+        self.kind() == other.kind() && self.severity() == other.severity() && self.range() == other.range() && self.message() == other.message()
     }
 }
 
@@ -87,6 +96,7 @@ impl fmt::Debug for GreenDiagnosticData {
             .field("code", &self.code())
             .field("severity", &self.severity())
             .field("message", &self.message())
+            .field("range", &self.range())
             .finish()
     }
 }
@@ -113,12 +123,25 @@ impl GreenDiagnostic {
     /// Creates new diagnostic with given kind, severity, and message.
     #[inline]
     pub fn new(kind: DiagnosticKind, severity: DiagnosticSeverity, message: &str) -> GreenDiagnostic {
+        Self::new_impl(kind, severity, message, None)
+    }
+
+    /// Creates a diagnostic like [`Self::new`] that applies to `range` of its element's text rather
+    /// than all of it, see [`GreenDiagnosticData::range`].
+    #[inline]
+    pub fn new_with_range(kind: DiagnosticKind, severity: DiagnosticSeverity, message: &str, range: Range<u32>) -> GreenDiagnostic {
+        Self::new_impl(kind, severity, message, Some((range.start, range.end)))
+    }
+
+    #[inline]
+    fn new_impl(kind: DiagnosticKind, severity: DiagnosticSeverity, message: &str, range: Option<(u32, u32)>) -> GreenDiagnostic {
         let bytes = message.as_bytes();
         assert!(bytes.len() <= u32::MAX as usize, "diagnostic message length exceeds u32::MAX");
 
         let head = GreenDiagnosticHead {
             kind,
             severity,
+            range,
             _c: Count::new(),
         };
         let ptr = ThinArc::from_header_and_iter(head, bytes.iter().copied());
@@ -225,21 +248,21 @@ mod memory_layout_tests {
 
     #[test]
     fn test_green_diagnostic_head_memory_layout() {
-        assert_eq!(std::mem::size_of::<GreenDiagnosticHead>(), 4);
-        assert_eq!(std::mem::align_of::<GreenDiagnosticHead>(), 2);
+        assert_eq!(std::mem::size_of::<GreenDiagnosticHead>(), 16);
+        assert_eq!(std::mem::align_of::<GreenDiagnosticHead>(), 4);
     }
 
     #[test]
     fn test_green_diagnostic_data_memory_layout() {
         #[cfg(target_pointer_width = "64")]
         {
-            assert_eq!(std::mem::size_of::<GreenDiagnosticData>(), 16);
+            assert_eq!(std::mem::size_of::<GreenDiagnosticData>(), 24);
             assert_eq!(std::mem::align_of::<GreenDiagnosticData>(), 8);
         }
 
         #[cfg(target_pointer_width = "32")]
         {
-            assert_eq!(std::mem::size_of::<GreenDiagnosticData>(), 8);
+            assert_eq!(std::mem::size_of::<GreenDiagnosticData>(), 20);
             assert_eq!(std::mem::align_of::<GreenDiagnosticData>(), 4);
         }
     }
@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use crate::{GreenDiagnostic, GreenNode, GreenNodeElement};
+
+/// A diagnostic together with the absolute byte span it applies to.
+///
+/// Green diagnostics carry no absolute position; this pairs one with the span of the element
+/// it was attached to (see [`GreenNode::diagnostic_infos`]) or the range recorded by the builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiagnosticInfo {
+    pub(crate) offset: u32,
+    pub(crate) length: u32,
+    pub(crate) diagnostic: GreenDiagnostic,
+}
+
+impl DiagnosticInfo {
+    #[inline]
+    pub(crate) fn new(offset: u32, length: u32, diagnostic: GreenDiagnostic) -> DiagnosticInfo {
+        DiagnosticInfo { offset, length, diagnostic }
+    }
+
+    /// Returns the exclusive end of the span.
+    #[inline]
+    pub(crate) fn end(&self) -> u32 {
+        self.offset + self.length
+    }
+
+    /// Returns `true` if `offset` falls inside the span. An empty span contains only its own offset.
+    #[inline]
+    pub(crate) fn contains_offset(&self, offset: u32) -> bool {
+        match self.length {
+            0 => offset == self.offset,
+            _ => (self.offset..self.end()).contains(&offset),
+        }
+    }
+
+    /// Sorts `diagnostics` by `(offset, length)`, keeping the recording order of equal spans.
+    pub(crate) fn sort(diagnostics: &mut [DiagnosticInfo]) {
+        diagnostics.sort_by_key(|info| (info.offset, info.length));
+    }
+
+    /// Sorts `diagnostics` and coalesces diagnostics of the same kind and severity whose spans
+    /// overlap or touch into one covering both. The message of the earliest one is kept.
+    pub(crate) fn merge_adjacent(mut diagnostics: Vec<DiagnosticInfo>) -> Vec<DiagnosticInfo> {
+        Self::sort(&mut diagnostics);
+        let mut merged: Vec<DiagnosticInfo> = Vec::with_capacity(diagnostics.len());
+        for info in diagnostics {
+            // Only the nearest same-kind diagnostic can still reach `info`; others may sit in between.
+            let open = merged
+                .iter_mut()
+                .rev()
+                .find(|last| last.diagnostic.kind() == info.diagnostic.kind() && last.diagnostic.severity() == info.diagnostic.severity());
+            match open {
+                Some(last) if info.offset <= last.end() => last.length = last.end().max(info.end()) - last.offset,
+                _ => merged.push(info),
+            }
+        }
+
+        merged
+    }
+}
+
+impl From<(Range<u32>, GreenDiagnostic)> for DiagnosticInfo {
+    #[inline]
+    fn from((range, diagnostic): (Range<u32>, GreenDiagnostic)) -> Self {
+        DiagnosticInfo::new(range.start, range.end.saturating_sub(range.start), diagnostic)
+    }
+}
+
+impl GreenNode {
+    /// Returns every diagnostic in the tree, each paired with the absolute span of its element.
+    ///
+    /// Node and token diagnostics span the element without its outer trivia, trivia diagnostics
+    /// span the trivia piece, unless the diagnostic has a [`crate::GreenDiagnosticData::range`]
+    /// within that span. The result is in pre-order; use [`DiagnosticInfo::sort`] or
+    /// [`DiagnosticInfo::merge_adjacent`] to present it by position.
+    pub(crate) fn diagnostic_infos(&self) -> Vec<DiagnosticInfo> {
+        let mut infos = Vec::new();
+        collect_node(self, 0, &mut infos);
+        infos
+    }
+}
+
+fn collect_node(node: &GreenNode, offset: u32, infos: &mut Vec<DiagnosticInfo>) {
+    push_all(node.diagnostics(), offset + node.leading_trivia_width(), node.width(), infos);
+    let mut position = offset;
+    for slot in node.slots() {
+        collect_element(slot, position, infos);
+        position += slot.full_width();
+    }
+}
+
+fn collect_element(element: &GreenNodeElement, offset: u32, infos: &mut Vec<DiagnosticInfo>) {
+    match element {
+        GreenNodeElement::Node(node) => collect_node(node, offset, infos),
+        GreenNodeElement::Token(token) => {
+            let text_offset = offset + token.leading_trivia_width();
+            push_all(token.diagnostics(), text_offset, token.width(), infos);
+            collect_trivia_list(token.leading_trivia(), offset, infos);
+            collect_trivia_list(token.trailing_trivia(), text_offset + token.width(), infos);
+        }
+        GreenNodeElement::Trivia(trivia) => push_all(trivia.diagnostics(), offset, trivia.width() as u32, infos),
+    }
+}
+
+#[inline]
+fn collect_trivia_list(list: Option<GreenNode>, offset: u32, infos: &mut Vec<DiagnosticInfo>) {
+    if let Some(list) = list {
+        collect_node(&list, offset, infos);
+    }
+}
+
+#[inline]
+fn push_all(diagnostics: Option<Vec<GreenDiagnostic>>, offset: u32, length: u32, infos: &mut Vec<DiagnosticInfo>) {
+    infos.extend(diagnostics.into_iter().flatten().map(|diagnostic| match diagnostic.range() {
+        Some(range) => DiagnosticInfo::new(offset + range.start, range.end - range.start, diagnostic),
+        None => DiagnosticInfo::new(offset, length, diagnostic),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DiagnosticKind, DiagnosticSeverity, SyntaxKind, tree};
+    use pretty_assertions::assert_eq;
+
+    fn error(kind: DiagnosticKind) -> GreenDiagnostic {
+        GreenDiagnostic::new(kind, DiagnosticSeverity::Error, kind.as_str())
+    }
+
+    fn spans(infos: &[DiagnosticInfo]) -> Vec<(u32, u32, DiagnosticKind)> {
+        infos.iter().map(|info| (info.offset, info.length, info.diagnostic.kind())).collect()
+    }
+
+    #[test]
+    fn test_diagnostic_infos_when_nested_token_and_node_diagnostics_expect_absolute_spans() {
+        let inner = tree! {
+            SyntaxKind::ArrayExpression => {
+                (SyntaxKind::OpenBracketToken, b"["),
+                @diagnostic(DiagnosticSeverity::Error, DiagnosticKind::InvalidHexEscapeInName, "Invalid hex escape in name"),
+                (SyntaxKind::NameLiteralToken) => {
+                    trivia(SyntaxKind::WhitespaceTrivia, b" "),
+                    text(b"/A#G0")
+                }
+            }
+        };
+        let unmatched = error(DiagnosticKind::UnmatchedOpeningDelimiter);
+        let inner = GreenNode::new_with_diagnostic(SyntaxKind::ArrayExpression, inner.slots().to_vec(), vec![unmatched]);
+        let root = tree! {
+            SyntaxKind::None => {
+                @diagnostic(DiagnosticSeverity::Error, DiagnosticKind::UnbalancedHexString, "Unbalanced hex string"),
+                (SyntaxKind::HexStringLiteralToken, b"<AB")
+            }
+        };
+        let root = GreenNode::new(SyntaxKind::None, [root.slots()[0].clone(), GreenNodeElement::Node(inner)]);
+
+        assert_eq!(root.full_text(), b"<AB[ /A#G0");
+        assert_eq!(
+            spans(&root.diagnostic_infos()),
+            vec![
+                (0, 3, DiagnosticKind::UnbalancedHexString),
+                (3, 7, DiagnosticKind::UnmatchedOpeningDelimiter),
+                (5, 5, DiagnosticKind::InvalidHexEscapeInName),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_infos_when_no_diagnostics_expect_empty() {
+        let root = tree! {
+            SyntaxKind::None => {
+                (SyntaxKind::NullKeyword, b"null")
+            }
+        };
+
+        assert_eq!(root.diagnostic_infos(), vec![]);
+    }
+
+    #[test]
+    fn test_sort_when_unordered_expect_by_offset_then_length() {
+        let mut infos = vec![
+            DiagnosticInfo::new(4, 2, error(DiagnosticKind::UnrecognizedToken)),
+            DiagnosticInfo::new(0, 5, error(DiagnosticKind::UnbalancedHexString)),
+            DiagnosticInfo::new(0, 1, error(DiagnosticKind::UnrecognizedToken)),
+        ];
+        DiagnosticInfo::sort(&mut infos);
+
+        assert_eq!(
+            spans(&infos),
+            vec![
+                (0, 1, DiagnosticKind::UnrecognizedToken),
+                (0, 5, DiagnosticKind::UnbalancedHexString),
+                (4, 2, DiagnosticKind::UnrecognizedToken),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_adjacent_when_same_kind_touching_or_overlapping_expect_coalesced() {
+        let infos = vec![
+            DiagnosticInfo::new(6, 2, error(DiagnosticKind::UnrecognizedToken)),
+            DiagnosticInfo::new(0, 3, error(DiagnosticKind::UnrecognizedToken)),
+            DiagnosticInfo::new(2, 1, error(DiagnosticKind::UnbalancedHexString)),
+            DiagnosticInfo::new(3, 2, error(DiagnosticKind::UnrecognizedToken)),
+            DiagnosticInfo::new(12, 1, error(DiagnosticKind::UnrecognizedToken)),
+        ];
+
+        assert_eq!(
+            spans(&DiagnosticInfo::merge_adjacent(infos)),
+            vec![
+                (0, 5, DiagnosticKind::UnrecognizedToken),
+                (2, 1, DiagnosticKind::UnbalancedHexString),
+                (6, 2, DiagnosticKind::UnrecognizedToken),
+                (12, 1, DiagnosticKind::UnrecognizedToken),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_adjacent_when_same_kind_different_severity_expect_kept_apart() {
+        let kind = DiagnosticKind::UnrecognizedToken;
+        let infos = vec![
+            DiagnosticInfo::new(0, 2, error(kind)),
+            DiagnosticInfo::new(1, 2, GreenDiagnostic::new(kind, DiagnosticSeverity::Warning, kind.as_str())),
+        ];
+
+        assert_eq!(DiagnosticInfo::merge_adjacent(infos).len(), 2);
+    }
+
+    #[test]
+    fn test_contains_offset_when_span_and_empty_span_expect_half_open_and_own_offset() {
+        let info = DiagnosticInfo::new(2, 3, error(DiagnosticKind::UnrecognizedToken));
+        assert_eq!(
+            (1..7).map(|offset| info.contains_offset(offset)).collect::<Vec<_>>(),
+            vec![false, true, true, true, false, false]
+        );
+
+        let empty = DiagnosticInfo::new(4, 0, error(DiagnosticKind::UnrecognizedToken));
+        assert_eq!(
+            (empty.contains_offset(3), empty.contains_offset(4), empty.contains_offset(5)),
+            (false, true, false)
+        );
+    }
+
+    #[test]
+    fn test_from_when_builder_range_expect_offset_and_length() {
+        let info = DiagnosticInfo::from((3..7, error(DiagnosticKind::UnrecognizedToken)));
+        assert_eq!((info.offset, info.length, info.end()), (3, 4, 7));
+    }
+}
@@ -1,12 +1,15 @@
 use std::{
     borrow::Borrow,
     fmt,
+    hash::BuildHasherDefault,
+    io,
     mem::{self, ManuallyDrop},
     ops::{self},
     ptr,
 };
 
 use countme::Count;
+use rustc_hash::FxHasher;
 
 use crate::{
     GreenDiagnostic, GreenFlags, GreenNodeElement, GreenNodeElementRef, GreenTokenElement, GreenTokenElementRef, GreenTriviaData, SyntaxKind,
@@ -14,6 +17,7 @@ use crate::{
 };
 use crate::{GreenTrivia, syntax::green::diagnostics};
 
+type HashMap<K, V> = hashbrown::HashMap<K, V, BuildHasherDefault<FxHasher>>;
 type Repr = HeaderSlice<GreenNodeHead, [GreenNodeElement]>;
 type ReprThin = HeaderSlice<GreenNodeHead, [GreenNodeElement; 0]>;
 
@@ -26,6 +30,25 @@ struct GreenNodeHead {
     _c: Count<GreenNode>,
 }
 
+/// Line ending emitted for end-of-line trivia by [`GreenNodeData::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EolStyle {
+    /// Line feed (`\n`).
+    Lf,
+    /// Carriage return followed by line feed (`\r\n`).
+    CrLf,
+}
+
+impl EolStyle {
+    #[inline]
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            EolStyle::Lf => b"\n",
+            EolStyle::CrLf => b"\r\n",
+        }
+    }
+}
+
 #[repr(transparent)]
 pub struct GreenNodeData {
     data: ReprThin,
@@ -134,7 +157,14 @@ impl GreenNodeData {
         !self.flags().contains(GreenFlags::IS_NOT_MISSING)
     }
 
-    /// Returns the node's text as a byte vector.
+    /// Returns the node's text as a byte vector, see [`Self::write_text_to`].
+    fn write_to(&self, leading: bool, trailing: bool) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.full_width() as usize);
+        self.write_text_to(leading, trailing, &mut output).expect("writing to a Vec cannot fail");
+        output
+    }
+
+    /// Writes the node's text to `writer`, token by token, without collecting it first.
     ///
     /// Similar to Roslyn's WriteTo implementation, uses an explicit stack to avoid
     /// stack overflow on deeply nested structures.
@@ -142,46 +172,56 @@ impl GreenNodeData {
     /// # Parameters
     /// * `leading` - If true, include the first node's leading trivia
     /// * `trailing` - If true, include the last node's trailing trivia
-    fn write_to(&self, leading: bool, trailing: bool) -> Vec<u8> {
-        fn process_stack(output: &mut Vec<u8>, stack: &mut Vec<(GreenNodeElementRef<'_>, bool, bool)>) {
-            while let Some((item, current_leading, current_trailing)) = stack.pop() {
-                match item {
-                    GreenNodeElementRef::Token(token_data) => {
-                        output.extend_from_slice(&token_data.write_to(current_leading, current_trailing));
+    pub(crate) fn write_text_to<W: io::Write>(&self, leading: bool, trailing: bool, writer: &mut W) -> io::Result<()> {
+        // Explicit stack to avoid recursion on deeply nested trees.
+        let mut stack: Vec<(GreenNodeElementRef<'_>, bool, bool)> = Vec::with_capacity(64);
+
+        // Seed with this node itself; processing will drill into its slots.
+        stack.push((GreenNodeElementRef::Node(self), leading, trailing));
+
+        while let Some((item, current_leading, current_trailing)) = stack.pop() {
+            match item {
+                GreenNodeElementRef::Token(token_data) => {
+                    if current_leading && let Some(leading_trivia) = token_data.leading_trivia() {
+                        leading_trivia.write_text_to(true, true, writer)?;
                     }
-                    GreenNodeElementRef::Trivia(trivia_data) => {
-                        output.extend_from_slice(trivia_data.text());
+                    writer.write_all(token_data.text())?;
+                    if current_trailing && let Some(trailing_trivia) = token_data.trailing_trivia() {
+                        trailing_trivia.write_text_to(true, true, writer)?;
+                    }
+                }
+                GreenNodeElementRef::Trivia(trivia_data) => {
+                    writer.write_all(trivia_data.text())?;
+                }
+                GreenNodeElementRef::Node(node_data) => {
+                    let slots = node_data.slots();
+                    if slots.is_empty() {
+                        continue;
                     }
-                    GreenNodeElementRef::Node(node_data) => {
-                        let slots = node_data.slots();
-                        if slots.is_empty() {
-                            continue;
-                        }
 
-                        let first_index = 0;
-                        let last_index = slots.len() - 1;
-
-                        // Push children in reverse so they are processed in forward order.
-                        for i in (first_index..=last_index).rev() {
-                            let child = &slots[i];
-                            let is_first = i == first_index;
-                            let is_last = i == last_index;
-                            let include_leading = current_leading || !is_first;
-                            let include_trailing = current_trailing || !is_last;
-
-                            match child {
-                                GreenNodeElement::Node(node) => {
-                                    let node_data: &GreenNodeData = node;
-                                    stack.push((GreenNodeElementRef::Node(node_data), include_leading, include_trailing));
-                                }
-                                GreenNodeElement::Token(token) => {
-                                    let token_data: GreenTokenElementRef = token.as_deref();
-                                    stack.push((GreenNodeElementRef::Token(token_data), include_leading, include_trailing));
-                                }
-                                GreenNodeElement::Trivia(trivia) => {
-                                    let trivia_data: &GreenTriviaData = trivia;
-                                    stack.push((GreenNodeElementRef::Trivia(trivia_data), include_leading, include_trailing));
-                                }
+                    let first_index = 0;
+                    let last_index = slots.len() - 1;
+
+                    // Push children in reverse so they are processed in forward order.
+                    for i in (first_index..=last_index).rev() {
+                        let child = &slots[i];
+                        let is_first = i == first_index;
+                        let is_last = i == last_index;
+                        let include_leading = current_leading || !is_first;
+                        let include_trailing = current_trailing || !is_last;
+
+                        match child {
+                            GreenNodeElement::Node(node) => {
+                                let node_data: &GreenNodeData = node;
+                                stack.push((GreenNodeElementRef::Node(node_data), include_leading, include_trailing));
+                            }
+                            GreenNodeElement::Token(token) => {
+                                let token_data: GreenTokenElementRef = token.as_deref();
+                                stack.push((GreenNodeElementRef::Token(token_data), include_leading, include_trailing));
+                            }
+                            GreenNodeElement::Trivia(trivia) => {
+                                let trivia_data: &GreenTriviaData = trivia;
+                                stack.push((GreenNodeElementRef::Trivia(trivia_data), include_leading, include_trailing));
                             }
                         }
                     }
@@ -189,18 +229,299 @@ impl GreenNodeData {
             }
         }
 
-        let mut output = Vec::new();
+        Ok(())
+    }
 
-        // Explicit stack to avoid recursion on deeply nested trees.
-        let mut stack: Vec<(GreenNodeElementRef<'_>, bool, bool)> = Vec::with_capacity(64);
+    /// Returns `true` if both nodes have the same kind and byte-identical full text, trivia included.
+    ///
+    /// Stricter than `==`, which compares structure and widths but not the trivia bytes themselves.
+    /// Use it to assert that a rebuilt tree round-trips exactly. A node shared by both sides is
+    /// equal without comparing its text.
+    #[inline]
+    pub fn eq_exact(&self, other: &Self) -> bool {
+        ptr::eq(self, other) || (self.kind() == other.kind() && self.full_width() == other.full_width() && self.full_text() == other.full_text())
+    }
 
-        // Seed with this node itself; processing will drill into its slots.
-        stack.push((GreenNodeElementRef::Node(self), leading, trailing));
+    /// Returns a copy of this node with the child `old`, starting at `offset` relative to this node,
+    /// replaced by `new`.
+    ///
+    /// Children of `List` slots are searched too, matching how red nodes flatten lists. Every other
+    /// slot is shared with this node, and node diagnostics are kept. Returns `None` if no such child exists.
+    pub(crate) fn replace_child(&self, offset: u32, old: &GreenNodeElement, new: GreenNodeElement) -> Option<GreenNode> {
+        let mut start = 0u32;
+        for (index, slot) in self.slots().iter().enumerate() {
+            let end = start + slot.full_width();
+            if (start..=end).contains(&offset) {
+                if offset == start && slot == old {
+                    return Some(self.with_slot(index, new));
+                }
+                if let GreenNodeElement::Node(list) = slot
+                    && slot.is_list()
+                    && let Some(new_list) = list.replace_child(offset - start, old, new.clone())
+                {
+                    return Some(self.with_slot(index, new_list.into()));
+                }
+            }
+            start = end;
+        }
+        None
+    }
+
+    fn with_slot(&self, index: usize, new: GreenNodeElement) -> GreenNode {
+        let mut slots = self.slots().to_vec();
+        slots[index] = new;
+        let diagnostics = self.to_owned().diagnostics().unwrap_or_default();
+        GreenNode::new_with_diagnostic(self.kind(), slots, diagnostics)
+    }
+
+    /// Returns each trivia piece of this trivia list with its kind, bytes and offset within the run.
+    ///
+    /// Meant for the `List` nodes holding a token's leading or trailing trivia, so a formatter can
+    /// address one comment in a multi-piece run by position. Non-trivia slots are skipped but still
+    /// advance the offset.
+    pub(crate) fn pieces_with_offsets(&self) -> impl Iterator<Item = (SyntaxKind, &[u8], u32)> {
+        self.slots()
+            .iter()
+            .scan(0u32, |offset, slot| {
+                let start = *offset;
+                *offset += slot.full_width();
+                Some((slot, start))
+            })
+            .filter_map(|(slot, start)| match slot {
+                GreenNodeElement::Trivia(trivia) => Some((trivia.kind(), trivia.text(), start)),
+                _ => None,
+            })
+    }
+
+    /// Returns the smallest node whose full span, relative to this node, covers `range`.
+    ///
+    /// `List` nodes are looked through but never returned, matching how red nodes flatten lists.
+    /// Green nodes carry no absolute position, so the returned node can be used as a standalone
+    /// tree rooted at offset 0. Returns `None` if `range` extends past this node.
+    pub(crate) fn subtree_covering(&self, range: ops::Range<u32>) -> Option<GreenNode> {
+        if range.start > range.end || range.end > self.full_width() {
+            return None;
+        }
+
+        let mut covering = self.to_owned();
+        let mut current = self.to_owned();
+        let mut offset = 0u32;
+        loop {
+            let mut start = offset;
+            let mut next = None;
+            for slot in current.slots() {
+                let end = start + slot.full_width();
+                if let GreenNodeElement::Node(child) = slot
+                    && start <= range.start
+                    && range.end <= end
+                {
+                    next = Some((child.clone(), start));
+                    break;
+                }
+                start = end;
+            }
+
+            let Some((child, child_offset)) = next else {
+                return Some(covering);
+            };
+            if child.kind() != SyntaxKind::List {
+                covering = child.clone();
+            }
+            current = child;
+            offset = child_offset;
+        }
+    }
+
+    /// Returns the slot indices leading from this node to the deepest token or trivia covering `offset`.
+    ///
+    /// `offset` is relative to this node's full text. At the boundary between two slots the earlier
+    /// one is preferred, so the offset at the end of a token maps to that token. `List` slots are a
+    /// level of the path like any other node, and childless nodes are skipped. Returns `None` if
+    /// `offset` is past this node or no terminal covers it.
+    pub(crate) fn child_path_at_offset(&self, offset: u32) -> Option<Vec<usize>> {
+        if offset > self.full_width() {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        let mut current = self;
+        let mut start = 0u32;
+        loop {
+            let (index, slot) = current.slots().iter().enumerate().find(|(_, slot)| {
+                let end = start + slot.full_width();
+                let covers = offset <= end;
+                if !covers {
+                    start = end;
+                }
+                covers && !matches!(slot, GreenNodeElement::Node(node) if node.slot_count() == 0)
+            })?;
+
+            path.push(index);
+            match slot {
+                GreenNodeElement::Node(node) => current = node,
+                GreenNodeElement::Token(_) | GreenNodeElement::Trivia(_) => return Some(path),
+            }
+        }
+    }
+
+    /// Returns the smallest element whose full span, relative to this node, contains `range`.
+    ///
+    /// Descends through nodes, `List` included, down to tokens and trivia; a range spanning several
+    /// children yields their parent, and this node itself if it spans several of its own slots. An
+    /// empty range on a slot boundary belongs to the earlier slot. Returns `None`, rather than panicking
+    /// like red-tree lookups, if `range` is inverted or extends past this node.
+    pub(crate) fn covering_slot(&self, range: ops::Range<u32>) -> Option<GreenNodeElement> {
+        if range.start > range.end || range.end > self.full_width() {
+            return None;
+        }
+
+        let mut current = self;
+        let mut start = 0u32;
+        loop {
+            let mut next = None;
+            for slot in current.slots() {
+                let end = start + slot.full_width();
+                if start <= range.start && range.end <= end {
+                    next = Some(slot);
+                    break;
+                }
+                if end > range.start {
+                    break;
+                }
+                start = end;
+            }
+
+            match next {
+                Some(GreenNodeElement::Node(node)) => current = node,
+                Some(slot) => return Some(slot.clone()),
+                None => return Some(GreenNodeElement::Node(current.to_owned())),
+            }
+        }
+    }
+
+    /// Rebuilds the tree, replacing each token for which `f` returns `Some`.
+    ///
+    /// Returning `None` keeps the token. Subtrees without replaced tokens are shared with this tree,
+    /// node diagnostics are kept, and if nothing changes the result is this node itself.
+    pub(crate) fn map_tokens(&self, mut f: impl FnMut(&GreenTokenElement) -> Option<GreenTokenElement>) -> GreenNode {
+        self.map_tokens_inner(&mut f).unwrap_or_else(|| self.to_owned())
+    }
+
+    /// Rebuilds the tree, replacing every token of `kind` with the result of `f`.
+    ///
+    /// A convenience over [`Self::map_tokens`]: other tokens and untouched subtrees are shared,
+    /// and node widths follow the replacement texts.
+    pub(crate) fn replace_tokens_of_kind(&self, kind: SyntaxKind, mut f: impl FnMut(&GreenTokenElement) -> GreenTokenElement) -> GreenNode {
+        self.map_tokens(|token| (token.kind() == kind).then(|| f(token)))
+    }
+
+    /// Rebuilds the whole tree with `f` applied to every node and token kind, e.g. to move a tree into
+    /// another kind space.
+    ///
+    /// Token text, trivia and node diagnostics are kept, so structure and text are unchanged. Tokens
+    /// whose kind `f` changes lose their diagnostics, as with [`GreenTokenElement::with_kind`].
+    pub(crate) fn remap_kinds(&self, f: impl Fn(SyntaxKind) -> SyntaxKind) -> GreenNode {
+        self.remap_kinds_inner(&f)
+    }
+
+    fn remap_kinds_inner(&self, f: &impl Fn(SyntaxKind) -> SyntaxKind) -> GreenNode {
+        let slots: Vec<GreenNodeElement> = self
+            .slots()
+            .iter()
+            .map(|slot| match slot {
+                GreenNodeElement::Node(node) => node.remap_kinds_inner(f).into(),
+                GreenNodeElement::Token(token) => match f(token.kind()) {
+                    kind if kind == token.kind() => slot.clone(),
+                    kind => token.with_kind(kind).into(),
+                },
+                GreenNodeElement::Trivia(_) => slot.clone(),
+            })
+            .collect();
+        let diagnostics = self.to_owned().diagnostics().unwrap_or_default();
+        GreenNode::new_with_diagnostic(f(self.kind()), slots, diagnostics)
+    }
+
+    fn map_tokens_inner(&self, f: &mut impl FnMut(&GreenTokenElement) -> Option<GreenTokenElement>) -> Option<GreenNode> {
+        let mut slots: Option<Vec<GreenNodeElement>> = None;
+        for (index, slot) in self.slots().iter().enumerate() {
+            let new = match slot {
+                GreenNodeElement::Node(node) => node.map_tokens_inner(f).map(GreenNodeElement::Node),
+                GreenNodeElement::Token(token) => f(token).map(GreenNodeElement::Token),
+                GreenNodeElement::Trivia(_) => None,
+            };
+            if let Some(new) = new {
+                slots.get_or_insert_with(|| self.slots().to_vec())[index] = new;
+            }
+        }
+
+        let slots = slots?;
+        let diagnostics = self.to_owned().diagnostics().unwrap_or_default();
+        Some(GreenNode::new_with_diagnostic(self.kind(), slots, diagnostics))
+    }
+
+    /// Folds `f` over every token in this tree in source order, starting from `init`.
+    ///
+    /// Trivia is not visited on its own; it is reachable through each token. Uses an explicit stack
+    /// like [`Self::render`], so deeply nested trees do not recurse.
+    pub(crate) fn fold_tokens<A>(&self, init: A, mut f: impl FnMut(A, &GreenTokenElement) -> A) -> A {
+        let mut accumulator = init;
+        let mut stack: Vec<&GreenNodeElement> = Vec::with_capacity(64);
+        stack.extend(self.slots().iter().rev());
+
+        while let Some(item) = stack.pop() {
+            match item {
+                GreenNodeElement::Node(node) => stack.extend(node.slots().iter().rev()),
+                GreenNodeElement::Token(token) => accumulator = f(accumulator, token),
+                GreenNodeElement::Trivia(_) => {}
+            }
+        }
+        accumulator
+    }
+
+    /// Counts the tokens in this tree by kind, in a single walk.
+    ///
+    /// Only tokens are counted; nodes and trivia are not. Useful for document statistics, e.g. telling a
+    /// content-heavy file by its share of numeric tokens.
+    pub fn kind_histogram(&self) -> HashMap<SyntaxKind, usize> {
+        self.fold_tokens(HashMap::default(), |mut histogram, token| {
+            *histogram.entry(token.kind()).or_insert(0) += 1;
+            histogram
+        })
+    }
 
-        process_stack(&mut output, &mut stack);
+    /// Returns the full text of this node with every end-of-line trivia piece rewritten to `eol`.
+    ///
+    /// Only [`SyntaxKind::EndOfLineTrivia`] is rewritten. Token bytes are emitted unchanged, so line
+    /// breaks inside literal strings or raw stream data keep their original form.
+    pub fn render(&self, eol: EolStyle) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.full_width() as usize);
+        self.render_to(&mut output, eol);
         output
     }
 
+    fn render_to(&self, output: &mut Vec<u8>, eol: EolStyle) {
+        // Explicit stack to avoid recursion on deeply nested trees.
+        let mut stack: Vec<&GreenNodeElement> = Vec::with_capacity(64);
+        stack.extend(self.slots().iter().rev());
+
+        while let Some(item) = stack.pop() {
+            match item {
+                GreenNodeElement::Node(node) => stack.extend(node.slots().iter().rev()),
+                GreenNodeElement::Token(token) => {
+                    if let Some(leading) = token.leading_trivia() {
+                        leading.render_to(output, eol);
+                    }
+                    output.extend_from_slice(&token.text());
+                    if let Some(trailing) = token.trailing_trivia() {
+                        trailing.render_to(output, eol);
+                    }
+                }
+                GreenNodeElement::Trivia(trivia) if trivia.kind() == SyntaxKind::EndOfLineTrivia => output.extend_from_slice(eol.as_bytes()),
+                GreenNodeElement::Trivia(trivia) => output.extend_from_slice(trivia.text()),
+            }
+        }
+    }
+
     /// Returns the first terminal node in the node tree
     fn first_token(&self) -> Option<&GreenTokenElement> {
         for child in self.slots() {
@@ -610,6 +931,189 @@ mod tests {
     use crate::{DiagnosticKind, DiagnosticSeverity, GreenToken};
     use pretty_assertions::assert_eq;
 
+    fn nested_arrays() -> GreenNode {
+        let array = |keyword| {
+            let slots: Vec<GreenNodeElement> = vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                GreenToken::new(keyword).into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ];
+            GreenNode::new(SyntaxKind::ArrayExpression, slots)
+        };
+        let slots: Vec<GreenNodeElement> = vec![array(SyntaxKind::NullKeyword).into(), array(SyntaxKind::TrueKeyword).into()];
+        GreenNode::new(SyntaxKind::ArrayExpression, slots)
+    }
+
+    fn child_node(node: &GreenNodeData, index: usize) -> &GreenNodeData {
+        match node.slot(index) {
+            Some(GreenNodeElement::Node(child)) => child,
+            _ => panic!("expected a node at slot {index}"),
+        }
+    }
+
+    #[test]
+    fn test_map_tokens_when_one_token_replaced_expect_unchanged_subtrees_shared() {
+        let node = nested_arrays();
+        let mapped = node.map_tokens(|token| match token.kind() {
+            SyntaxKind::TrueKeyword => Some(GreenToken::new(SyntaxKind::FalseKeyword).into()),
+            _ => None,
+        });
+
+        assert_eq!(mapped.full_text(), b"[null][false]");
+        assert!(ptr::eq(child_node(&node, 0), child_node(&mapped, 0)));
+        assert!(!ptr::eq(child_node(&node, 1), child_node(&mapped, 1)));
+    }
+
+    #[test]
+    fn test_child_path_at_offset_when_inside_or_between_tokens_expect_path_preferring_earlier_slot() {
+        // "[null][true]"
+        let tree = nested_arrays();
+
+        assert_eq!(tree.child_path_at_offset(0), Some(vec![0, 0]));
+        assert_eq!(tree.child_path_at_offset(1), Some(vec![0, 0]));
+        assert_eq!(tree.child_path_at_offset(2), Some(vec![0, 1]));
+        assert_eq!(tree.child_path_at_offset(6), Some(vec![0, 2]));
+        assert_eq!(tree.child_path_at_offset(7), Some(vec![1, 0]));
+        assert_eq!(tree.child_path_at_offset(12), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_covering_slot_when_range_inside_one_token_or_spanning_children_expect_smallest_element() {
+        // "[null][true]"
+        let tree = nested_arrays();
+        let kind = |range: ops::Range<u32>| tree.covering_slot(range).map(|element| element.kind());
+
+        assert_eq!(kind(2..4), Some(SyntaxKind::NullKeyword));
+        assert_eq!(kind(0..3), Some(SyntaxKind::ArrayExpression));
+        assert_eq!(tree.covering_slot(0..6).map(|element| element.full_text()), Some(b"[null]".to_vec()));
+        assert_eq!(tree.covering_slot(5..8).map(|element| element.full_text()), Some(b"[null][true]".to_vec()));
+        assert_eq!(kind(0..12), Some(SyntaxKind::ArrayExpression));
+    }
+
+    #[test]
+    fn test_covering_slot_when_empty_range_at_boundary_or_invalid_expect_earlier_slot_or_none() {
+        let tree = nested_arrays();
+
+        assert_eq!(tree.covering_slot(1..1).map(|element| element.kind()), Some(SyntaxKind::OpenBracketToken));
+        assert_eq!(tree.covering_slot(6..6).map(|element| element.kind()), Some(SyntaxKind::CloseBracketToken));
+        assert_eq!(tree.covering_slot(12..12).map(|element| element.kind()), Some(SyntaxKind::CloseBracketToken));
+        assert_eq!(tree.covering_slot(ops::Range { start: 4, end: 2 }), None);
+        assert_eq!(tree.covering_slot(10..13), None);
+    }
+
+    #[test]
+    fn test_child_path_at_offset_when_out_of_range_or_no_terminal_expect_none() {
+        assert_eq!(nested_arrays().child_path_at_offset(13), None);
+        assert_eq!(GreenNode::new(SyntaxKind::ArrayExpression, Vec::new()).child_path_at_offset(0), None);
+    }
+
+    #[test]
+    fn test_subtree_covering_when_range_inside_inner_array_expect_that_array() {
+        let node = nested_arrays();
+        let subtree = node.subtree_covering(7..11).expect("range is in bounds");
+        assert_eq!(subtree.kind(), SyntaxKind::ArrayExpression);
+        assert_eq!(subtree.full_text(), node.full_text()[6..12].to_vec());
+    }
+
+    #[test]
+    fn test_subtree_covering_when_range_spans_children_expect_root() {
+        let node = nested_arrays();
+        let subtree = node.subtree_covering(2..8).expect("range is in bounds");
+        assert!(ptr::eq::<GreenNodeData>(&*node, &*subtree));
+        assert_eq!(node.subtree_covering(0..13), None);
+    }
+
+    #[test]
+    fn test_pieces_with_offsets_when_trivia_run_expect_kind_text_and_relative_offset() {
+        let trivia = GreenNode::new(
+            SyntaxKind::List,
+            vec![
+                GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b"  ").into(),
+                GreenTrivia::new(SyntaxKind::CommentTrivia, b"% note").into(),
+                GreenTrivia::new(SyntaxKind::EndOfLineTrivia, b"\r\n").into(),
+            ],
+        );
+
+        let pieces: Vec<_> = trivia.pieces_with_offsets().collect();
+        assert_eq!(
+            pieces,
+            vec![
+                (SyntaxKind::WhitespaceTrivia, &b"  "[..], 0),
+                (SyntaxKind::CommentTrivia, &b"% note"[..], 2),
+                (SyntaxKind::EndOfLineTrivia, &b"\r\n"[..], 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fold_tokens_when_summing_text_lengths_expect_node_text_length() {
+        let node = nested_arrays();
+        let length = node.fold_tokens(0usize, |length, token| length + token.text().len());
+        assert_eq!(length, node.text().len());
+
+        let kinds = node.fold_tokens(Vec::new(), |mut kinds, token| {
+            kinds.push(token.kind());
+            kinds
+        });
+        assert_eq!(
+            kinds[..3],
+            [SyntaxKind::OpenBracketToken, SyntaxKind::NullKeyword, SyntaxKind::CloseBracketToken]
+        );
+        assert_eq!(kinds.len(), 6);
+    }
+
+    #[test]
+    fn test_replace_tokens_of_kind_when_keywords_replaced_expect_only_that_kind_changed_and_widths_updated() {
+        let node = nested_arrays();
+        let replaced = node.replace_tokens_of_kind(SyntaxKind::NullKeyword, |_| GreenToken::new(SyntaxKind::FalseKeyword).into());
+
+        assert_eq!(replaced.full_text(), b"[false][true]");
+        assert_eq!(replaced.full_width(), node.full_width() + 1);
+        assert_eq!(child_node(&replaced, 0).full_width(), 7);
+        assert!(ptr::eq(child_node(&node, 1), child_node(&replaced, 1)));
+    }
+
+    #[test]
+    fn test_kind_histogram_when_nested_arrays_expect_counts_summing_to_token_count() {
+        let node = nested_arrays();
+        let histogram = node.kind_histogram();
+
+        assert_eq!(histogram.get(&SyntaxKind::OpenBracketToken), Some(&2));
+        assert_eq!(histogram.get(&SyntaxKind::CloseBracketToken), Some(&2));
+        assert_eq!(histogram.get(&SyntaxKind::NullKeyword), Some(&1));
+        assert_eq!(histogram.get(&SyntaxKind::ArrayExpression), None);
+        assert_eq!(histogram.values().sum::<usize>(), node.fold_tokens(0, |count, _| count + 1));
+    }
+
+    #[test]
+    fn test_remap_kinds_when_all_kinds_mapped_expect_structure_and_text_preserved() {
+        let node = nested_arrays();
+        let remapped = node.remap_kinds(|kind| match kind {
+            SyntaxKind::ArrayExpression => SyntaxKind::DictionaryExpression,
+            SyntaxKind::OpenBracketToken => SyntaxKind::OpenDictToken,
+            SyntaxKind::CloseBracketToken => SyntaxKind::CloseDictToken,
+            _ => SyntaxKind::BadToken,
+        });
+
+        assert_eq!(remapped.kind(), SyntaxKind::DictionaryExpression);
+        assert_eq!(remapped.full_text(), node.full_text());
+        assert_eq!(remapped.slot_count(), node.slot_count());
+        for index in 0..2 {
+            let (before, after) = (child_node(&node, index), child_node(&remapped, index));
+            assert_eq!(after.kind(), SyntaxKind::DictionaryExpression);
+            assert_eq!(after.full_text(), before.full_text());
+            let kinds: Vec<_> = after.slots().iter().map(|slot| slot.kind()).collect();
+            assert_eq!(kinds, vec![SyntaxKind::OpenDictToken, SyntaxKind::BadToken, SyntaxKind::CloseDictToken]);
+        }
+    }
+
+    #[test]
+    fn test_map_tokens_when_nothing_replaced_expect_same_node() {
+        let node = nested_arrays();
+        let mapped = node.map_tokens(|_| None);
+        assert!(ptr::eq::<GreenNodeData>(&*node, &*mapped));
+    }
+
     #[test]
     fn test_new_when_empty_expect_node_with_zero_width() {
         let node = GreenNode::new(SyntaxKind::List, vec![]);
@@ -977,6 +1481,54 @@ mod tests {
         assert_eq!(node1, node2);
     }
 
+    #[test]
+    fn test_eq_exact_when_only_boundary_trivia_differs_expect_not_equal() {
+        let spaced = crate::tree! {
+            SyntaxKind::ArrayExpression => {
+                (SyntaxKind::OpenBracketToken) => {
+                    trivia(SyntaxKind::WhitespaceTrivia, b" "),
+                    text(b"[")
+                },
+                (SyntaxKind::CloseBracketToken, b"]")
+            }
+        };
+        let tabbed = crate::tree! {
+            SyntaxKind::ArrayExpression => {
+                (SyntaxKind::OpenBracketToken) => {
+                    trivia(SyntaxKind::WhitespaceTrivia, b"\t"),
+                    text(b"[")
+                },
+                (SyntaxKind::CloseBracketToken, b"]")
+            }
+        };
+
+        assert_eq!(spaced.text(), tabbed.text());
+        assert!(!spaced.eq_exact(&tabbed));
+        assert!(spaced.eq_exact(&spaced.clone()));
+    }
+
+    #[test]
+    fn test_render_when_mixed_eol_trivia_expect_normalized_line_endings() {
+        let node = crate::tree! {
+            SyntaxKind::None => {
+                (SyntaxKind::NumericLiteralToken) => {
+                    trivia(SyntaxKind::EndOfLineTrivia, b"\r"),
+                    text(b"1"),
+                    trivia(SyntaxKind::EndOfLineTrivia, b"\r\n")
+                },
+                (SyntaxKind::StringLiteralToken) => {
+                    text(b"(a\rb\r\nc)"),
+                    trivia(SyntaxKind::WhitespaceTrivia, b" "),
+                    trivia(SyntaxKind::CommentTrivia, b"% note"),
+                    trivia(SyntaxKind::EndOfLineTrivia, b"\n")
+                }
+            }
+        };
+
+        assert_eq!(node.render(EolStyle::Lf), b"\n1\n(a\rb\r\nc) % note\n");
+        assert_eq!(node.render(EolStyle::CrLf), b"\r\n1\r\n(a\rb\r\nc) % note\r\n");
+    }
+
     #[test]
     fn test_new_with_diagnostic_when_created_expect_accessible_and_cleared_on_drop() {
         let diagnostic = GreenDiagnostic::new(DiagnosticKind::Unknown, DiagnosticSeverity::Warning, "node diag");
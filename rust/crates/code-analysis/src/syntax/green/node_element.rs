@@ -54,6 +54,16 @@ impl GreenNodeElement {
         }
     }
 
+    /// Returns `true` if both elements have the same kind and byte-identical full text, trivia
+    /// included, see [`GreenNodeData::eq_exact`].
+    #[inline]
+    pub fn eq_exact(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GreenNodeElement::Node(left), GreenNodeElement::Node(right)) => left.eq_exact(right),
+            _ => self.kind() == other.kind() && self.full_width() == other.full_width() && self.full_text() == other.full_text(),
+        }
+    }
+
     #[inline]
     pub fn leading_trivia_width(&self) -> u32 {
         match self {
@@ -0,0 +1,125 @@
+use crate::{GreenNode, GreenNodeData, GreenNodeElement, SyntaxKind, syntax::green::GreenSyntaxFactory};
+
+/// Options for [`GreenNodeData::normalize_whitespace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct NormalizeOptions {
+    /// Also removes all trivia after the last token, comments included, such as the bytes after a
+    /// final `%%EOF`. An end of file token's leading trivia counts as trailing trivia.
+    pub(crate) strip_trailing_trivia: bool,
+}
+
+impl GreenNodeData {
+    /// Returns a copy of this tree with whitespace trivia in canonical form, for diffing.
+    ///
+    /// Each run of whitespace and line breaks within a token's leading or trailing trivia becomes a
+    /// single line feed if it contains a line break, and a single space otherwise; a space run
+    /// starting a line or the input is dropped. Comments, the byte order mark and token text, literal strings
+    /// and stream data included, are kept exactly. Tokens whose trivia changes lose their
+    /// diagnostics, as with [`crate::GreenTokenElement::with_trivia`]; widths are recomputed.
+    pub(crate) fn normalize_whitespace(&self, options: NormalizeOptions) -> GreenNode {
+        let last_token = self
+            .fold_tokens((0usize, None), |(index, last), token| {
+                (index + 1, if token.kind() == SyntaxKind::EndOfFileToken { last } else { Some(index) })
+            })
+            .1;
+
+        let mut index = 0usize;
+        let mut at_line_start = true;
+        self.map_tokens(|token| {
+            let is_tail = options.strip_trailing_trivia && last_token.is_none_or(|last| index > last);
+            let is_last = options.strip_trailing_trivia && last_token == Some(index);
+            index += 1;
+
+            let leading = match is_tail {
+                true => None,
+                false => normalize_trivia(token.leading_trivia(), &mut at_line_start),
+            };
+            if token.width() > 0 {
+                at_line_start = false;
+            }
+            let trailing = match is_last || is_tail {
+                true => None,
+                false => normalize_trivia(token.trailing_trivia(), &mut at_line_start),
+            };
+
+            let text = |trivia: &Option<GreenNode>| trivia.as_ref().map(|trivia| trivia.full_text());
+            let is_unchanged = text(&leading) == text(&token.leading_trivia()) && text(&trailing) == text(&token.trailing_trivia());
+            (!is_unchanged).then(|| token.with_trivia(leading, trailing))
+        })
+    }
+}
+
+/// Rebuilds a trivia list with its whitespace runs collapsed, tracking whether the output is at the start of a line.
+fn normalize_trivia(trivia: Option<GreenNode>, at_line_start: &mut bool) -> Option<GreenNode> {
+    // `Some(true)` inside a run containing a line break, `Some(false)` inside a run of spaces only.
+    fn flush(run: Option<bool>, pieces: &mut Vec<GreenNodeElement>, at_line_start: &mut bool) {
+        match run {
+            Some(true) => {
+                pieces.push(GreenNodeElement::Trivia(GreenSyntaxFactory::line_feed()));
+                *at_line_start = true;
+            }
+            Some(false) if !*at_line_start => pieces.push(GreenNodeElement::Trivia(GreenSyntaxFactory::space())),
+            _ => {}
+        }
+    }
+
+    let trivia = trivia?;
+    let mut pieces = Vec::new();
+    let mut run = None;
+    for slot in trivia.slots() {
+        match slot.kind() {
+            SyntaxKind::WhitespaceTrivia => run = Some(run.unwrap_or(false)),
+            SyntaxKind::EndOfLineTrivia => run = Some(true),
+            _ => {
+                flush(run.take(), &mut pieces, at_line_start);
+                pieces.push(slot.clone());
+                *at_line_start = false;
+            }
+        }
+    }
+    flush(run, &mut pieces, at_line_start);
+
+    (!pieces.is_empty()).then(|| GreenNode::new(SyntaxKind::List, pieces))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lexer;
+    use pretty_assertions::assert_eq;
+
+    fn normalized(source: &[u8], options: NormalizeOptions) -> Vec<u8> {
+        let tree = GreenNode::from_lexer(SyntaxKind::None, &mut Lexer::new(source));
+        let normalized = tree.normalize_whitespace(options);
+        assert_eq!(normalized.full_width() as usize, normalized.full_text().len());
+        normalized.full_text()
+    }
+
+    #[test]
+    fn test_normalize_whitespace_when_runs_of_spaces_and_line_breaks_expect_single_space_or_line_feed() {
+        let source = b"1   0\tobj  \r\n\r\n   <<  /A   (x  y) >>\n\n  endobj";
+        assert_eq!(normalized(source, NormalizeOptions::default()), b"1 0 obj\n<< /A (x  y) >>\nendobj");
+    }
+
+    #[test]
+    fn test_normalize_whitespace_when_comments_expect_comments_kept_exactly() {
+        let source = b"  %PDF-1.7   \n%  binary \xE2\xE3  \r\n   null   % trailing   note\r\n";
+        assert_eq!(
+            normalized(source, NormalizeOptions::default()),
+            b"%PDF-1.7\n%  binary \xE2\xE3  \nnull % trailing   note\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_whitespace_when_stripping_trailing_trivia_expect_tail_removed() {
+        let options = NormalizeOptions { strip_trailing_trivia: true };
+        assert_eq!(normalized(b"null  \n %%EOF  \n  % tail\n", options), b"null\n%%EOF");
+        assert_eq!(normalized(b"  \n", options), b"");
+    }
+
+    #[test]
+    fn test_normalize_whitespace_when_already_canonical_expect_same_text() {
+        let source = b"1 0 obj\n[1 2]\nendobj\n";
+        assert_eq!(normalized(source, NormalizeOptions::default()), source);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{GreenNode, GreenNodeData, GreenNodeElement, Lexer, SyntaxKind, TextEdit};
+
+impl GreenNode {
+    /// Builds a token-level tree: a `kind` node whose slots are all tokens of `lexer`, the end of file token included.
+    pub(crate) fn from_lexer(kind: SyntaxKind, lexer: &mut Lexer<'_>) -> GreenNode {
+        let mut slots = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            let is_end = token.kind() == SyntaxKind::EndOfFileToken;
+            slots.push(GreenNodeElement::Token(token));
+            if is_end {
+                return GreenNode::new(kind, slots);
+            }
+        }
+    }
+}
+
+impl GreenNodeData {
+    /// Returns the tree for this node's text with `edit` applied, re-lexing only the tokens around the edit.
+    ///
+    /// `self` must be a token-level tree as built by [`GreenNode::from_lexer`] and `lexer` must run over
+    /// the edited text. The slots of this node are the reparse boundaries: slots before the edit are
+    /// reused, lexing restarts one slot before the first slot touching the edit (the safety margin, also
+    /// moved before a `stream` keyword so stream data is never entered mid-way, and before the keyword,
+    /// numbers, entries and comments of a cross-reference table so a table is always lexed from its
+    /// start), and the old slots are reused again from the first re-lexed token that ends on an old
+    /// slot boundary past the edit and equals that old slot, unless that token may still be part of a
+    /// table. The result equals [`GreenNode::from_lexer`] over the edited text, diagnostics included.
+    pub(crate) fn reparse(&self, edit: &TextEdit, lexer: &mut Lexer<'_>) -> GreenNode {
+        let slots = self.slots();
+        let ends: Vec<u32> = slots
+            .iter()
+            .scan(0, |end, slot| {
+                *end += slot.full_width();
+                Some(*end)
+            })
+            .collect();
+
+        let touching = ends.iter().position(|&end| end >= edit.range.start).unwrap_or(slots.len());
+        let mut first = touching.saturating_sub(1);
+        while first > 0 && (slots[first - 1].kind() == SyntaxKind::StreamKeyword || is_xref_table_kind(slots[first - 1].kind())) {
+            first -= 1;
+        }
+
+        let start = match first {
+            0 => 0,
+            _ => ends[first - 1] as usize,
+        };
+        let inserted_end = edit.range.start as usize + edit.new_text.len();
+        let removed_len = edit.range.len();
+
+        let mut new_slots = slots[..first].to_vec();
+        let mut old_index = first;
+        let mut position = start;
+        lexer.seek(start);
+        loop {
+            let token = lexer.next_token();
+            position += token.full_width() as usize;
+            let is_end = token.kind() == SyntaxKind::EndOfFileToken;
+            let token = GreenNodeElement::Token(token);
+
+            if position >= inserted_end {
+                let old_end = (position + removed_len - edit.new_text.len()) as u32;
+                while old_index < slots.len() && ends[old_index] < old_end {
+                    old_index += 1;
+                }
+
+                if let Some(old_slot) = slots.get(old_index)
+                    && ends[old_index] == old_end
+                    && old_end - old_slot.full_width() >= edit.range.end
+                    && !is_xref_table_kind(token.kind())
+                    && is_same(old_slot, &token)
+                {
+                    new_slots.extend_from_slice(&slots[old_index..]);
+                    break;
+                }
+            }
+
+            new_slots.push(token);
+            if is_end {
+                break;
+            }
+        }
+
+        GreenNode::new(self.kind(), new_slots)
+    }
+}
+
+/// Whether two slots lexed from equal bytes in equal lexer state.
+#[inline]
+fn is_same(a: &GreenNodeElement, b: &GreenNodeElement) -> bool {
+    a.kind() == b.kind() && a.full_text() == b.full_text() && a.diagnostics() == b.diagnostics()
+}
+
+/// Whether a token of `kind` may belong to a cross-reference table, where lexing depends on the tokens before it.
+///
+/// Comment tokens count too, since the lexer keeps its table state across them.
+#[inline]
+fn is_xref_table_kind(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::XRefKeyword | SyntaxKind::NumericLiteralToken | SyntaxKind::XRefEntryToken | SyntaxKind::CommentToken
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LexerOptions, lexer::tests::utils::xorshift};
+    use pretty_assertions::assert_eq;
+
+    const SOURCE: &[u8] = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n2 0 obj\n<< /Length 12 >>\nstream\nBT (a) Tj ET\nendstream\nendobj\n3 0 obj [(nested (str)) <48656C6C6F> 1.5 -3 true] endobj\n";
+
+    fn apply(source: &[u8], edit: &TextEdit) -> Vec<u8> {
+        let mut text = source.to_vec();
+        text.splice(edit.range.start as usize..edit.range.end as usize, edit.new_text.iter().copied());
+        text
+    }
+
+    fn assert_reparse_matches_full_parse(source: &[u8], edit: &TextEdit) {
+        assert_reparse_matches_full_parse_with_options(source, edit, LexerOptions::default());
+    }
+
+    fn assert_reparse_matches_full_parse_with_options(source: &[u8], edit: &TextEdit, options: LexerOptions) {
+        let old = GreenNode::from_lexer(SyntaxKind::None, &mut Lexer::with_options(source, options));
+        let text = apply(source, edit);
+
+        let incremental = old.reparse(edit, &mut Lexer::with_options(&text, options));
+        let full = GreenNode::from_lexer(SyntaxKind::None, &mut Lexer::with_options(&text, options));
+
+        let context = format!("edit {:?} of {:?}", edit, String::from_utf8_lossy(source));
+        assert_eq!(incremental, full, "{context}");
+        assert!(incremental.eq_exact(&full), "{context}");
+        let diagnostics = |node: &GreenNode| node.slots().iter().map(GreenNodeElement::diagnostics).collect::<Vec<_>>();
+        assert_eq!(diagnostics(&incremental), diagnostics(&full), "{context}");
+    }
+
+    #[test]
+    fn test_reparse_when_token_edited_expect_full_parse_of_edited_text() {
+        let edit = TextEdit {
+            range: 27..34,
+            new_text: b"Page".to_vec(),
+        };
+        assert_eq!(&SOURCE[27..34], b"Catalog");
+        assert_reparse_matches_full_parse(SOURCE, &edit);
+    }
+
+    #[test]
+    fn test_reparse_when_edit_opens_string_or_stream_expect_following_tokens_relexed() {
+        for (offset, new_text) in [(35u32, &b"("[..]), (35, b"stream\n"), (90, b"endstream\n"), (10, b"%"), (20, b"<")] {
+            let edit = TextEdit {
+                range: offset..offset,
+                new_text: new_text.to_vec(),
+            };
+            assert_reparse_matches_full_parse(SOURCE, &edit);
+        }
+    }
+
+    #[test]
+    fn test_reparse_when_xref_table_edited_expect_entries_relexed_from_table_start() {
+        let source = b"xref\n0 2\n0000000000 65535 f \n0000000017 00000 n \n5 1\n0000000123 00002 n \ntrailer\n";
+        for (range, new_text) in [(8u32..9, &b"1"[..]), (8..9, b"3"), (30..31, b"2"), (0..4, b"xre"), (50..50, b"\n")] {
+            let edit = TextEdit {
+                range,
+                new_text: new_text.to_vec(),
+            };
+            assert_reparse_matches_full_parse(source, &edit);
+        }
+    }
+
+    #[test]
+    fn test_reparse_when_comment_between_xref_entries_expect_table_relexed_across_comment() {
+        let source = b"xref\n0 3\n0000000000 65535 f \n% free list\n0000000017 00000 n \n0000000081 00000 n \ntrailer\n";
+        let options = LexerOptions {
+            comments_as_tokens: true,
+            ..LexerOptions::default()
+        };
+        for (range, new_text) in [(8u32..9, &b"2"[..]), (8..9, b"4"), (29..29, b" "), (42..43, b"1"), (71..72, b"9")] {
+            let edit = TextEdit {
+                range,
+                new_text: new_text.to_vec(),
+            };
+            assert_reparse_matches_full_parse_with_options(source, &edit, options);
+        }
+    }
+
+    #[test]
+    fn test_reparse_when_random_edits_expect_same_tree_as_full_parse() {
+        const FRAGMENTS: [&[u8]; 14] = [
+            b"",
+            b" ",
+            b"\n",
+            b"(",
+            b")",
+            b"\\",
+            b"<",
+            b">>",
+            b"%",
+            b"12",
+            b"/N",
+            b"R",
+            b"stream\n",
+            b"endstream",
+        ];
+
+        let mut next = xorshift(0x2545_F491_4F6C_DD1D);
+
+        for _ in 0..500 {
+            let start = next(SOURCE.len() + 1);
+            let end = (start + next(8)).min(SOURCE.len());
+            let edit = TextEdit {
+                range: start as u32..end as u32,
+                new_text: FRAGMENTS[next(FRAGMENTS.len())].to_vec(),
+            };
+            assert_reparse_matches_full_parse(SOURCE, &edit);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::BTreeMap,
+    hash::BuildHasherDefault,
+    sync::{Mutex, MutexGuard},
+};
+
+use rustc_hash::FxHasher;
+
+use crate::{GreenNode, GreenTrivia, SyntaxKind, arc::Arc};
+
+type HashMap<K, V> = hashbrown::HashMap<K, V, BuildHasherDefault<FxHasher>>;
+/// Interned trivia by kind, then by text.
+type TriviaByKind = HashMap<SyntaxKind, HashMap<Box<[u8]>, GreenTrivia>>;
+
+/// A shared store of green subtrees, so equal subtrees from different documents share one allocation.
+///
+/// Nodes are keyed by their content hash and compared by content, so, e.g., identical font
+/// dictionaries parsed from separate files intern to the same instance. The store is internally
+/// synchronized and can be shared between threads. Unless created with [`Self::with_capacity`], it
+/// holds every interned node until dropped.
+#[derive(Default, Debug)]
+pub(crate) struct SubtreeStore {
+    inner: Mutex<StoreInner>,
+}
+
+/// Hit and miss counts of [`SubtreeStore::intern`], for tuning the store's capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct StoreStats {
+    /// Lookups that returned an already stored node.
+    pub(crate) hits: u64,
+    /// Lookups that stored the given node.
+    pub(crate) misses: u64,
+}
+
+#[derive(Default, Debug)]
+struct StoreInner {
+    /// Each stored node with the tick of its last use.
+    nodes: HashMap<GreenNode, u64>,
+    /// Stored nodes by the tick of their last use, least recent first.
+    recency: BTreeMap<u64, GreenNode>,
+    tick: u64,
+    capacity: Option<usize>,
+    stats: StoreStats,
+}
+
+impl SubtreeStore {
+    /// Creates an empty, unbounded store.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty store holding at most `capacity` nodes, evicting the least recently used first.
+    ///
+    /// Eviction only gives up sharing for later equal nodes: nodes already handed out stay valid,
+    /// since they are reference counted.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let store = Self::default();
+        store.lock().capacity = Some(capacity);
+        store
+    }
+
+    /// Returns the canonical instance of `node`, storing `node` itself if no equal node is stored.
+    ///
+    /// Only `node` itself is looked up; its children are shared only if they already were. Nodes
+    /// containing diagnostics are returned unchanged and not counted, since diagnostics are attached
+    /// per instance.
+    pub(crate) fn intern(&self, node: GreenNode) -> GreenNode {
+        if node.contains_diagnostics() {
+            return node;
+        }
+
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some((canonical, last_use)) = inner.nodes.get_key_value_mut(&node) {
+            let canonical = canonical.clone();
+            let previous_use = std::mem::replace(last_use, tick);
+            inner.recency.remove(&previous_use);
+            inner.recency.insert(tick, canonical.clone());
+            inner.stats.hits += 1;
+            return canonical;
+        }
+
+        inner.stats.misses += 1;
+        inner.nodes.insert(node.clone(), tick);
+        inner.recency.insert(tick, node.clone());
+        while inner.capacity.is_some_and(|capacity| inner.nodes.len() > capacity) {
+            let Some((_, evicted)) = inner.recency.pop_first() else { break };
+            inner.nodes.remove(&evicted);
+        }
+        node
+    }
+
+    /// Returns the number of distinct nodes in the store.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.lock().nodes.len()
+    }
+
+    /// Returns the hit and miss counts since the store was created.
+    #[inline]
+    pub(crate) fn stats(&self) -> StoreStats {
+        self.lock().stats
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, StoreInner> {
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// A shared set of trivia, so equal whitespace, end-of-line markers and comments built by
+/// different [`crate::GreenNodeBuilder`]s share one allocation.
+///
+/// Only trivia created through a builder is deduplicated: the lexer creates the trivia of its
+/// tokens directly, so lexed trees do not go through the interner.
+///
+/// Trivia are keyed by kind and text. The interner is internally synchronized; share it between
+/// builders and threads through the crate's `Arc` from [`Self::new_shared`] (see
+/// [`crate::GreenNodeBuilder::with_shared_cache`]). It holds every interned trivia until dropped.
+#[derive(Default, Debug)]
+pub(crate) struct TriviaInterner {
+    trivia: Mutex<TriviaByKind>,
+}
+
+impl TriviaInterner {
+    /// Creates an empty interner.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty interner behind the crate's `Arc`, ready to be shared between builders.
+    #[inline]
+    pub(crate) fn new_shared() -> Arc<Self> {
+        Arc::new(Self::new())
+    }
+
+    /// Returns the canonical trivia of `kind` with `text`, creating it on first use.
+    pub(crate) fn intern(&self, kind: SyntaxKind, text: &[u8]) -> GreenTrivia {
+        let mut trivia = self.lock();
+        let of_kind = trivia.entry(kind).or_default();
+        if let Some(canonical) = of_kind.get(text) {
+            return canonical.clone();
+        }
+
+        let canonical = GreenTrivia::new(kind, text);
+        of_kind.insert(text.into(), canonical.clone());
+        canonical
+    }
+
+    /// Returns the number of distinct trivia in the interner.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.lock().values().map(HashMap::len).sum()
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, TriviaByKind> {
+        match self.trivia.lock() {
+            Ok(trivia) => trivia,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNodeData, GreenNodeElement, GreenToken, GreenTokenWithStringValue, SyntaxKind};
+    use pretty_assertions::assert_eq;
+
+    fn font_dictionary(font: &str) -> GreenNode {
+        let name = |value: &str| {
+            let text = format!("/{value}");
+            let token: GreenNodeElement = GreenTokenWithStringValue::new(SyntaxKind::NameLiteralToken, text.as_bytes(), value.to_string()).into();
+            GreenNode::new(SyntaxKind::NameLiteralExpression, vec![token])
+        };
+        GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenDictToken).into(),
+                name("BaseFont").into(),
+                name(font).into(),
+                GreenToken::new(SyntaxKind::CloseDictToken).into(),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_intern_when_equal_subtrees_from_two_documents_expect_one_shared_instance() {
+        let store = SubtreeStore::new();
+        let (first, second) = (font_dictionary("Helvetica"), font_dictionary("Helvetica"));
+        assert!(!std::ptr::eq::<GreenNodeData>(&*first, &*second));
+
+        let first = store.intern(first);
+        let second = store.intern(second);
+
+        assert!(std::ptr::eq::<GreenNodeData>(&*first, &*second));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_intern_when_subtrees_differ_expect_separate_instances() {
+        let store = SubtreeStore::new();
+        let helvetica = store.intern(font_dictionary("Helvetica"));
+        let courier = store.intern(font_dictionary("Courier"));
+
+        assert!(!std::ptr::eq::<GreenNodeData>(&*helvetica, &*courier));
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_intern_when_shared_between_threads_expect_same_canonical_instance() {
+        let store = SubtreeStore::new();
+        let canonical = store.intern(font_dictionary("Helvetica"));
+
+        let interned = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| store.intern(font_dictionary("Helvetica")))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>()
+        });
+
+        assert!(interned.iter().all(|node| std::ptr::eq::<GreenNodeData>(&**node, &*canonical)));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_intern_when_capacity_exceeded_expect_least_recently_used_evicted_and_handles_valid() {
+        let store = SubtreeStore::with_capacity(2);
+        let helvetica = store.intern(font_dictionary("Helvetica"));
+        let courier = store.intern(font_dictionary("Courier"));
+        assert!(std::ptr::eq::<GreenNodeData>(&*store.intern(font_dictionary("Helvetica")), &*helvetica));
+
+        // Courier is now the least recently used entry.
+        let times = store.intern(font_dictionary("Times"));
+        assert_eq!(store.len(), 2);
+
+        let courier_again = store.intern(font_dictionary("Courier"));
+        assert!(!std::ptr::eq::<GreenNodeData>(&*courier_again, &*courier));
+        assert_eq!(courier.full_text(), courier_again.full_text());
+        assert!(std::ptr::eq::<GreenNodeData>(&*store.intern(font_dictionary("Courier")), &*courier_again));
+        assert_eq!(times.full_text(), font_dictionary("Times").full_text());
+        assert_eq!(store.stats(), StoreStats { hits: 2, misses: 4 });
+    }
+}
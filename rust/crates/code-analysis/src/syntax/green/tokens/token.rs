@@ -54,6 +54,18 @@ impl GreenTokenData {
         self.kind().get_text()
     }
 
+    /// Returns `true` if the token text begins with `prefix`.
+    #[inline]
+    pub fn text_starts_with(&self, prefix: &[u8]) -> bool {
+        self.text().starts_with(prefix)
+    }
+
+    /// Returns `true` if the token text ends with `suffix`.
+    #[inline]
+    pub fn text_ends_with(&self, suffix: &[u8]) -> bool {
+        self.text().ends_with(suffix)
+    }
+
     /// Returns the length of the text covered by this token.
     #[inline]
     pub fn width(&self) -> u8 {
@@ -1,4 +1,9 @@
-use std::sync::LazyLock;
+use std::{
+    hash::{Hash, Hasher},
+    sync::LazyLock,
+};
+
+use rustc_hash::FxHasher;
 
 use crate::{
     GreenDiagnostic, GreenFlags, GreenNode, GreenSyntaxFactory, GreenToken, GreenTokenData, GreenTokenWithFloatValue,
@@ -7,7 +12,7 @@ use crate::{
     GreenTokenWithIntValueAndTrailingTriviaData, GreenTokenWithIntValueAndTrivia, GreenTokenWithIntValueAndTriviaData, GreenTokenWithIntValueData,
     GreenTokenWithStringValue, GreenTokenWithStringValueAndTrailingTrivia, GreenTokenWithStringValueAndTrailingTriviaData, GreenTokenWithStringValueAndTrivia,
     GreenTokenWithStringValueAndTriviaData, GreenTokenWithStringValueData, GreenTokenWithTrailingTrivia, GreenTokenWithTrailingTriviaData,
-    GreenTokenWithTrivia, GreenTokenWithTriviaData, SyntaxKind, syntax::green::TokenType,
+    GreenTokenWithTrivia, GreenTokenWithTriviaData, SyntaxKind, XRefEntry, syntax::green::TokenType,
 };
 
 /// Concrete token element used in node slots.
@@ -128,6 +133,48 @@ impl GreenTokenElement {
         GreenTokenWithTrivia::new_missing(kind, leading_trivia, trailing_trivia).into()
     }
 
+    /// Returns a copy of this token retagged as `kind`, keeping its text, value and trivia.
+    ///
+    /// The original bytes are always kept so widths and offsets stay identical; the compact
+    /// text-less (and value-less) representation is used only when `kind` has fixed text equal to them.
+    /// Diagnostics are not carried over, since they describe the token under its previous kind.
+    pub(crate) fn with_kind(&self, kind: SyntaxKind) -> GreenTokenElement {
+        self.rebuilt(kind, self.as_deref().text(), self.leading_trivia(), self.trailing_trivia())
+    }
+
+    /// Returns a copy of this token with its text replaced by `text`, keeping its kind, value and trivia.
+    ///
+    /// The value is not re-derived from the new text, so this suits fixes that leave it unchanged,
+    /// such as normalizing the spelling of a name. Diagnostics are not carried over.
+    pub(crate) fn with_text(&self, text: &[u8]) -> GreenTokenElement {
+        self.rebuilt(self.kind(), text, self.leading_trivia(), self.trailing_trivia())
+    }
+
+    /// Returns a copy of this token with its trivia replaced, keeping its kind, text and value.
+    ///
+    /// Diagnostics are not carried over.
+    pub(crate) fn with_trivia(&self, leading_trivia: Option<GreenNode>, trailing_trivia: Option<GreenNode>) -> GreenTokenElement {
+        self.rebuilt(self.kind(), self.as_deref().text(), leading_trivia, trailing_trivia)
+    }
+
+    fn rebuilt(&self, kind: SyntaxKind, text: &[u8], leading_trivia: Option<GreenNode>, trailing_trivia: Option<GreenNode>) -> GreenTokenElement {
+        match self {
+            _ if !kind.get_text().is_empty() && kind.get_text() == text => Self::create_with_trivia(kind, leading_trivia, trailing_trivia),
+            Self::TokenWithIntValue(t) => Self::create_with_int_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithIntValueAndTrivia(t) => Self::create_with_int_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithIntValueAndTrailingTrivia(t) => Self::create_with_int_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithFloatValue(t) => Self::create_with_float_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithFloatValueAndTrivia(t) => Self::create_with_float_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithFloatValueAndTrailingTrivia(t) => Self::create_with_float_value_and_trivia(kind, text, *t.value(), leading_trivia, trailing_trivia),
+            Self::TokenWithStringValue(t) => Self::create_with_string_value_and_trivia(kind, text, t.value().clone(), leading_trivia, trailing_trivia),
+            Self::TokenWithStringValueAndTrivia(t) => Self::create_with_string_value_and_trivia(kind, text, t.value().clone(), leading_trivia, trailing_trivia),
+            Self::TokenWithStringValueAndTrailingTrivia(t) => {
+                Self::create_with_string_value_and_trivia(kind, text, t.value().clone(), leading_trivia, trailing_trivia)
+            }
+            _ => Self::create_with_int_value_and_trivia(kind, text, 0, leading_trivia, trailing_trivia),
+        }
+    }
+
     #[inline]
     pub(crate) fn kind(&self) -> SyntaxKind {
         match self {
@@ -164,6 +211,144 @@ impl GreenTokenElement {
         }
     }
 
+    /// Returns `true` if the token text begins with `prefix`, without allocating.
+    #[inline]
+    pub(crate) fn text_starts_with(&self, prefix: &[u8]) -> bool {
+        self.as_deref().text_starts_with(prefix)
+    }
+
+    /// Returns `true` if the token text ends with `suffix`, without allocating.
+    #[inline]
+    pub(crate) fn text_ends_with(&self, suffix: &[u8]) -> bool {
+        self.as_deref().text_ends_with(suffix)
+    }
+
+    /// Returns the bytes of a name token with its leading `/` removed and `#XX` escapes resolved
+    /// (ISO 32000-2:2020 §7.3.5), or `None` if this is not a [`SyntaxKind::NameLiteralToken`].
+    ///
+    /// A `#` not followed by two hex digits is kept as is; the lexer has already reported it
+    /// as [`crate::DiagnosticKind::InvalidHexEscapeInName`]. The token text itself is not changed.
+    pub(crate) fn decoded_name(&self) -> Option<Vec<u8>> {
+        if self.kind() != SyntaxKind::NameLiteralToken {
+            return None;
+        }
+
+        let text = self.as_deref().text();
+        let text = text.strip_prefix(b"/").unwrap_or(text);
+        let mut decoded = Vec::with_capacity(text.len());
+        let mut i = 0;
+        while i < text.len() {
+            let escaped = match text.get(i..i + 3) {
+                Some([b'#', high, low]) => hex_value(*high).zip(hex_value(*low)).map(|(high, low)| (high << 4) | low),
+                _ => None,
+            };
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                None => {
+                    decoded.push(text[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        Some(decoded)
+    }
+
+    /// Returns the number of bytes a hex string decodes to (ISO 32000-2:2020 §7.3.4.3), or `None` if
+    /// this is not a [`SyntaxKind::HexStringLiteralToken`].
+    ///
+    /// Whitespace between the digits is ignored and an odd final digit counts as a whole byte, as
+    /// if followed by `0`, so `<>` and `< >` decode to 0 bytes and `<901FA>` to 3. Only hex digits
+    /// are counted; the lexer has already reported any other byte.
+    pub(crate) fn hex_decoded_len(&self) -> Option<usize> {
+        if self.kind() != SyntaxKind::HexStringLiteralToken {
+            return None;
+        }
+
+        let text = self.as_deref().text();
+        let digits = text.iter().filter(|byte| byte.is_ascii_hexdigit()).count();
+        Some(digits.div_ceil(2))
+    }
+
+    /// Returns the value of an integer [`SyntaxKind::NumericLiteralToken`], or `None` for other
+    /// kinds, for reals and for integers outside the `i64` range.
+    ///
+    /// Follows the PDF number grammar (ISO 32000-2:2020 §7.3.3) rather than Rust's: a leading `+`
+    /// and leading zeros are allowed, and a lone sign, which the lexer accepts as a number, is `0`.
+    /// Forms such as `--5` or `1-2` are lexed as [`SyntaxKind::BadToken`] and have no value. Integers
+    /// beyond the 32-bit range readers must support still have their value, the lexer reports them
+    /// as [`crate::DiagnosticKind::IntegerOverflow`].
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        if self.kind() != SyntaxKind::NumericLiteralToken {
+            return None;
+        }
+
+        let (negative, digits) = split_sign(self.as_deref().text());
+        digits.iter().try_fold(0i64, |value, &digit| {
+            let digit = match digit {
+                b'0'..=b'9' => i64::from(digit - b'0'),
+                _ => return None, // a real
+            };
+            match negative {
+                true => value.checked_mul(10)?.checked_sub(digit),
+                false => value.checked_mul(10)?.checked_add(digit),
+            }
+        })
+    }
+
+    /// Returns the value of a [`SyntaxKind::NumericLiteralToken`], integer or real, or `None` for
+    /// other kinds.
+    ///
+    /// Besides the forms of [`Self::as_i64`], a real may omit the digits on either side of its
+    /// point, as in `.5`, `-.002` or `4.`; a point without digits is `0`. Exponents are not part of
+    /// the PDF grammar and never reach a numeric token. Integers too large for `i64` are rounded.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        if self.kind() != SyntaxKind::NumericLiteralToken {
+            return None;
+        }
+
+        let (negative, digits) = split_sign(self.as_deref().text());
+        let value = match digits.iter().any(u8::is_ascii_digit) {
+            true => std::str::from_utf8(digits).ok()?.parse::<f64>().ok()?,
+            false => 0.0,
+        };
+        Some(if negative { -value } else { value })
+    }
+
+    /// Returns the offset, generation and in-use flag of a [`SyntaxKind::XRefEntryToken`], or `None`
+    /// for other kinds and for a malformed entry, which the lexer has already reported as
+    /// [`crate::DiagnosticKind::MalformedXRefEntry`].
+    #[inline]
+    pub(crate) fn xref_entry(&self) -> Option<XRefEntry> {
+        match self.kind() {
+            SyntaxKind::XRefEntryToken => XRefEntry::parse(self.as_deref().text()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if both tokens have the same kind and text, whatever their trivia.
+    ///
+    /// `==` is unchanged and still tells apart tokens that differ in trivia, value or diagnostics,
+    /// as tree identity requires; this is the comparison to use for formatting-insensitive matching.
+    #[inline]
+    pub(crate) fn eq_ignoring_trivia(&self, other: &GreenTokenElement) -> bool {
+        let (this, other) = (self.as_deref(), other.as_deref());
+        this.kind() == other.kind() && this.text() == other.text()
+    }
+
+    /// Returns a hash of the kind and text only, consistent with [`Self::eq_ignoring_trivia`],
+    /// for indexing tokens regardless of their trivia.
+    pub(crate) fn text_hash(&self) -> u64 {
+        let token = self.as_deref();
+        let mut hasher = FxHasher::default();
+        token.kind().hash(&mut hasher);
+        token.text().hash(&mut hasher);
+        hasher.finish()
+    }
+
     #[inline]
     pub(crate) fn full_text(&self) -> Vec<u8> {
         match self {
@@ -552,6 +737,16 @@ impl<'a> GreenTokenElementRef<'a> {
         }
     }
 
+    #[inline]
+    pub(crate) fn text_starts_with(&self, prefix: &[u8]) -> bool {
+        self.text().starts_with(prefix)
+    }
+
+    #[inline]
+    pub(crate) fn text_ends_with(&self, suffix: &[u8]) -> bool {
+        self.text().ends_with(suffix)
+    }
+
     #[inline]
     pub(crate) fn full_text(&self) -> Vec<u8> {
         match self {
@@ -679,6 +874,21 @@ impl<'a> GreenTokenElementRef<'a> {
     }
 }
 
+/// Splits a leading `+` or `-` off a number, returning whether it was negative and the rest.
+#[inline]
+fn split_sign(text: &[u8]) -> (bool, &[u8]) {
+    match text.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        Some((b'+', rest)) => (false, rest),
+        _ => (false, text),
+    }
+}
+
+#[inline]
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
 #[cfg(test)]
 mod memory_layout_tests {
     use super::*;
@@ -830,6 +1040,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_text_starts_with_when_owned_variants_expect_prefix_match() {
+        let variants = create_owned_variants();
+
+        assert!(variants[0].text_starts_with(b"tr"));
+        assert!(variants[2].text_starts_with(b"4"));
+        assert!(variants[4].text_starts_with(b""));
+        assert!(!variants[4].text_starts_with(b"/Type"));
+        assert!(!variants[3].text_starts_with(b"3.55"));
+    }
+
+    #[test]
+    fn test_text_ends_with_when_owned_variants_expect_suffix_match() {
+        let variants = create_owned_variants();
+
+        assert!(variants[1].text_ends_with(b"ue"));
+        assert!(variants[3].text_ends_with(b".5"));
+        assert!(!variants[5].text_ends_with(b"\n"));
+        assert!(variants.iter().all(|token| token.as_deref().text_ends_with(&token.text())));
+    }
+
+    #[test]
+    fn test_with_kind_when_owned_variants_expect_kind_changed_and_text_preserved() {
+        for token in create_owned_variants() {
+            let retagged = token.with_kind(SyntaxKind::BadToken);
+
+            assert_eq!(retagged.kind(), SyntaxKind::BadToken);
+            assert_eq!(retagged.full_text(), token.full_text());
+            assert_eq!(retagged.full_width(), token.full_width());
+        }
+    }
+
+    #[test]
+    fn test_with_kind_when_text_matches_fixed_kind_text_expect_compact_token() {
+        let token = GreenTokenElement::create_with_int_value_and_trivia(SyntaxKind::BadToken, b"null", 0, None, trailing_trivia());
+        let retagged = token.with_kind(SyntaxKind::NullKeyword);
+
+        assert!(matches!(retagged, GreenTokenElement::TokenWithTrailingTrivia(_)) || matches!(retagged, GreenTokenElement::TokenWithTrivia(_)));
+        assert_eq!(retagged.full_text(), b"null\n");
+    }
+
     #[test]
     fn test_trivia_accessors_when_owned_variants_expect_none() {
         let variants = create_owned_variants();
@@ -915,4 +1166,29 @@ mod tests {
         assert!(matches!(float_value_trailing, GreenTokenElement::TokenWithFloatValueAndTrailingTrivia(_)));
         assert!(matches!(string_value_trailing, GreenTokenElement::TokenWithStringValueAndTrailingTrivia(_)));
     }
+
+    #[test]
+    fn test_eq_ignoring_trivia_when_names_differ_only_in_leading_whitespace_expect_equal_but_not_eq() {
+        let plain: GreenTokenElement = GreenTokenWithStringValue::new(SyntaxKind::NameLiteralToken, b"/Type", "Type".to_string()).into();
+        let indented: GreenTokenElement =
+            GreenTokenWithStringValueAndTrivia::new(SyntaxKind::NameLiteralToken, b"/Type", "Type".to_string(), leading_trivia(), None).into();
+
+        assert!(plain.eq_ignoring_trivia(&indented));
+        assert_ne!(plain, indented);
+        assert_eq!(plain.text_hash(), indented.text_hash());
+    }
+
+    #[test]
+    fn test_eq_ignoring_trivia_when_kind_or_text_differs_expect_not_equal() {
+        let name: GreenTokenElement = GreenTokenWithStringValue::new(SyntaxKind::NameLiteralToken, b"/Type", "Type".to_string()).into();
+        let other_name: GreenTokenElement = GreenTokenWithStringValue::new(SyntaxKind::NameLiteralToken, b"/Subtype", "Subtype".to_string()).into();
+        let keyword: GreenTokenElement = GreenToken::new(SyntaxKind::TrueKeyword).into();
+        let keyword_with_trivia: GreenTokenElement = GreenTokenWithTrivia::new(SyntaxKind::TrueKeyword, None, trailing_trivia()).into();
+
+        assert!(!name.eq_ignoring_trivia(&other_name));
+        assert_ne!(name.text_hash(), other_name.text_hash());
+        assert!(!name.eq_ignoring_trivia(&keyword));
+        assert!(keyword.eq_ignoring_trivia(&keyword_with_trivia));
+        assert_eq!(keyword.text_hash(), keyword_with_trivia.text_hash());
+    }
 }
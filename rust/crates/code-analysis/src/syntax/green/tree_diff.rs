@@ -0,0 +1,219 @@
+use std::ops::Range;
+
+use crate::{GreenNodeData, GreenNodeElement, TextEdit};
+
+/// How a run of slots differs between two trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TreeChangeKind {
+    /// Slots only present in the new tree.
+    Insert,
+    /// Slots only present in the old tree.
+    Remove,
+    /// Slots replaced by different slots.
+    Replace,
+}
+
+/// A run of differing slots, with the byte ranges of their full text in both trees.
+///
+/// For [`TreeChangeKind::Insert`] the old range is empty and marks the insertion point, and
+/// likewise the new range for [`TreeChangeKind::Remove`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TreeChange {
+    pub(crate) kind: TreeChangeKind,
+    pub(crate) old_range: Range<u32>,
+    pub(crate) new_range: Range<u32>,
+}
+
+impl TreeChange {
+    /// Returns the edit replacing the old range by the bytes of the new range in `new_text`, the
+    /// full text of the new tree.
+    pub(crate) fn to_text_edit(&self, new_text: &[u8]) -> TextEdit {
+        TextEdit {
+            range: self.old_range.clone(),
+            new_text: new_text[self.new_range.start as usize..self.new_range.end as usize].to_vec(),
+        }
+    }
+}
+
+impl GreenNodeData {
+    /// Returns the slot runs that differ between this tree and `other`, in ascending order.
+    ///
+    /// The comparison is top-down rather than a tree edit distance: equal leading and trailing slots
+    /// are skipped, shared subtrees by pointer without looking inside. When as many slots remain on
+    /// both sides they are compared pairwise, recursing into nodes of the same kind; otherwise the
+    /// remaining runs form one change. Trees whose roots differ in kind are one [`TreeChangeKind::Replace`].
+    pub(crate) fn diff(&self, other: &GreenNodeData) -> Vec<TreeChange> {
+        let mut changes = Vec::new();
+        match self.kind() == other.kind() {
+            true => diff_slots(self.slots(), 0, other.slots(), 0, &mut changes),
+            false => changes.push(TreeChange {
+                kind: TreeChangeKind::Replace,
+                old_range: 0..self.full_width(),
+                new_range: 0..other.full_width(),
+            }),
+        }
+        changes
+    }
+}
+
+fn diff_slots(old: &[GreenNodeElement], old_start: u32, new: &[GreenNodeElement], new_start: u32, changes: &mut Vec<TreeChange>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| is_same(a, b)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| is_same(a, b))
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    let mut old_position = old_start + width(&old[..prefix]);
+    let mut new_position = new_start + width(&new[..prefix]);
+
+    if old_middle.len() == new_middle.len() {
+        for (old_slot, new_slot) in old_middle.iter().zip(new_middle) {
+            match (old_slot, new_slot) {
+                _ if is_same(old_slot, new_slot) => {}
+                (GreenNodeElement::Node(a), GreenNodeElement::Node(b)) if a.kind() == b.kind() => {
+                    diff_slots(a.slots(), old_position, b.slots(), new_position, changes)
+                }
+                _ => changes.push(TreeChange {
+                    kind: TreeChangeKind::Replace,
+                    old_range: old_position..old_position + old_slot.full_width(),
+                    new_range: new_position..new_position + new_slot.full_width(),
+                }),
+            }
+            old_position += old_slot.full_width();
+            new_position += new_slot.full_width();
+        }
+        return;
+    }
+
+    let kind = match (old_middle.is_empty(), new_middle.is_empty()) {
+        (true, _) => TreeChangeKind::Insert,
+        (_, true) => TreeChangeKind::Remove,
+        _ => TreeChangeKind::Replace,
+    };
+    changes.push(TreeChange {
+        kind,
+        old_range: old_position..old_position + width(old_middle),
+        new_range: new_position..new_position + width(new_middle),
+    });
+}
+
+#[inline]
+fn width(slots: &[GreenNodeElement]) -> u32 {
+    slots.iter().map(GreenNodeElement::full_width).sum()
+}
+
+#[inline]
+fn is_same(a: &GreenNodeElement, b: &GreenNodeElement) -> bool {
+    match (a, b) {
+        (GreenNodeElement::Node(a), GreenNodeElement::Node(b)) if std::ptr::eq::<GreenNodeData>(&**a, &**b) => true,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNode, GreenToken, SyntaxKind};
+    use pretty_assertions::assert_eq;
+
+    fn literal(kind: SyntaxKind, keyword: SyntaxKind) -> GreenNodeElement {
+        GreenNode::new(kind, vec![GreenToken::new(keyword).into()]).into()
+    }
+
+    fn array(elements: Vec<GreenNodeElement>) -> GreenNode {
+        let mut slots = vec![GreenToken::new(SyntaxKind::OpenBracketToken).into()];
+        slots.extend(elements);
+        slots.push(GreenToken::new(SyntaxKind::CloseBracketToken).into());
+        GreenNode::new(SyntaxKind::ArrayExpression, slots)
+    }
+
+    fn apply(old_text: &[u8], new_text: &[u8], changes: &[TreeChange]) -> Vec<u8> {
+        let mut text = old_text.to_vec();
+        for change in changes.iter().rev() {
+            let edit = change.to_text_edit(new_text);
+            text.splice(edit.range.start as usize..edit.range.end as usize, edit.new_text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_diff_when_one_child_replaced_expect_single_replace_with_old_and_new_spans() {
+        let old = array(vec![
+            literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword),
+            literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword),
+        ]);
+        let new = old
+            .replace_child(5, &old.slots()[2], literal(SyntaxKind::FalseLiteralExpression, SyntaxKind::FalseKeyword))
+            .expect("true literal is a child of the array");
+
+        let changes = old.diff(&new);
+        assert_eq!(
+            changes,
+            vec![TreeChange {
+                kind: TreeChangeKind::Replace,
+                old_range: 5..9,
+                new_range: 5..10,
+            }]
+        );
+        assert_eq!(apply(&old.full_text(), &new.full_text(), &changes), b"[nullfalse]");
+    }
+
+    #[test]
+    fn test_diff_when_same_tree_or_equal_copy_expect_no_changes() {
+        let null = || literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword);
+        let tree = array(vec![null()]);
+
+        assert_eq!(tree.diff(&tree), vec![]);
+        assert_eq!(tree.diff(&array(vec![null()])), vec![]);
+    }
+
+    #[test]
+    fn test_diff_when_child_inserted_or_removed_expect_insert_and_remove_with_empty_range_at_point() {
+        let null = || literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword);
+        let short = array(vec![null()]);
+        let long = array(vec![null(), literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword)]);
+
+        let inserted = short.diff(&long);
+        assert_eq!(
+            inserted,
+            vec![TreeChange {
+                kind: TreeChangeKind::Insert,
+                old_range: 5..5,
+                new_range: 5..9,
+            }]
+        );
+        assert_eq!(apply(&short.full_text(), &long.full_text(), &inserted), long.full_text());
+        assert_eq!(
+            long.diff(&short),
+            vec![TreeChange {
+                kind: TreeChangeKind::Remove,
+                old_range: 5..9,
+                new_range: 5..5,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_when_nested_change_expect_change_inside_the_nested_node_only() {
+        let null = || literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword);
+        let old = array(vec![array(vec![null()]).into(), null()]);
+        let new = array(vec![
+            array(vec![literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword)]).into(),
+            null(),
+        ]);
+
+        let changes = old.diff(&new);
+        assert_eq!(
+            changes,
+            vec![TreeChange {
+                kind: TreeChangeKind::Replace,
+                old_range: 2..6,
+                new_range: 2..6,
+            }]
+        );
+        assert_eq!(apply(&old.full_text(), &new.full_text(), &changes), b"[[true]null]");
+    }
+}
@@ -0,0 +1,221 @@
+use std::ops::ControlFlow;
+
+use crate::{GreenNodeData, GreenNodeElement, GreenTokenElement, GreenTriviaData};
+
+/// Callbacks for [`GreenNodeData::visit`], all defaulting to doing nothing.
+///
+/// Returning [`ControlFlow::Break`] from [`Self::enter_node`] skips that node's children (and its
+/// [`Self::leave_node`] call); returning it from any other callback stops the whole walk.
+pub(crate) trait GreenVisitor {
+    /// Called before the slots of `node`.
+    fn enter_node(&mut self, _node: &GreenNodeData) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called after the slots of `node`.
+    fn leave_node(&mut self, _node: &GreenNodeData) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for every token, between the calls for its leading and trailing trivia.
+    fn token(&mut self, _token: &GreenTokenElement) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for every trivia piece, whether attached to a token or a direct slot.
+    fn trivia(&mut self, _trivia: &GreenTriviaData) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// A closure visits tokens only, the most common case.
+impl<F: FnMut(&GreenTokenElement)> GreenVisitor for F {
+    #[inline]
+    fn token(&mut self, token: &GreenTokenElement) -> ControlFlow<()> {
+        self(token);
+        ControlFlow::Continue(())
+    }
+}
+
+enum Step<'a> {
+    Enter(&'a GreenNodeData),
+    Slot(&'a GreenNodeElement),
+    Leave(&'a GreenNodeData),
+}
+
+impl GreenNodeData {
+    /// Walks this subtree in source order, calling `visitor` for every node, token and trivia piece.
+    ///
+    /// Like `write_to`, this uses an explicit stack instead of recursion, so deeply nested documents
+    /// cannot overflow the call stack, and nothing is allocated besides that stack. Returns
+    /// [`ControlFlow::Break`] if the visitor stopped the walk.
+    pub(crate) fn visit<V: GreenVisitor>(&self, visitor: &mut V) -> ControlFlow<()> {
+        let mut stack = Vec::with_capacity(64);
+        stack.push(Step::Enter(self));
+
+        while let Some(step) = stack.pop() {
+            let node = match step {
+                Step::Enter(node) => node,
+                Step::Slot(GreenNodeElement::Node(node)) => node,
+                Step::Slot(GreenNodeElement::Token(token)) => {
+                    visit_token(token, visitor)?;
+                    continue;
+                }
+                Step::Slot(GreenNodeElement::Trivia(trivia)) => {
+                    visitor.trivia(trivia)?;
+                    continue;
+                }
+                Step::Leave(node) => {
+                    visitor.leave_node(node)?;
+                    continue;
+                }
+            };
+
+            if visitor.enter_node(node).is_continue() {
+                stack.push(Step::Leave(node));
+                stack.extend(node.slots().iter().rev().map(Step::Slot));
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+fn visit_token<V: GreenVisitor>(token: &GreenTokenElement, visitor: &mut V) -> ControlFlow<()> {
+    fn visit_trivia<V: GreenVisitor>(list: &GreenNodeData, visitor: &mut V) -> ControlFlow<()> {
+        for slot in list.slots() {
+            if let GreenNodeElement::Trivia(trivia) = slot {
+                visitor.trivia(trivia)?;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    if let Some(leading) = token.leading_trivia() {
+        visit_trivia(&leading, visitor)?;
+    }
+    visitor.token(token)?;
+    if let Some(trailing) = token.trailing_trivia() {
+        visit_trivia(&trailing, visitor)?;
+    }
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNode, GreenToken, Lexer, SyntaxKind};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_visit_when_closure_counts_tokens_of_kind_expect_count() {
+        let tree = GreenNode::from_lexer(SyntaxKind::None, &mut Lexer::new(b"1 0 obj [1 2 /A 3] endobj"));
+        let mut numbers = 0;
+
+        let flow = tree.visit(&mut |token: &GreenTokenElement| {
+            if token.kind() == SyntaxKind::NumericLiteralToken {
+                numbers += 1;
+            }
+        });
+
+        assert_eq!(flow, ControlFlow::Continue(()));
+        assert_eq!(numbers, 5);
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skip: Option<SyntaxKind>,
+        stop_at: Option<SyntaxKind>,
+    }
+
+    impl GreenVisitor for Recorder {
+        fn enter_node(&mut self, node: &GreenNodeData) -> ControlFlow<()> {
+            self.events.push(format!("enter {:?}", node.kind()));
+            match Some(node.kind()) == self.skip {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            }
+        }
+
+        fn leave_node(&mut self, node: &GreenNodeData) -> ControlFlow<()> {
+            self.events.push(format!("leave {:?}", node.kind()));
+            ControlFlow::Continue(())
+        }
+
+        fn token(&mut self, token: &GreenTokenElement) -> ControlFlow<()> {
+            self.events.push(format!("token {:?}", token.kind()));
+            match Some(token.kind()) == self.stop_at {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            }
+        }
+
+        fn trivia(&mut self, trivia: &GreenTriviaData) -> ControlFlow<()> {
+            self.events.push(format!("trivia {:?}", trivia.kind()));
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn nested_arrays() -> GreenNode {
+        let inner = GreenNode::new(SyntaxKind::ArrayExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
+        GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![inner.into(), GreenToken::new(SyntaxKind::TrueKeyword).into()],
+        )
+    }
+
+    #[test]
+    fn test_visit_when_enter_node_breaks_expect_subtree_pruned_and_walk_continued() {
+        let mut recorder = Recorder {
+            skip: Some(SyntaxKind::ArrayExpression),
+            ..Default::default()
+        };
+
+        assert_eq!(nested_arrays().visit(&mut recorder), ControlFlow::Continue(()));
+        assert_eq!(
+            recorder.events,
+            vec![
+                "enter DictionaryExpression",
+                "enter ArrayExpression",
+                "token TrueKeyword",
+                "leave DictionaryExpression"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_when_token_breaks_expect_walk_stopped() {
+        let mut recorder = Recorder {
+            stop_at: Some(SyntaxKind::NullKeyword),
+            ..Default::default()
+        };
+
+        assert_eq!(nested_arrays().visit(&mut recorder), ControlFlow::Break(()));
+        assert_eq!(
+            recorder.events,
+            vec!["enter DictionaryExpression", "enter ArrayExpression", "token NullKeyword"]
+        );
+    }
+
+    #[test]
+    fn test_visit_when_tokens_have_trivia_expect_trivia_around_token() {
+        let tree = GreenNode::from_lexer(SyntaxKind::None, &mut Lexer::new(b" null % c\n"));
+        let mut recorder = Recorder::default();
+        let _ = tree.visit(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            vec![
+                "enter None",
+                "trivia WhitespaceTrivia",
+                "token NullKeyword",
+                "trivia WhitespaceTrivia",
+                "trivia CommentTrivia",
+                "trivia EndOfLineTrivia",
+                "token EndOfFileToken",
+                "leave None"
+            ]
+        );
+    }
+}
@@ -1,9 +1,14 @@
+mod edit;
 mod node;
+mod text_edit;
 mod token;
 mod trivia;
 
+pub(crate) use self::edit::EditBuilder;
+
 pub use self::{
     node::SyntaxNode,
+    text_edit::TextEdit,
     token::{SyntaxToken, SyntaxTokenValueRef},
     trivia::SyntaxTrivia,
 };
@@ -0,0 +1,199 @@
+use crate::{GreenNode, GreenNodeElement, SyntaxNode};
+
+#[derive(Debug, Clone)]
+enum Edit {
+    Insert(usize, GreenNodeElement),
+    Remove(usize),
+    Replace(usize, GreenNodeElement),
+}
+
+/// Records a batch of slot edits against one node and applies them together on [`Self::commit`].
+///
+/// Slot indices always refer to the node as it was when the builder was created, so the order in
+/// which edits are recorded does not shift later indices. Nothing is applied until `commit`;
+/// dropping the builder discards the batch.
+#[must_use = "edits are only applied by `commit`"]
+pub(crate) struct EditBuilder<'n, 'a> {
+    node: &'n SyntaxNode<'a>,
+    edits: Vec<Edit>,
+}
+
+impl<'n, 'a> EditBuilder<'n, 'a> {
+    pub(crate) fn new(node: &'n SyntaxNode<'a>) -> Self {
+        Self { node, edits: Vec::new() }
+    }
+
+    /// Inserts `element` before slot `index`, or appends it when `index` equals the slot count.
+    ///
+    /// Several inserts at the same index keep their recording order.
+    pub(crate) fn insert(&mut self, index: usize, element: impl Into<GreenNodeElement>) -> &mut Self {
+        assert!(index <= self.slot_count(), "Insert index {index} out of bounds");
+        self.edits.push(Edit::Insert(index, element.into()));
+        self
+    }
+
+    /// Removes slot `index`.
+    pub(crate) fn remove(&mut self, index: usize) -> &mut Self {
+        assert!(index < self.slot_count(), "Remove index {index} out of bounds");
+        self.edits.push(Edit::Remove(index));
+        self
+    }
+
+    /// Replaces slot `index` with `element`.
+    pub(crate) fn replace(&mut self, index: usize, element: impl Into<GreenNodeElement>) -> &mut Self {
+        assert!(index < self.slot_count(), "Replace index {index} out of bounds");
+        self.edits.push(Edit::Replace(index, element.into()));
+        self
+    }
+
+    /// Applies every recorded edit at once and returns the new root.
+    ///
+    /// Only the edited node and its ancestors are rebuilt; all other subtrees are shared with the
+    /// original tree, which is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the same slot is removed or replaced more than once.
+    pub(crate) fn commit(self) -> SyntaxNode<'static> {
+        let GreenNodeElement::Node(green) = self.node.underlying_node() else {
+            unreachable!("SyntaxNode must wrap a green node");
+        };
+
+        let slot_count = green.slot_count();
+        let mut inserts: Vec<Vec<GreenNodeElement>> = vec![Vec::new(); slot_count + 1];
+        let mut targets: Vec<Option<Option<GreenNodeElement>>> = vec![None; slot_count];
+        for edit in self.edits {
+            let (index, target) = match edit {
+                Edit::Insert(index, element) => {
+                    inserts[index].push(element);
+                    continue;
+                }
+                Edit::Remove(index) => (index, None),
+                Edit::Replace(index, element) => (index, Some(element)),
+            };
+            assert!(targets[index].is_none(), "Slot {index} is removed or replaced more than once");
+            targets[index] = Some(target);
+        }
+
+        let mut slots = Vec::with_capacity(slot_count);
+        for (index, slot) in green.slots().iter().enumerate() {
+            slots.append(&mut inserts[index]);
+            match targets[index].take() {
+                None => slots.push(slot.clone()),
+                Some(replacement) => slots.extend(replacement),
+            }
+        }
+        slots.append(&mut inserts[slot_count]);
+
+        let diagnostics = green.diagnostics().unwrap_or_default();
+        let updated = GreenNode::new_with_diagnostic(green.kind(), slots, diagnostics);
+        self.node.with_green(updated.into())
+    }
+
+    #[inline]
+    fn slot_count(&self) -> usize {
+        match self.node.underlying_node() {
+            GreenNodeElement::Node(green) => green.slot_count(),
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenNodeData, GreenToken, SyntaxKind};
+    use pretty_assertions::assert_eq;
+
+    fn literal(kind: SyntaxKind, keyword: SyntaxKind) -> GreenNode {
+        GreenNode::new(kind, vec![GreenToken::new(keyword).into()])
+    }
+
+    fn array() -> GreenNode {
+        let slots: Vec<GreenNodeElement> = vec![
+            GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+            literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword).into(),
+            literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword).into(),
+            GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+        ];
+        GreenNode::new(SyntaxKind::ArrayExpression, slots)
+    }
+
+    fn false_literal() -> GreenNode {
+        literal(SyntaxKind::FalseLiteralExpression, SyntaxKind::FalseKeyword)
+    }
+
+    fn null_literal() -> GreenNode {
+        literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword)
+    }
+
+    fn green(node: &SyntaxNode<'_>) -> GreenNode {
+        match node.underlying_node() {
+            GreenNodeElement::Node(green) => green,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_commit_when_batch_of_edits_expect_same_result_as_one_by_one() {
+        let root = SyntaxNode::new(None, array().into(), 0);
+
+        let mut builder = root.edit_builder();
+        builder.replace(1, false_literal()).remove(2).insert(3, null_literal());
+        let batched = builder.commit();
+
+        let mut builder = root.edit_builder();
+        builder.replace(1, false_literal());
+        let first = builder.commit();
+        let mut builder = first.edit_builder();
+        builder.remove(2);
+        let second = builder.commit();
+        let mut builder = second.edit_builder();
+        builder.insert(2, null_literal());
+        let step = builder.commit();
+
+        assert_eq!(batched.full_text(), b"[falsenull]");
+        assert!(green(&batched).eq_exact(&green(&step)));
+    }
+
+    #[test]
+    fn test_commit_when_nested_node_edited_expect_new_root_with_siblings_shared() {
+        let object = GreenNode::new(SyntaxKind::IndirectObjectExpression, vec![array().into(), array().into()]);
+        let root = SyntaxNode::new(None, object.into(), 0);
+        let second = SyntaxNode::new(Some(&root), green(&root).slot(1).cloned().unwrap(), 10);
+
+        let mut builder = second.edit_builder();
+        builder.remove(1);
+        let edited = builder.commit();
+
+        assert_eq!(edited.kind(), SyntaxKind::IndirectObjectExpression);
+        assert_eq!(edited.full_text(), b"[nulltrue][true]");
+        let (before, after) = (green(&root), green(&edited));
+        let (Some(GreenNodeElement::Node(before)), Some(GreenNodeElement::Node(after))) = (before.slot(0), after.slot(0)) else {
+            unreachable!("first slot must be the untouched array");
+        };
+        assert!(std::ptr::eq::<GreenNodeData>(&**before, &**after));
+    }
+
+    #[test]
+    fn test_edit_builder_when_dropped_without_commit_expect_tree_unchanged() {
+        let original = array();
+        let root = SyntaxNode::new(None, original.clone().into(), 0);
+
+        let mut builder = root.edit_builder();
+        builder.remove(1).insert(0, false_literal());
+        drop(builder);
+
+        assert_eq!(root.full_text(), b"[nulltrue]");
+        assert!(std::ptr::eq::<GreenNodeData>(&*original, &*green(&root)));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn test_commit_when_slot_removed_twice_expect_panic() {
+        let root = SyntaxNode::new(None, array().into(), 0);
+        let mut builder = root.edit_builder();
+        builder.remove(1).replace(1, false_literal());
+        let _ = builder.commit();
+    }
+}
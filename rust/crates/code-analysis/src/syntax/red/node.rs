@@ -1,6 +1,13 @@
-use std::{fmt, hash, ops};
+use std::{
+    fmt::{self, Write},
+    hash::{self, Hash, Hasher},
+    io, ops,
+};
 
-use crate::{GreenDiagnostic, GreenNodeElement, SyntaxKind};
+use crate::{
+    GreenDiagnostic, GreenNode, GreenNodeElement, GreenTokenElement, GreenTrivia, LineIndex, SyntaxKind, SyntaxToken, TextEdit,
+    syntax::red::{EditBuilder, text_edit},
+};
 
 #[derive(Clone)]
 #[repr(C)]
@@ -34,6 +41,257 @@ impl<'a> SyntaxNode<'a> {
         self.parent
     }
 
+    /// Returns an iterator over this node and its ancestors, walking up to the root.
+    #[inline]
+    pub fn ancestors(&self) -> impl Iterator<Item = &SyntaxNode<'a>> {
+        std::iter::successors(Some(self), |node| node.parent())
+    }
+
+    /// Returns the nearest node of `kind`, starting with this node and ascending the parent chain.
+    #[inline]
+    pub fn ancestor_of_kind(&self, kind: SyntaxKind) -> Option<&SyntaxNode<'a>> {
+        self.ancestors().find(|node| node.kind() == kind)
+    }
+
+    /// Returns the child nodes of this node in source order, with children of `List` slots flattened in.
+    ///
+    /// Tokens and trivia are skipped.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode<'_>> {
+        let mut children = Vec::new();
+        let mut position = self.position;
+        let mut stack: Vec<&GreenNodeElement> = match &self.underlying_node {
+            GreenNodeElement::Node(node) => node.slots().iter().rev().collect(),
+            _ => Vec::new(),
+        };
+        while let Some(slot) = stack.pop() {
+            match slot {
+                GreenNodeElement::Node(node) if slot.is_list() => {
+                    stack.extend(node.slots().iter().rev());
+                    continue;
+                }
+                GreenNodeElement::Node(_) => children.push(SyntaxNode::new(Some(self), slot.clone(), position)),
+                _ => {}
+            }
+            position += slot.full_width();
+        }
+        children.into_iter()
+    }
+
+    /// Returns the child nodes of `kind`, see [`Self::children`]. Nested matches below them are not included.
+    #[inline]
+    pub fn children_of_kind(&self, kind: SyntaxKind) -> impl Iterator<Item = SyntaxNode<'_>> {
+        self.children().filter(move |child| child.kind() == kind)
+    }
+
+    /// Returns the descendant nodes whose kind matches `pred`, in pre-order, this node excluded.
+    ///
+    /// `List` nodes are walked through but never yielded. Since a red node borrows its parent, the
+    /// results are detached: they keep their absolute position, so [`Self::span`] is correct, but have
+    /// no parent. Subtrees are visited lazily, so stopping early skips the rest of the tree.
+    pub fn descendants_by_kind<F: Fn(SyntaxKind) -> bool>(&self, pred: F) -> impl Iterator<Item = SyntaxNode<'static>> {
+        let mut stack: Vec<(GreenNodeElement, u32)> = Vec::new();
+        push_child_nodes(&mut stack, &self.underlying_node, self.position);
+        std::iter::from_fn(move || {
+            while let Some((element, position)) = stack.pop() {
+                push_child_nodes(&mut stack, &element, position);
+                if !element.is_list() && pred(element.kind()) {
+                    return Some(SyntaxNode::new(None, element, position));
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns the first descendant node in pre-order whose kind matches `pred`, see [`Self::descendants_by_kind`].
+    #[inline]
+    pub fn first_descendant_by_kind(&self, pred: &dyn Fn(SyntaxKind) -> bool) -> Option<SyntaxNode<'static>> {
+        self.descendants_by_kind(pred).next()
+    }
+
+    /// Returns `true` if this node is a strict ancestor of `other`, i.e. appears in `other`'s parent chain.
+    #[inline]
+    pub fn is_ancestor_of(&self, other: &SyntaxNode<'_>) -> bool {
+        std::iter::successors(other.parent(), |node| node.parent()).any(|node| node == self)
+    }
+
+    /// Returns `true` if this node is a strict descendant of `other`.
+    #[inline]
+    pub fn is_descendant_of(&self, other: &SyntaxNode<'_>) -> bool {
+        other.is_ancestor_of(self)
+    }
+
+    /// Returns `true` if both nodes wrap equivalent green nodes, regardless of tree or position.
+    ///
+    /// `==` has cursor identity semantics: it also compares parents and positions, so equal content in
+    /// two separately built trees, or at two offsets of one tree, is `!=`. This compares only the green
+    /// side, see `GreenNodeData::eq_exact`.
+    #[inline]
+    pub fn green_eq(&self, other: &SyntaxNode<'_>) -> bool {
+        self.underlying_node.eq_exact(&other.underlying_node)
+    }
+
+    /// Returns the kind and span of every token under this node that intersects lines
+    /// `start_line..=end_line`, in source order.
+    ///
+    /// Meant for highlighting only the visible part of a large document: subtrees entirely before or
+    /// after the line range are skipped by offset without being visited. Lines past the end of the
+    /// document are clamped to it.
+    pub fn tokens_in_line_range(&self, index: &LineIndex, start_line: u32, end_line: u32) -> Vec<(SyntaxKind, ops::Range<u32>)> {
+        let Some(start) = index.line_range(start_line).map(|line| line.start) else {
+            return Vec::new();
+        };
+        let end = index.line_range(end_line).map_or(index.len(), |line| line.end);
+
+        let mut tokens = Vec::new();
+        let mut position = self.position;
+        let mut stack: Vec<&GreenNodeElement> = vec![&self.underlying_node];
+        while let Some(item) = stack.pop() {
+            let full_end = position + item.full_width();
+            if full_end <= start && item.full_width() > 0 {
+                position = full_end;
+                continue;
+            }
+            if position >= end {
+                break;
+            }
+
+            match item {
+                GreenNodeElement::Node(node) => stack.extend(node.slots().iter().rev()),
+                GreenNodeElement::Token(token) => {
+                    let token_start = position + token.leading_trivia_width();
+                    let token_end = token_start + token.width();
+                    if token_start < end && (token_end > start || (token_start == token_end && token_start >= start)) {
+                        tokens.push((token.kind(), token_start..token_end));
+                    }
+                    position = full_end;
+                }
+                GreenNodeElement::Trivia(_) => position = full_end,
+            }
+        }
+        tokens
+    }
+
+    /// Returns an identifier for this node derived from its kind and the slot indices leading to it from the root.
+    ///
+    /// Unlike the position, the ID survives edits that only change the width of unrelated subtrees,
+    /// so diagnostics republished after a reparse can be matched to the ones they replace. Inserting
+    /// or removing an earlier sibling (or one of an ancestor) does change it. IDs are only comparable
+    /// within one process.
+    pub fn stable_id(&self) -> u64 {
+        let mut hasher = hash::DefaultHasher::new();
+        let mut node = self;
+        while let Some(parent) = node.parent() {
+            node.kind().hash(&mut hasher);
+            let GreenNodeElement::Node(green) = parent.underlying_node() else {
+                unreachable!("SyntaxNode parent must wrap a green node");
+            };
+            let mut path = Vec::new();
+            let found = slot_path(&green, node.position() - parent.position(), &node.underlying_node, &mut path);
+            debug_assert!(found, "node must be a child of its parent");
+            path.hash(&mut hasher);
+            node = parent;
+        }
+        node.kind().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a hash of this subtree's structure and significant text, ignoring all trivia.
+    ///
+    /// Node and token kinds, nesting and token text are hashed; whitespace, comments and line breaks
+    /// are not, so an object that was only reformatted keeps its fingerprint across a reparse.
+    /// Fingerprints are only comparable within one process.
+    pub fn structural_fingerprint(&self) -> u64 {
+        let mut hasher = hash::DefaultHasher::new();
+        // `None` marks the end of a node, so the nesting is part of the hash.
+        let mut stack: Vec<Option<&GreenNodeElement>> = vec![Some(&self.underlying_node)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Some(GreenNodeElement::Node(node)) => {
+                    node.kind().hash(&mut hasher);
+                    stack.push(None);
+                    stack.extend(node.slots().iter().rev().map(Some));
+                }
+                Some(GreenNodeElement::Token(token)) => {
+                    token.kind().hash(&mut hasher);
+                    token.text().hash(&mut hasher);
+                }
+                Some(GreenNodeElement::Trivia(_)) => {}
+                None => hasher.write_u8(0xFF),
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Returns the byte-range replacements that turn `old`'s full text into this node's full text.
+    ///
+    /// Ranges are absolute offsets into the old text, ascending and non-overlapping, so applying the
+    /// edits from last to first reproduces the new text. Subtrees shared with `old` or with equal text
+    /// are skipped, and each edit is trimmed to the bytes that differ, so an edit in one object of a
+    /// reparsed document yields a small edit rather than a whole-document replacement.
+    pub fn text_edits_from(&self, old: &SyntaxNode<'_>) -> Vec<TextEdit> {
+        let mut edits = Vec::new();
+        match (&old.underlying_node, &self.underlying_node) {
+            (GreenNodeElement::Node(old_green), GreenNodeElement::Node(new_green)) if old_green.kind() == new_green.kind() => {
+                text_edit::diff_slots(old_green.slots(), old.position, new_green.slots(), &mut edits)
+            }
+            _ => text_edit::push_trimmed(old.position, &old.full_text(), &self.full_text(), &mut edits),
+        }
+        edits
+    }
+
+    /// Starts a batch of edits to this node's slots, applied atomically by [`EditBuilder::commit`].
+    #[inline]
+    pub(crate) fn edit_builder(&self) -> EditBuilder<'_, 'a> {
+        EditBuilder::new(self)
+    }
+
+    /// Returns a new root in which a `kind` token with `text` and the given trivia is inserted before
+    /// slot `index` of this node, or appended when `index` equals the slot count.
+    ///
+    /// Trees are immutable, so this is an [`Self::edit_builder`] insert committed at once: offsets
+    /// in the returned tree account for the new token, and this tree is left unchanged. Empty
+    /// trivia is not attached. For kinds with fixed text, such as keywords and delimiters, `text`
+    /// must be that text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the slot count, or if `text` does not match a fixed-text `kind`.
+    pub(crate) fn insert_token_at(&self, index: usize, kind: SyntaxKind, text: &[u8], leading: GreenTrivia, trailing: GreenTrivia) -> SyntaxNode<'static> {
+        let trivia_list = |trivia: GreenTrivia| (trivia.width() > 0).then(|| GreenNode::from(trivia));
+        let (leading, trailing) = (trivia_list(leading), trivia_list(trailing));
+        let token = match kind.get_text() {
+            [] => GreenTokenElement::create_with_int_value_and_trivia(kind, text, 0, leading, trailing),
+            fixed_text => {
+                assert_eq!(fixed_text, text, "Text of {kind:?} must be {:?}", String::from_utf8_lossy(fixed_text));
+                GreenTokenElement::create_with_trivia(kind, leading, trailing)
+            }
+        };
+
+        let mut builder = self.edit_builder();
+        builder.insert(index, token);
+        builder.commit()
+    }
+
+    /// Returns a new root in which this node is replaced by `replacement`.
+    ///
+    /// Only the nodes on the path from this node to the root are rebuilt; every other subtree is
+    /// shared with the original tree.
+    pub(crate) fn with_green(&self, replacement: GreenNodeElement) -> SyntaxNode<'static> {
+        let mut replacement = replacement;
+        let mut node = self;
+        while let Some(parent) = node.parent() {
+            let GreenNodeElement::Node(green) = parent.underlying_node() else {
+                unreachable!("SyntaxNode parent must wrap a green node");
+            };
+            replacement = green
+                .replace_child(node.position() - parent.position(), &node.underlying_node(), replacement)
+                .expect("node must be a child of its parent")
+                .into();
+            node = parent;
+        }
+        SyntaxNode::new(None, replacement, node.position())
+    }
+
     /// Returns a reference to the underlying green token.
     #[inline]
     pub(crate) fn underlying_node(&self) -> GreenNodeElement {
@@ -52,6 +310,31 @@ impl<'a> SyntaxNode<'a> {
         self.underlying_node.text()
     }
 
+    /// Appends the text of [`Self::text`] to `buf`, reusing its allocation.
+    #[inline]
+    pub fn text_into(&self, buf: &mut Vec<u8>) {
+        buf.reserve(self.text_len() as usize);
+        self.write_text(buf).expect("writing to a Vec cannot fail");
+    }
+
+    /// Writes the text of [`Self::text`] to `writer`, token by token, without collecting it first.
+    pub fn write_text<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        match &self.underlying_node {
+            GreenNodeElement::Node(node) => node.write_text_to(false, false, writer),
+            GreenNodeElement::Token(token) => writer.write_all(token.as_deref().text()),
+            GreenNodeElement::Trivia(trivia) => writer.write_all(trivia.text()),
+        }
+    }
+
+    /// Returns the length of [`Self::text`] in bytes without building it.
+    ///
+    /// Derived from the full width stored in the green node and the trivia widths of its first and
+    /// last tokens, so it does not depend on the size of the subtree.
+    #[inline]
+    pub fn text_len(&self) -> u32 {
+        self.width()
+    }
+
     #[inline]
     pub(crate) fn width(&self) -> u32 {
         self.underlying_node.width()
@@ -74,6 +357,34 @@ impl<'a> SyntaxNode<'a> {
         start..end
     }
 
+    /// Returns the range from the first to the last significant token under this node.
+    ///
+    /// Unlike [`Self::span`], which only trims the trivia attached to the boundary tokens, this also
+    /// skips trivia stored as standalone children at either end of the node. For nodes built by the
+    /// lexer, where trivia is always attached, both spans are equal. A node without tokens yields an
+    /// empty range at the start of its span.
+    pub fn content_span(&self) -> ops::Range<u32> {
+        let mut content: Option<ops::Range<u32>> = None;
+        let mut position = self.position;
+        let mut stack: Vec<&GreenNodeElement> = vec![&self.underlying_node];
+        while let Some(item) = stack.pop() {
+            match item {
+                GreenNodeElement::Node(node) => stack.extend(node.slots().iter().rev()),
+                GreenNodeElement::Token(token) => {
+                    let start = position + token.leading_trivia_width();
+                    let end = start + token.width();
+                    content = Some(content.map_or(start..end, |content| content.start..end));
+                    position += token.full_width();
+                }
+                GreenNodeElement::Trivia(trivia) => position += u32::from(trivia.width()),
+            }
+        }
+        content.unwrap_or_else(|| {
+            let start = self.span().start;
+            start..start
+        })
+    }
+
     /// Returns the byte range span of this token.
     #[inline]
     pub fn full_span(&self) -> ops::Range<u32> {
@@ -111,6 +422,167 @@ impl<'a> SyntaxNode<'a> {
     pub fn has_trailing_trivia(&self) -> bool {
         self.underlying_node.trailing_trivia().is_some()
     }
+
+    /// Returns the chain of nodes enclosing `offset`, ordered from the root down to the innermost node.
+    ///
+    /// The chain starts with this node's ancestors, so it is rooted at the tree root even when called
+    /// on an inner node. Each entry carries the node kind and its [`Self::span`]; containment is tested
+    /// against full spans, so an offset inside trivia belongs to the node owning that trivia. `List`
+    /// nodes are skipped. Returns an empty chain if `offset` is outside this node.
+    pub fn breadcrumb_at(&self, offset: u32) -> Vec<(SyntaxKind, ops::Range<u32>)> {
+        if !self.full_span().contains(&offset) {
+            return Vec::new();
+        }
+
+        let mut breadcrumb: Vec<_> = self.ancestors().map(|node| (node.kind(), node.span())).collect();
+        breadcrumb.reverse();
+
+        let mut current = self.underlying_node.clone();
+        let mut position = self.position;
+        'descend: while let GreenNodeElement::Node(node) = current {
+            for slot in node.slots() {
+                let end = position + slot.full_width();
+                if offset < end {
+                    if !matches!(slot, GreenNodeElement::Node(_)) {
+                        break 'descend;
+                    }
+                    if !slot.is_list() {
+                        let start = position + slot.leading_trivia_width();
+                        breadcrumb.push((slot.kind(), start..start + slot.width()));
+                    }
+                    current = slot.clone();
+                    continue 'descend;
+                }
+                position = end;
+            }
+            break;
+        }
+
+        breadcrumb
+    }
+
+    /// Serializes this node and its descendants as JSON for tree views.
+    ///
+    /// Unlike the green tree, red nodes know their absolute position, so every entry carries
+    /// `span` and `full_span` as `[start, end]` byte offsets that a viewer can use to highlight
+    /// the matching source. Token text is decoded as lossy UTF-8. List nodes are flattened into
+    /// their parent, the same way red children are exposed.
+    pub fn to_json_with_ranges(&self) -> String {
+        let mut output = String::with_capacity(self.full_width() as usize * 4);
+        self.write_json(&mut output);
+        output
+    }
+
+    fn write_json(&self, output: &mut String) {
+        write_json_entry(output, self.kind(), self.span(), self.full_span());
+        output.push_str(",\"children\":[");
+        if let GreenNodeElement::Node(node) = &self.underlying_node {
+            let mut position = self.position;
+            let mut index = 0u16;
+            self.write_children_json(node.slots(), &mut position, &mut index, output);
+        }
+        output.push_str("]}");
+    }
+
+    fn write_children_json(&self, slots: &[GreenNodeElement], position: &mut u32, index: &mut u16, output: &mut String) {
+        for slot in slots {
+            match slot {
+                GreenNodeElement::Node(node) if slot.is_list() => {
+                    self.write_children_json(node.slots(), position, index, output);
+                    continue;
+                }
+                GreenNodeElement::Trivia(_) => {
+                    *position += slot.full_width();
+                    continue;
+                }
+                _ => {}
+            }
+
+            if !output.ends_with('[') {
+                output.push(',');
+            }
+
+            match slot {
+                GreenNodeElement::Token(_) => {
+                    let token = SyntaxToken::new(self, slot.clone(), *position, *index);
+                    write_json_entry(output, token.kind(), token.span(), token.full_span());
+                    output.push_str(",\"text\":\"");
+                    write_json_escaped(output, &String::from_utf8_lossy(&token.text()));
+                    output.push_str("\"}");
+                }
+                _ => SyntaxNode::new(Some(self), slot.clone(), *position).write_json(output),
+            }
+
+            *position += slot.full_width();
+            *index += 1;
+        }
+    }
+}
+
+/// Writes the fields shared by node and token JSON entries, leaving the object open.
+fn write_json_entry(output: &mut String, kind: SyntaxKind, span: ops::Range<u32>, full_span: ops::Range<u32>) {
+    let _ = write!(
+        output,
+        "{{\"kind\":\"{:?}\",\"span\":[{},{}],\"full_span\":[{},{}]",
+        kind, span.start, span.end, full_span.start, full_span.end
+    );
+}
+
+fn write_json_escaped(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+/// Pushes the slot indices leading from `node` to `child` at `offset`, descending into lists
+/// the same way [`crate::GreenNodeData::replace_child`] does.
+fn slot_path(node: &crate::GreenNodeData, offset: u32, child: &GreenNodeElement, path: &mut Vec<usize>) -> bool {
+    let mut start = 0u32;
+    for (index, slot) in node.slots().iter().enumerate() {
+        let end = start + slot.full_width();
+        if (start..=end).contains(&offset) {
+            path.push(index);
+            if offset == start && slot == child {
+                return true;
+            }
+            if let GreenNodeElement::Node(list) = slot
+                && slot.is_list()
+                && slot_path(list, offset - start, child, path)
+            {
+                return true;
+            }
+            path.pop();
+        }
+        start = end;
+    }
+    false
+}
+
+/// Pushes the child nodes of `element`, which starts at `position`, so that they pop in source order.
+fn push_child_nodes(stack: &mut Vec<(GreenNodeElement, u32)>, element: &GreenNodeElement, position: u32) {
+    let GreenNodeElement::Node(node) = element else {
+        return;
+    };
+
+    let first = stack.len();
+    let mut position = position;
+    for slot in node.slots() {
+        if let GreenNodeElement::Node(_) = slot {
+            stack.push((slot.clone(), position));
+        }
+        position += slot.full_width();
+    }
+    stack[first..].reverse();
 }
 
 impl<'a> PartialEq for SyntaxNode<'a> {
@@ -139,3 +611,536 @@ impl<'a> fmt::Debug for SyntaxNode<'a> {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GreenSyntaxFactory, GreenToken, GreenTokenWithIntValue, Lexer, SyntaxToken};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ancestor_of_kind_when_nested_token_expect_enclosing_dictionary_and_object() {
+        let value: GreenNodeElement = GreenTokenWithIntValue::new(SyntaxKind::NumericLiteralToken, b"1", 1).into();
+        let literal = GreenNode::new(SyntaxKind::NumericLiteralExpression, vec![value.clone()]);
+        let element = GreenNode::new(SyntaxKind::DictionaryElementExpression, vec![literal.clone().into()]);
+        let dictionary = GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenDictToken).into(),
+                element.clone().into(),
+                GreenToken::new(SyntaxKind::CloseDictToken).into(),
+            ],
+        );
+        let object = GreenNode::new(SyntaxKind::IndirectObjectExpression, vec![dictionary.clone().into()]);
+
+        let object_red = SyntaxNode::new(None, object.into(), 0);
+        let dictionary_red = SyntaxNode::new(Some(&object_red), dictionary.into(), 0);
+        let element_red = SyntaxNode::new(Some(&dictionary_red), element.into(), 2);
+        let literal_red = SyntaxNode::new(Some(&element_red), literal.into(), 2);
+        let token_red = SyntaxToken::new(&literal_red, value, 2, 0);
+
+        let parent = token_red.parent();
+        assert_eq!(parent.ancestor_of_kind(SyntaxKind::NumericLiteralExpression), Some(&literal_red));
+        assert_eq!(parent.ancestor_of_kind(SyntaxKind::DictionaryExpression), Some(&dictionary_red));
+        assert_eq!(parent.ancestor_of_kind(SyntaxKind::IndirectObjectExpression), Some(&object_red));
+        assert_eq!(parent.ancestor_of_kind(SyntaxKind::ArrayExpression), None);
+        assert_eq!(parent.ancestors().count(), 4);
+    }
+
+    #[test]
+    fn test_to_json_with_ranges_when_dictionary_expect_absolute_spans_and_flattened_list() {
+        let space = GreenNode::new(SyntaxKind::List, vec![crate::GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b" ").into()]);
+        let name: GreenNodeElement =
+            crate::GreenTokenWithStringValueAndTrivia::new(SyntaxKind::NameLiteralToken, b"/A\"", "A\"".to_string(), Some(space.clone()), Some(space)).into();
+        let elements = GreenNode::new(SyntaxKind::List, vec![GreenNode::new(SyntaxKind::NameLiteralExpression, vec![name]).into()]);
+        let dictionary = GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenDictToken).into(),
+                elements.into(),
+                GreenToken::new(SyntaxKind::CloseDictToken).into(),
+            ],
+        );
+        let root = SyntaxNode::new(None, dictionary.into(), 10);
+
+        assert_eq!(
+            root.to_json_with_ranges(),
+            concat!(
+                r#"{"kind":"DictionaryExpression","span":[10,19],"full_span":[10,19],"children":["#,
+                r#"{"kind":"OpenDictToken","span":[10,12],"full_span":[10,12],"text":"<<"},"#,
+                r#"{"kind":"NameLiteralExpression","span":[13,16],"full_span":[12,17],"children":["#,
+                r#"{"kind":"NameLiteralToken","span":[13,16],"full_span":[12,17],"text":"/A\""}]},"#,
+                r#"{"kind":"CloseDictToken","span":[17,19],"full_span":[17,19],"text":">>"}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_breadcrumb_at_when_offset_in_nested_value_expect_root_first_chain_to_innermost_node() {
+        let value = GreenTokenWithIntValue::new(SyntaxKind::NumericLiteralToken, b"1", 1);
+        let literal = GreenNode::new(SyntaxKind::NumericLiteralExpression, vec![value.into()]);
+        let elements = GreenNode::new(SyntaxKind::List, vec![literal.into()]);
+        let array = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                elements.into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let object = GreenNode::new(SyntaxKind::IndirectObjectExpression, vec![array.clone().into()]);
+        let object_red = SyntaxNode::new(None, object.into(), 5);
+        let array_red = SyntaxNode::new(Some(&object_red), array.into(), 5);
+
+        let expected = vec![
+            (SyntaxKind::IndirectObjectExpression, 5..8),
+            (SyntaxKind::ArrayExpression, 5..8),
+            (SyntaxKind::NumericLiteralExpression, 6..7),
+        ];
+        assert_eq!(object_red.breadcrumb_at(6), expected);
+        assert_eq!(array_red.breadcrumb_at(6), expected);
+        assert_eq!(object_red.breadcrumb_at(5), expected[..2].to_vec());
+        assert_eq!(object_red.breadcrumb_at(8), vec![]);
+    }
+
+    #[test]
+    fn test_is_ancestor_of_when_nested_and_unrelated_nodes_expect_only_parent_chain_matches() {
+        let literal = GreenNode::new(SyntaxKind::NullLiteralExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
+        let array = GreenNode::new(SyntaxKind::ArrayExpression, vec![literal.clone().into(), literal.clone().into()]);
+        let object = GreenNode::new(SyntaxKind::IndirectObjectExpression, vec![array.clone().into()]);
+
+        let object_red = SyntaxNode::new(None, object.into(), 0);
+        let array_red = SyntaxNode::new(Some(&object_red), array.into(), 0);
+        let first_red = SyntaxNode::new(Some(&array_red), literal.clone().into(), 0);
+        let second_red = SyntaxNode::new(Some(&array_red), literal.into(), 4);
+
+        assert!(object_red.is_ancestor_of(&first_red));
+        assert!(array_red.is_ancestor_of(&second_red));
+        assert!(first_red.is_descendant_of(&object_red));
+        assert!(!first_red.is_ancestor_of(&first_red));
+        assert!(!first_red.is_ancestor_of(&second_red));
+        assert!(!second_red.is_descendant_of(&first_red));
+        assert!(!array_red.is_descendant_of(&first_red));
+    }
+
+    #[test]
+    fn test_green_eq_when_identical_trees_built_separately_expect_green_eq_but_not_eq() {
+        let build = || {
+            GreenNode::new(
+                SyntaxKind::ArrayExpression,
+                vec![
+                    GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                    GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+                ],
+            )
+        };
+        let first = SyntaxNode::new(None, build().into(), 0);
+        let second = SyntaxNode::new(None, build().into(), 4);
+        let different = SyntaxNode::new(
+            None,
+            GreenNode::new(SyntaxKind::ArrayExpression, vec![GreenToken::new(SyntaxKind::OpenBracketToken).into()]).into(),
+            0,
+        );
+
+        assert_ne!(first, second);
+        assert!(first.green_eq(&second));
+        assert!(first.green_eq(&first.clone()));
+        assert!(!first.green_eq(&different));
+    }
+
+    #[test]
+    fn test_content_span_when_standalone_trivia_children_at_ends_expect_trivia_trimmed() {
+        let space: GreenNodeElement = crate::GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b"  ").into();
+        let array = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                space.clone(),
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                space.clone(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+                space,
+            ],
+        );
+        let node = SyntaxNode::new(None, array.into(), 10);
+
+        assert_eq!(node.span(), 10..18);
+        assert_eq!(node.content_span(), 12..16);
+    }
+
+    #[test]
+    fn test_content_span_when_trivia_attached_to_tokens_expect_same_as_span() {
+        let space = GreenNode::new(SyntaxKind::List, vec![crate::GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b" ").into()]);
+        let open = GreenNodeElement::Token(crate::GreenTokenWithTrivia::new(SyntaxKind::OpenBracketToken, Some(space.clone()), None).into());
+        let close = GreenNodeElement::Token(crate::GreenTokenWithTrivia::new(SyntaxKind::CloseBracketToken, None, Some(space)).into());
+        let node = SyntaxNode::new(None, GreenNode::new(SyntaxKind::ArrayExpression, vec![open, close]).into(), 0);
+
+        assert_eq!(node.content_span(), 1..3);
+        assert_eq!(node.content_span(), node.span());
+    }
+
+    #[test]
+    fn test_tokens_in_line_range_when_boundaries_at_line_starts_and_ends_expect_intersecting_tokens() {
+        let source = b"1 0 obj\n<< /A [1 2]\n/B 3 >>\nendobj";
+        let mut lexer = crate::Lexer::new(source);
+        let mut builder = crate::GreenNodeBuilder::new();
+        std::iter::from_fn(|| Some(lexer.next_token()))
+            .take_while(|token| token.kind() != SyntaxKind::EndOfFileToken)
+            .for_each(|token| builder.token(token));
+        let root = SyntaxNode::new(None, builder.finish_wrapped(SyntaxKind::None).0.into(), 0);
+        let index = LineIndex::new(source);
+        let kinds = |start_line, end_line| {
+            root.tokens_in_line_range(&index, start_line, end_line)
+                .into_iter()
+                .map(|(kind, _)| kind)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            root.tokens_in_line_range(&index, 1, 1),
+            vec![
+                (SyntaxKind::OpenDictToken, 8..10),
+                (SyntaxKind::NameLiteralToken, 11..13),
+                (SyntaxKind::OpenBracketToken, 14..15),
+                (SyntaxKind::NumericLiteralToken, 15..16),
+                (SyntaxKind::NumericLiteralToken, 17..18),
+                (SyntaxKind::CloseBracketToken, 18..19),
+            ]
+        );
+        assert_eq!(
+            kinds(0, 0),
+            vec![
+                SyntaxKind::NumericLiteralToken,
+                SyntaxKind::NumericLiteralToken,
+                SyntaxKind::IndirectObjectKeyword
+            ]
+        );
+        assert_eq!(
+            kinds(2, 9),
+            vec![
+                SyntaxKind::NameLiteralToken,
+                SyntaxKind::NumericLiteralToken,
+                SyntaxKind::CloseDictToken,
+                SyntaxKind::IndirectEndObjectKeyword
+            ]
+        );
+        assert_eq!(kinds(9, 9), vec![]);
+    }
+
+    #[test]
+    fn test_stable_id_when_unrelated_sibling_edited_expect_id_unchanged() {
+        let literal = |kind, keyword| GreenNode::new(kind, vec![GreenToken::new(keyword).into()]);
+        let array = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword).into(),
+                literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword).into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let object = GreenNode::new(SyntaxKind::IndirectObjectExpression, vec![array.clone().into(), array.clone().into()]);
+        let child = |parent: &GreenNodeElement, index: usize| match parent {
+            GreenNodeElement::Node(node) => node.slot(index).cloned().unwrap(),
+            _ => unreachable!(),
+        };
+
+        let root = SyntaxNode::new(None, object.into(), 0);
+        let first = SyntaxNode::new(Some(&root), child(&root.underlying_node(), 0), 0);
+        let second = SyntaxNode::new(Some(&root), child(&root.underlying_node(), 1), 10);
+        let first_null = SyntaxNode::new(Some(&first), child(&first.underlying_node(), 1), 1);
+        let second_null = SyntaxNode::new(Some(&second), child(&second.underlying_node(), 1), 11);
+        let second_true = SyntaxNode::new(Some(&second), child(&second.underlying_node(), 2), 15);
+
+        let true_literal = SyntaxNode::new(Some(&first), child(&first.underlying_node(), 2), 5);
+        let edited_root = true_literal.with_green(literal(SyntaxKind::FalseLiteralExpression, SyntaxKind::FalseKeyword).into());
+        let edited_second = SyntaxNode::new(Some(&edited_root), child(&edited_root.underlying_node(), 1), 11);
+        let edited_null = SyntaxNode::new(Some(&edited_second), child(&edited_second.underlying_node(), 1), 12);
+
+        assert_eq!(edited_root.full_text(), b"[nullfalse][nulltrue]");
+        assert_eq!(edited_null.stable_id(), second_null.stable_id());
+        assert_eq!(edited_root.stable_id(), root.stable_id());
+        assert_ne!(second_null.stable_id(), first_null.stable_id());
+        assert_ne!(second_null.stable_id(), second_true.stable_id());
+    }
+
+    #[test]
+    fn test_children_of_kind_when_nested_match_expect_only_direct_children() {
+        let element = |value: GreenNode| GreenNode::new(SyntaxKind::ArrayElementExpression, vec![value.into()]);
+        let inner = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                element(GreenNode::new(
+                    SyntaxKind::NullLiteralExpression,
+                    vec![GreenToken::new(SyntaxKind::NullKeyword).into()],
+                ))
+                .into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let elements = GreenNode::new(
+            SyntaxKind::List,
+            vec![
+                element(GreenNode::new(
+                    SyntaxKind::TrueLiteralExpression,
+                    vec![GreenToken::new(SyntaxKind::TrueKeyword).into()],
+                ))
+                .into(),
+                element(inner).into(),
+            ],
+        );
+        let outer = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                elements.into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let root = SyntaxNode::new(None, outer.into(), 0);
+
+        let children: Vec<_> = root.children_of_kind(SyntaxKind::ArrayElementExpression).collect();
+        assert_eq!(children.iter().map(|child| child.span()).collect::<Vec<_>>(), vec![1..5, 5..11]);
+        assert!(children.iter().all(|child| child.parent() == Some(&root)));
+        assert_eq!(root.children_of_kind(SyntaxKind::NullLiteralExpression).count(), 0);
+        assert_eq!(
+            children[1].children().map(|child| child.kind()).collect::<Vec<_>>(),
+            vec![SyntaxKind::ArrayExpression]
+        );
+    }
+
+    #[test]
+    fn test_descendants_by_kind_when_nested_matches_expect_pre_order_detached_nodes_with_spans() {
+        let element = |value: GreenNode| GreenNode::new(SyntaxKind::ArrayElementExpression, vec![value.into()]);
+        let null = GreenNode::new(SyntaxKind::NullLiteralExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
+        let inner = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                GreenNode::new(SyntaxKind::List, vec![element(null).into()]).into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let elements = GreenNode::new(
+            SyntaxKind::List,
+            vec![
+                element(GreenNode::new(
+                    SyntaxKind::TrueLiteralExpression,
+                    vec![GreenToken::new(SyntaxKind::TrueKeyword).into()],
+                ))
+                .into(),
+                element(inner).into(),
+            ],
+        );
+        let outer = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                elements.into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let root = SyntaxNode::new(None, outer.into(), 10);
+
+        let elements: Vec<_> = root.descendants_by_kind(|kind| kind == SyntaxKind::ArrayElementExpression).collect();
+        assert_eq!(elements.iter().map(|node| node.span()).collect::<Vec<_>>(), vec![11..15, 15..21, 16..20]);
+        assert!(elements.iter().all(|node| node.parent().is_none()));
+
+        let arrays: Vec<_> = root
+            .descendants_by_kind(|kind| kind == SyntaxKind::ArrayExpression)
+            .map(|node| node.span())
+            .collect();
+        assert_eq!(arrays, vec![15..21]);
+        assert_eq!(root.descendants_by_kind(|kind| kind == SyntaxKind::List).count(), 0);
+    }
+
+    #[test]
+    fn test_first_descendant_by_kind_when_predicate_matches_expect_first_in_pre_order() {
+        let literal = |kind: SyntaxKind, keyword: SyntaxKind| GreenNode::new(kind, vec![GreenToken::new(keyword).into()]);
+        let array = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenBracketToken).into(),
+                literal(SyntaxKind::NullLiteralExpression, SyntaxKind::NullKeyword).into(),
+                literal(SyntaxKind::FalseLiteralExpression, SyntaxKind::FalseKeyword).into(),
+                literal(SyntaxKind::TrueLiteralExpression, SyntaxKind::TrueKeyword).into(),
+                GreenToken::new(SyntaxKind::CloseBracketToken).into(),
+            ],
+        );
+        let root = SyntaxNode::new(None, array.into(), 0);
+
+        let boolean = root.first_descendant_by_kind(&|kind| matches!(kind, SyntaxKind::TrueLiteralExpression | SyntaxKind::FalseLiteralExpression));
+        assert_eq!(
+            boolean.map(|node| (node.kind(), node.span())),
+            Some((SyntaxKind::FalseLiteralExpression, 5..10))
+        );
+        assert_eq!(root.first_descendant_by_kind(&|kind| kind == SyntaxKind::ArrayExpression), None);
+    }
+
+    #[test]
+    fn test_structural_fingerprint_when_only_formatting_differs_expect_same_fingerprint() {
+        let fingerprint = |source: &[u8]| {
+            let mut lexer = crate::Lexer::new(source);
+            let mut builder = crate::GreenNodeBuilder::new();
+            builder.start_node(SyntaxKind::IndirectObjectExpression);
+            std::iter::from_fn(|| Some(lexer.next_token()))
+                .take_while(|token| token.kind() != SyntaxKind::EndOfFileToken)
+                .for_each(|token| builder.token(token));
+            builder.finish_node();
+            SyntaxNode::new(None, builder.finish().into(), 0).structural_fingerprint()
+        };
+
+        let original = fingerprint(b"1 0 obj << /A [1 2] >> endobj");
+        assert_eq!(fingerprint(b"1 0 obj\n<</A[1 2]>> % note\r\nendobj"), original);
+        assert_ne!(fingerprint(b"1 0 obj << /A [1 3] >> endobj"), original);
+        assert_ne!(fingerprint(b"1 0 obj << /B [1 2] >> endobj"), original);
+    }
+
+    #[test]
+    fn test_structural_fingerprint_when_nesting_differs_expect_different_fingerprint() {
+        let null = || GreenNode::new(SyntaxKind::NullLiteralExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
+        let nested = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![GreenNode::new(SyntaxKind::ArrayExpression, vec![null().into()]).into(), null().into()],
+        );
+        let flat = GreenNode::new(
+            SyntaxKind::ArrayExpression,
+            vec![GreenNode::new(SyntaxKind::ArrayExpression, vec![null().into(), null().into()]).into()],
+        );
+
+        let nested = SyntaxNode::new(None, nested.into(), 0);
+        let flat = SyntaxNode::new(None, flat.into(), 0);
+        assert_ne!(nested.structural_fingerprint(), flat.structural_fingerprint());
+    }
+
+    #[test]
+    fn test_text_edits_from_when_reparsed_after_edits_expect_minimal_edits_reproducing_new_text() {
+        fn parse(source: &[u8]) -> GreenNode {
+            let mut lexer = crate::Lexer::new(source);
+            let mut builder = crate::GreenNodeBuilder::new();
+            builder.start_node(SyntaxKind::None);
+            let mut in_object = false;
+            let mut token = lexer.next_token();
+            while token.kind() != SyntaxKind::EndOfFileToken {
+                let is_end = token.kind() == SyntaxKind::IndirectEndObjectKeyword;
+                if !in_object {
+                    builder.start_node(SyntaxKind::IndirectObjectExpression);
+                    in_object = true;
+                }
+                builder.token(token);
+                if is_end {
+                    builder.finish_node();
+                    in_object = false;
+                }
+                token = lexer.next_token();
+            }
+            builder.finish_node();
+            builder.finish()
+        }
+        fn apply(old: &[u8], edits: &[TextEdit]) -> Vec<u8> {
+            let mut text = old.to_vec();
+            for edit in edits.iter().rev() {
+                text.splice(edit.range.start as usize..edit.range.end as usize, edit.new_text.iter().copied());
+            }
+            text
+        }
+
+        let old_text = b"1 0 obj << /A 1 >> endobj\n2 0 obj [true] endobj\n";
+        let old = SyntaxNode::new(None, parse(old_text).into(), 0);
+        for new_text in [
+            &b"1 0 obj << /A 1 >> endobj\n2 0 obj [false] endobj\n"[..],
+            b"1 0 obj << /A 12 >> endobj\n2 0 obj [true] endobj\n",
+            b"1 0 obj << /A 1 >> endobj\n3 0 obj null endobj\n2 0 obj [true] endobj\n",
+            b"",
+        ] {
+            let new = SyntaxNode::new(None, parse(new_text).into(), 0);
+            let edits = new.text_edits_from(&old);
+            assert_eq!(apply(old_text, &edits), new_text);
+            assert!(edits.windows(2).all(|pair| pair[0].range.end <= pair[1].range.start));
+        }
+
+        let new = SyntaxNode::new(None, parse(b"1 0 obj << /A 1 >> endobj\n2 0 obj [false] endobj\n").into(), 0);
+        assert_eq!(
+            new.text_edits_from(&old),
+            vec![TextEdit {
+                range: 35..38,
+                new_text: b"fals".to_vec()
+            }]
+        );
+        assert_eq!(old.text_edits_from(&old), vec![]);
+    }
+
+    #[test]
+    fn test_insert_token_at_when_name_with_leading_space_expect_new_root_with_token_spliced_in() {
+        let mut lexer = Lexer::new(b"<</Type /Catalog>>");
+        let tokens: Vec<GreenNodeElement> = std::iter::from_fn(|| Some(lexer.next_token()))
+            .take_while(|token| token.kind() != SyntaxKind::EndOfFileToken)
+            .map(GreenNodeElement::Token)
+            .collect();
+        let dictionary = GreenNode::new(SyntaxKind::DictionaryExpression, tokens);
+        let root = SyntaxNode::new(
+            None,
+            GreenNode::new(
+                SyntaxKind::None,
+                vec![
+                    dictionary.into(),
+                    GreenNode::new(SyntaxKind::NullLiteralExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]).into(),
+                ],
+            )
+            .into(),
+            0,
+        );
+        let dictionary = root.children().next().unwrap();
+
+        let no_trivia = GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b"");
+        let edited = dictionary.insert_token_at(3, SyntaxKind::NameLiteralToken, b"/NewKey", GreenSyntaxFactory::space(), no_trivia);
+
+        assert_eq!(edited.full_text(), b"<</Type /Catalog /NewKey>>null");
+        assert_eq!(root.full_text(), b"<</Type /Catalog>>null");
+        let children: Vec<_> = edited.children().collect();
+        let GreenNodeElement::Node(green) = children[0].underlying_node() else {
+            unreachable!("dictionary must be a node");
+        };
+        assert_eq!(
+            green.slot(3).map(|slot| (slot.kind(), slot.full_text())),
+            Some((SyntaxKind::NameLiteralToken, b" /NewKey".to_vec()))
+        );
+        assert_eq!(children[1].span(), 26..30);
+    }
+
+    #[test]
+    fn test_write_text_when_nested_nodes_with_trivia_expect_same_bytes_as_text() {
+        let mut lexer = Lexer::new(b"  % header\n1 0 obj\n<< /Kids [ 2 0 R ] >> % note\nendobj  \n");
+        let mut builder = crate::GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::IndirectObjectExpression);
+        for _ in 0..3 {
+            builder.token(lexer.next_token());
+        }
+        builder.start_node(SyntaxKind::DictionaryExpression);
+        builder.token(lexer.next_token());
+        builder.token(lexer.next_token());
+        builder.start_node(SyntaxKind::ArrayExpression);
+        for _ in 0..5 {
+            builder.token(lexer.next_token());
+        }
+        builder.finish_node();
+        builder.token(lexer.next_token());
+        builder.finish_node();
+        builder.token(lexer.next_token());
+        builder.finish_node();
+        let root = SyntaxNode::new(None, builder.finish().into(), 0);
+        let dictionary = root.children().next().expect("dictionary child");
+
+        for node in [&root, &dictionary] {
+            let mut written = Vec::new();
+            node.write_text(&mut written).expect("writing to a Vec cannot fail");
+            assert_eq!(written, node.text());
+            assert_eq!(node.text_len() as usize, node.text().len());
+        }
+        assert_eq!(dictionary.text(), b"<< /Kids [ 2 0 R ] >>");
+
+        let mut buf = b"text: ".to_vec();
+        root.text_into(&mut buf);
+        assert_eq!(buf, b"text: 1 0 obj\n<< /Kids [ 2 0 R ] >> % note\nendobj");
+    }
+}
@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+use crate::GreenNodeElement;
+
+/// A replacement of the bytes in `range` of the old text by `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Absolute byte range in the old text.
+    pub range: Range<u32>,
+    /// Bytes replacing the range.
+    pub new_text: Vec<u8>,
+}
+
+/// Appends the edits turning the slots `old` (starting at byte `old_start`) into the slots `new`.
+///
+/// Equal leading and trailing slots are skipped by pointer or text comparison. When as many slots
+/// remain on both sides they are compared pairwise, recursing into nodes of the same kind;
+/// otherwise the whole remaining run becomes one edit. Edits are trimmed to the bytes that differ.
+pub(super) fn diff_slots(old: &[GreenNodeElement], old_start: u32, new: &[GreenNodeElement], edits: &mut Vec<TextEdit>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| is_same(a, b)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| is_same(a, b))
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    let mut position = old_start + old[..prefix].iter().map(|slot| slot.full_width()).sum::<u32>();
+
+    if old_middle.len() == new_middle.len() {
+        for (old_slot, new_slot) in old_middle.iter().zip(new_middle) {
+            match (old_slot, new_slot) {
+                _ if is_same(old_slot, new_slot) => {}
+                (GreenNodeElement::Node(a), GreenNodeElement::Node(b)) if a.kind() == b.kind() => diff_slots(a.slots(), position, b.slots(), edits),
+                _ => push_trimmed(position, &old_slot.full_text(), &new_slot.full_text(), edits),
+            }
+            position += old_slot.full_width();
+        }
+        return;
+    }
+
+    let old_text: Vec<u8> = old_middle.iter().flat_map(|slot| slot.full_text()).collect();
+    let new_text: Vec<u8> = new_middle.iter().flat_map(|slot| slot.full_text()).collect();
+    push_trimmed(position, &old_text, &new_text, edits);
+}
+
+/// Appends an edit replacing `old` at `start` by `new`, without their common leading and trailing bytes.
+pub(super) fn push_trimmed(start: u32, old: &[u8], new: &[u8], edits: &mut Vec<TextEdit>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    if prefix == old.len() && prefix == new.len() {
+        return;
+    }
+
+    let range = start + prefix as u32..start + (old.len() - suffix) as u32;
+    edits.push(TextEdit {
+        range,
+        new_text: new[prefix..new.len() - suffix].to_vec(),
+    });
+}
+
+#[inline]
+fn is_same(a: &GreenNodeElement, b: &GreenNodeElement) -> bool {
+    if let (GreenNodeElement::Node(a), GreenNodeElement::Node(b)) = (a, b)
+        && std::ptr::eq::<crate::GreenNodeData>(&**a, &**b)
+    {
+        return true;
+    }
+    a.kind() == b.kind() && a.full_width() == b.full_width() && a.full_text() == b.full_text()
+}
@@ -174,6 +174,49 @@ impl<'a> SyntaxToken<'a> {
             .or_else(|| token.as_token_with_string_value_and_trailing_trivia().map(|t| t.value().as_str()))
     }
 
+    /// Returns a new root in which only this token's kind is changed to `kind`.
+    ///
+    /// Text, trivia and therefore all offsets are preserved. Only the nodes on the path from this
+    /// token to the root are rebuilt; every other subtree is shared with the original tree.
+    pub fn with_kind(&self, kind: SyntaxKind) -> SyntaxNode<'static> {
+        let GreenNodeElement::Node(green) = self.parent.underlying_node() else {
+            unreachable!("SyntaxToken parent must wrap a green node");
+        };
+        let replacement = self.token_element().with_kind(kind).into();
+        let updated = green
+            .replace_child(self.position - self.parent.position(), &self.underlying_node, replacement)
+            .expect("token must be a child of its parent");
+        self.parent.with_green(updated.into())
+    }
+
+    /// Returns a new root in which this token's text is replaced by `new_text`.
+    ///
+    /// Kind, value and trivia are kept; the value is not re-derived from the new text. The widths of
+    /// every ancestor, and so the offsets of everything after the token, shift by the length difference.
+    ///
+    /// Red trees are immutable and have no mutable mode, so there is no in-place `set_text(&self, ..)`:
+    /// like [`Self::with_kind`], this rebuilds the path to the root and callers continue from the
+    /// returned root, while the original tree stays valid.
+    pub fn with_text(&self, new_text: &[u8]) -> SyntaxNode<'static> {
+        let GreenNodeElement::Node(green) = self.parent.underlying_node() else {
+            unreachable!("SyntaxToken parent must wrap a green node");
+        };
+        let replacement = self.token_element().with_text(new_text).into();
+        let updated = green
+            .replace_child(self.position - self.parent.position(), &self.underlying_node, replacement)
+            .expect("token must be a child of its parent");
+        self.parent.with_green(updated.into())
+    }
+
+    /// Returns `true` if both tokens wrap equivalent green tokens, regardless of tree or position.
+    ///
+    /// Unlike `==`, which also compares parent, position and index, this compares kind, text and
+    /// trivia only, so tokens from separately built trees can be matched.
+    #[inline]
+    pub fn green_eq(&self, other: &SyntaxToken<'_>) -> bool {
+        self.underlying_node.eq_exact(&other.underlying_node)
+    }
+
     #[inline]
     fn token_element(&self) -> &GreenTokenElement {
         match &self.underlying_node {
@@ -258,6 +301,47 @@ mod tests {
         assert_eq!(red_token.value(), Some(SyntaxTokenValueRef::String("Type")));
     }
 
+    #[test]
+    fn test_with_kind_when_nested_token_expect_only_that_token_retagged_and_siblings_shared() {
+        let space = GreenNode::new(SyntaxKind::List, vec![crate::GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b" ").into()]);
+        let key = GreenNode::new(
+            SyntaxKind::NameLiteralExpression,
+            vec![GreenNodeElement::Token(
+                GreenTokenWithStringValueAndTrailingTrivia::new(SyntaxKind::NameLiteralToken, b"/Open", "Open".to_string(), Some(space.clone())).into(),
+            )],
+        );
+        let bad: GreenNodeElement = GreenTokenElement::create_with_int_value_and_trivia(SyntaxKind::BadToken, b"ture", 0, None, Some(space)).into();
+        let value = GreenNode::new(SyntaxKind::TrueLiteralExpression, vec![bad.clone()]);
+        let dictionary = GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenDictToken).into(),
+                key.clone().into(),
+                value.clone().into(),
+                GreenToken::new(SyntaxKind::CloseDictToken).into(),
+            ],
+        );
+
+        let root = SyntaxNode::new(None, dictionary.clone().into(), 0);
+        let value_red = SyntaxNode::new(Some(&root), value.into(), 8);
+        let token_red = SyntaxToken::new(&value_red, bad, 8, 0);
+
+        let new_root = token_red.with_kind(SyntaxKind::NameLiteralToken);
+        let GreenNodeElement::Node(new_dictionary) = new_root.underlying_node() else {
+            panic!("expected a node root");
+        };
+
+        assert_eq!(new_root.full_text(), root.full_text());
+        assert_eq!(new_root.full_span(), root.full_span());
+        let new_value = new_dictionary.slot(2).and_then(|slot| slot.as_node()).expect("value node");
+        assert_eq!(new_value.slot(0).map(|slot| slot.kind()), Some(SyntaxKind::NameLiteralToken));
+
+        let old_key = dictionary.slot(1).and_then(|slot| slot.as_node()).expect("key node");
+        let new_key = new_dictionary.slot(1).and_then(|slot| slot.as_node()).expect("key node");
+        assert!(std::ptr::eq::<crate::GreenNodeData>(&**old_key, &**new_key));
+        assert!(!std::ptr::eq::<crate::GreenNodeData>(&*dictionary, &*new_dictionary));
+    }
+
     #[test]
     fn test_value_when_plain_token_expect_none() {
         let parent_green = GreenNode::new(SyntaxKind::DirectObjectExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
@@ -271,4 +355,48 @@ mod tests {
         assert_eq!(red_token.string_value(), None);
         assert_eq!(red_token.value(), None);
     }
+
+    #[test]
+    fn test_green_eq_when_same_token_in_separate_trees_expect_green_eq_but_not_eq() {
+        let build = || GreenNode::new(SyntaxKind::DirectObjectExpression, vec![GreenToken::new(SyntaxKind::NullKeyword).into()]);
+        let (first_parent, second_parent) = (SyntaxNode::new(None, build().into(), 0), SyntaxNode::new(None, build().into(), 7));
+        let first = SyntaxToken::new(&first_parent, GreenToken::new(SyntaxKind::NullKeyword).into(), 0, 0);
+        let second = SyntaxToken::new(&second_parent, GreenToken::new(SyntaxKind::NullKeyword).into(), 7, 0);
+        let other = SyntaxToken::new(&first_parent, GreenToken::new(SyntaxKind::TrueKeyword).into(), 0, 0);
+
+        assert_ne!(first, second);
+        assert!(first.green_eq(&second));
+        assert!(!first.green_eq(&other));
+    }
+
+    #[test]
+    fn test_with_text_when_name_lengthened_expect_trivia_kept_and_following_offsets_shifted() {
+        let space = GreenNode::new(SyntaxKind::List, vec![crate::GreenTrivia::new(SyntaxKind::WhitespaceTrivia, b" ").into()]);
+        let name = GreenNodeElement::Token(
+            GreenTokenWithStringValueAndTrailingTrivia::new(SyntaxKind::NameLiteralToken, b"/Open", "Open".to_string(), Some(space)).into(),
+        );
+        let key = GreenNode::new(SyntaxKind::NameLiteralExpression, vec![name.clone()]);
+        let value = GreenNode::new(SyntaxKind::TrueLiteralExpression, vec![GreenToken::new(SyntaxKind::TrueKeyword).into()]);
+        let dictionary = GreenNode::new(
+            SyntaxKind::DictionaryExpression,
+            vec![
+                GreenToken::new(SyntaxKind::OpenDictToken).into(),
+                key.clone().into(),
+                value.into(),
+                GreenToken::new(SyntaxKind::CloseDictToken).into(),
+            ],
+        );
+
+        let root = SyntaxNode::new(None, dictionary.into(), 0);
+        let key_red = SyntaxNode::new(Some(&root), key.into(), 2);
+        let token_red = SyntaxToken::new(&key_red, name, 2, 0);
+        assert_eq!(root.breadcrumb_at(9).last(), Some(&(SyntaxKind::TrueLiteralExpression, 8..12)));
+
+        let new_root = token_red.with_text(b"/OpenAction");
+
+        assert_eq!(new_root.full_text(), b"<</OpenAction true>>");
+        assert_eq!(new_root.full_span(), 0..20);
+        assert_eq!(new_root.breadcrumb_at(15).last(), Some(&(SyntaxKind::TrueLiteralExpression, 14..18)));
+        assert_eq!(new_root.breadcrumb_at(3).last(), Some(&(SyntaxKind::NameLiteralExpression, 2..13)));
+    }
 }
@@ -48,6 +48,19 @@ pub enum SyntaxKind {
     // special tokens
     /// Raw stream data token
     RawStreamDataToken,
+    /// Object number and offset pairs at the start of a decoded object stream, e.g. `11 0 12 27`.
+    ///
+    /// See: ISO 32000-2:2020, §7.5.7 Object streams.
+    ObjStmHeaderToken,
+    /// A fixed-width cross-reference table entry, e.g. `0000000017 00000 n`, see `XRefEntry`.
+    ///
+    /// See: ISO 32000-2:2020, §7.5.4 Cross-reference table.
+    XRefEntryToken,
+    /// A comment from `%` up to the next EOL, emitted as a token rather than
+    /// [`SyntaxKind::CommentTrivia`] when `LexerOptions::comments_as_tokens` is set.
+    ///
+    /// See: ISO 32000-2:2020, §7.2.4 Comments.
+    CommentToken,
 
     // invalid tokens
     BadToken,
@@ -66,6 +79,8 @@ pub enum SyntaxKind {
     ///
     /// See: ISO 32000-2:2020, §7.2.4 Comments.
     CommentTrivia,
+    /// UTF-8 byte order mark (`EF BB BF`) that some producers incorrectly write before the `%PDF-` header.
+    ByteOrderMarkTrivia,
 
     // primary expressions
     NumericLiteralExpression,
@@ -263,6 +278,151 @@ pub enum SyntaxKind {
     CurveToFinalReplicatedOperator,
 }
 
+/// Names of all kinds, indexed by discriminant, as returned by [`SyntaxKind::name`].
+///
+/// Sized by the last kind, so adding a kind without a name fails to compile.
+const KIND_NAMES: [&str; SyntaxKind::CurveToFinalReplicatedOperator as usize + 1] = [
+    "None",
+    "List",
+    "PdfVersionToken",
+    "NumericLiteralToken",
+    "NameLiteralToken",
+    "StringLiteralToken",
+    "HexStringLiteralToken",
+    "EndOfFileMarkerToken",
+    "TrueKeyword",
+    "FalseKeyword",
+    "NullKeyword",
+    "IndirectObjectKeyword",
+    "IndirectEndObjectKeyword",
+    "IndirectReferenceKeyword",
+    "StreamKeyword",
+    "EndStreamKeyword",
+    "XRefKeyword",
+    "XRefFreeEntryKeyword",
+    "XRefInUseEntryKeyword",
+    "FileTrailerKeyword",
+    "StartXRefKeyword",
+    "OpenBracketToken",
+    "CloseBracketToken",
+    "OpenDictToken",
+    "CloseDictToken",
+    "EndOfFileToken",
+    "RawStreamDataToken",
+    "ObjStmHeaderToken",
+    "XRefEntryToken",
+    "CommentToken",
+    "BadToken",
+    "EndOfLineTrivia",
+    "WhitespaceTrivia",
+    "CommentTrivia",
+    "ByteOrderMarkTrivia",
+    "NumericLiteralExpression",
+    "NameLiteralExpression",
+    "StringLiteralExpression",
+    "HexStringLiteralExpression",
+    "TrueLiteralExpression",
+    "FalseLiteralExpression",
+    "NullLiteralExpression",
+    "DirectObjectExpression",
+    "ArrayExpression",
+    "ArrayElementExpression",
+    "DictionaryExpression",
+    "DictionaryElementExpression",
+    "IndirectObjectExpression",
+    "IndirectObjectHeaderExpression",
+    "IndirectObjectBodyExpression",
+    "IndirectReferenceExpression",
+    "StreamExpression",
+    "StreamBodyExpression",
+    "StreamRawDataExpression",
+    "StreamOperandOperatorExpression",
+    "TextObjectExpression",
+    "InlineImageExpression",
+    "MarkedContentExpression",
+    "CompatibilityExpression",
+    "XRefTableExpression",
+    "XRefSectionExpression",
+    "XRefSubSectionExpression",
+    "XRefEntryExpression",
+    "FileTrailerExpression",
+    "FileTrailerStartXrefExpression",
+    "PdfDocument",
+    "PdfDocumentElementExpression",
+    "PdfVersionExpression",
+    "CloseFillStrokePathOperator",
+    "FillStrokePathOperator",
+    "CloseFillStrokePathEvenOddOperator",
+    "FillStrokePathEvenOddOperator",
+    "BeginMarkedContentPropertyOperator",
+    "BeginInlineImageOperator",
+    "BeginMarkedContentOperator",
+    "BeginTextOperator",
+    "BeginCompatibilityOperator",
+    "CurveToOperator",
+    "ConcatMatrixOperator",
+    "SetStrokeColorSpaceOperator",
+    "SetNonStrokeColorSpaceOperator",
+    "SetDashPatternOperator",
+    "SetCharWidthOperator",
+    "SetCacheDeviceOperator",
+    "InvokeXObjectOperator",
+    "DefineMarkedContentPropertyOperator",
+    "EndInlineImageOperator",
+    "EndMarkedContentOperator",
+    "EndTextOperator",
+    "EndCompatibilityOperator",
+    "FillPathOperator",
+    "FillPathDeprecatedOperator",
+    "FillPathEvenOddOperator",
+    "SetStrokeGrayOperator",
+    "SetNonStrokeGrayOperator",
+    "SetGraphicsStateParametersOperator",
+    "CloseSubpathOperator",
+    "SetFlatnessToleranceOperator",
+    "BeginInlineImageDataOperator",
+    "SetLineJoinOperator",
+    "SetLineCapOperator",
+    "SetStrokeCMYKColorOperator",
+    "SetNonStrokeCMYKColorOperator",
+    "LineToOperator",
+    "MoveToOperator",
+    "SetMiterLimitOperator",
+    "DefineMarkedContentPointOperator",
+    "EndPathOperator",
+    "SaveGraphicsStateOperator",
+    "RestoreGraphicsStateOperator",
+    "RectangleOperator",
+    "SetStrokeRGBColorOperator",
+    "SetNonStrokeRGBColorOperator",
+    "SetRenderingIntentOperator",
+    "CloseStrokePathOperator",
+    "StrokePathOperator",
+    "SetStrokeColorOperator",
+    "SetNonStrokeColorOperator",
+    "SetStrokeColorICCSpecialOperator",
+    "SetNonStrokeColorICCSpecialOperator",
+    "ShadeFillOperator",
+    "TextNextLineOperator",
+    "SetCharSpacingOperator",
+    "MoveTextPositionOperator",
+    "MoveTextSetLeadingOperator",
+    "SetTextFontOperator",
+    "ShowTextOperator",
+    "ShowTextAdjustedOperator",
+    "SetTextLeadingOperator",
+    "SetTextMatrixOperator",
+    "SetTextRenderingModeOperator",
+    "SetTextRiseOperator",
+    "SetWordSpacingOperator",
+    "SetHorizontalScalingOperator",
+    "CurveToInitialReplicatedOperator",
+    "SetLineWidthOperator",
+    "ClipOperator",
+    "EvenOddClipOperator",
+    "CurveToFinalReplicatedOperator",
+];
+
 impl SyntaxKind {
     pub const FIRST_WELL_KNOWN_TEXT_TOKEN_KIND: SyntaxKind = SyntaxKind::EndOfFileMarkerToken;
     pub const LAST_WELL_KNOWN_TEXT_TOKEN_KIND: SyntaxKind = SyntaxKind::CloseDictToken;
@@ -275,6 +435,59 @@ impl SyntaxKind {
         }
     }
 
+    /// Returns `true` for the keyword tokens, from `true`, `false` and `null` to `startxref`.
+    ///
+    /// Content stream operators are not keywords.
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::TrueKeyword
+                | SyntaxKind::FalseKeyword
+                | SyntaxKind::NullKeyword
+                | SyntaxKind::IndirectObjectKeyword
+                | SyntaxKind::IndirectEndObjectKeyword
+                | SyntaxKind::IndirectReferenceKeyword
+                | SyntaxKind::StreamKeyword
+                | SyntaxKind::EndStreamKeyword
+                | SyntaxKind::XRefKeyword
+                | SyntaxKind::XRefFreeEntryKeyword
+                | SyntaxKind::XRefInUseEntryKeyword
+                | SyntaxKind::FileTrailerKeyword
+                | SyntaxKind::StartXRefKeyword
+        )
+    }
+
+    /// Returns `true` for number, name, literal string and hexadecimal string tokens.
+    ///
+    /// Booleans and `null` are keywords, see [`Self::is_keyword`].
+    pub fn is_literal(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::NumericLiteralToken | SyntaxKind::NameLiteralToken | SyntaxKind::StringLiteralToken | SyntaxKind::HexStringLiteralToken
+        )
+    }
+
+    /// Returns `true` for the trivia kinds: line breaks, whitespace, comments and the byte order mark.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::EndOfLineTrivia | SyntaxKind::WhitespaceTrivia | SyntaxKind::CommentTrivia | SyntaxKind::ByteOrderMarkTrivia
+        )
+    }
+
+    /// Returns `true` for the array and dictionary brackets `[`, `]`, `<<` and `>>`.
+    pub fn is_delimiter(&self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::OpenBracketToken | SyntaxKind::CloseBracketToken | SyntaxKind::OpenDictToken | SyntaxKind::CloseDictToken
+        )
+    }
+
+    /// Returns `true` for number tokens, integer and real alike.
+    pub fn is_number(&self) -> bool {
+        matches!(self, SyntaxKind::NumericLiteralToken)
+    }
+
     pub fn get_text(&self) -> &'static [u8] {
         match self {
             SyntaxKind::EndOfFileMarkerToken => b"%%EOF",
@@ -298,6 +511,158 @@ impl SyntaxKind {
             _ => b"",
         }
     }
+
+    /// Returns the stable name of this kind, e.g. `"NumericLiteralToken"`.
+    ///
+    /// Names equal the variant identifiers but come from an explicit table rather than `Debug`, so
+    /// they can be relied on as an external contract, e.g. in serialized trees.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        KIND_NAMES[*self as usize]
+    }
+
+    /// Returns the kind named `name`, the inverse of [`Self::name`].
+    pub fn from_name(name: &str) -> Option<SyntaxKind> {
+        let index = KIND_NAMES.iter().position(|candidate| *candidate == name)?;
+        SyntaxKind::try_from(index as u8).ok()
+    }
+
+    /// Returns a human-readable name for user-facing text such as diagnostics and hovers,
+    /// e.g. `"name"` for [`SyntaxKind::NameLiteralToken`].
+    ///
+    /// Unlike the `Debug` output, this is prose and may be shared by several kinds.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SyntaxKind::None => "nothing",
+            SyntaxKind::List => "list",
+            SyntaxKind::PdfVersionToken | SyntaxKind::PdfVersionExpression => "PDF version header",
+            SyntaxKind::NumericLiteralToken | SyntaxKind::NumericLiteralExpression => "number literal",
+            SyntaxKind::NameLiteralToken | SyntaxKind::NameLiteralExpression => "name",
+            SyntaxKind::StringLiteralToken | SyntaxKind::StringLiteralExpression => "literal string",
+            SyntaxKind::HexStringLiteralToken | SyntaxKind::HexStringLiteralExpression => "hexadecimal string",
+            SyntaxKind::EndOfFileMarkerToken => "end-of-file marker",
+            SyntaxKind::TrueKeyword | SyntaxKind::FalseKeyword | SyntaxKind::TrueLiteralExpression | SyntaxKind::FalseLiteralExpression => "boolean",
+            SyntaxKind::NullKeyword | SyntaxKind::NullLiteralExpression => "null",
+            SyntaxKind::IndirectObjectKeyword => "'obj' keyword",
+            SyntaxKind::IndirectEndObjectKeyword => "'endobj' keyword",
+            SyntaxKind::IndirectReferenceKeyword => "'R' keyword",
+            SyntaxKind::StreamKeyword => "'stream' keyword",
+            SyntaxKind::EndStreamKeyword => "'endstream' keyword",
+            SyntaxKind::XRefKeyword => "'xref' keyword",
+            SyntaxKind::XRefFreeEntryKeyword => "free entry marker",
+            SyntaxKind::XRefInUseEntryKeyword => "in-use entry marker",
+            SyntaxKind::FileTrailerKeyword => "'trailer' keyword",
+            SyntaxKind::StartXRefKeyword => "'startxref' keyword",
+            SyntaxKind::OpenBracketToken => "'['",
+            SyntaxKind::CloseBracketToken => "']'",
+            SyntaxKind::OpenDictToken => "'<<'",
+            SyntaxKind::CloseDictToken => "'>>'",
+            SyntaxKind::EndOfFileToken => "end of file",
+            SyntaxKind::RawStreamDataToken | SyntaxKind::StreamRawDataExpression => "stream data",
+            SyntaxKind::ObjStmHeaderToken => "object stream header",
+            SyntaxKind::XRefEntryToken => "cross-reference entry",
+            SyntaxKind::BadToken => "unrecognized token",
+            SyntaxKind::EndOfLineTrivia => "line break",
+            SyntaxKind::WhitespaceTrivia => "whitespace",
+            SyntaxKind::CommentTrivia | SyntaxKind::CommentToken => "comment",
+            SyntaxKind::ByteOrderMarkTrivia => "byte order mark",
+            SyntaxKind::DirectObjectExpression => "direct object",
+            SyntaxKind::ArrayExpression => "array",
+            SyntaxKind::ArrayElementExpression => "array element",
+            SyntaxKind::DictionaryExpression => "dictionary",
+            SyntaxKind::DictionaryElementExpression => "dictionary entry",
+            SyntaxKind::IndirectObjectExpression => "indirect object",
+            SyntaxKind::IndirectObjectHeaderExpression => "indirect object header",
+            SyntaxKind::IndirectObjectBodyExpression => "indirect object body",
+            SyntaxKind::IndirectReferenceExpression => "indirect reference",
+            SyntaxKind::StreamExpression => "stream",
+            SyntaxKind::StreamBodyExpression => "stream body",
+            SyntaxKind::StreamOperandOperatorExpression => "content stream instruction",
+            SyntaxKind::TextObjectExpression => "text object",
+            SyntaxKind::InlineImageExpression => "inline image",
+            SyntaxKind::MarkedContentExpression => "marked-content sequence",
+            SyntaxKind::CompatibilityExpression => "compatibility section",
+            SyntaxKind::XRefTableExpression => "cross-reference table",
+            SyntaxKind::XRefSectionExpression => "cross-reference section",
+            SyntaxKind::XRefSubSectionExpression => "cross-reference subsection",
+            SyntaxKind::XRefEntryExpression => "cross-reference entry",
+            SyntaxKind::FileTrailerExpression => "file trailer",
+            SyntaxKind::FileTrailerStartXrefExpression => "'startxref' section",
+            SyntaxKind::PdfDocument => "PDF document",
+            SyntaxKind::PdfDocumentElementExpression => "document element",
+            SyntaxKind::CloseFillStrokePathOperator
+            | SyntaxKind::FillStrokePathOperator
+            | SyntaxKind::CloseFillStrokePathEvenOddOperator
+            | SyntaxKind::FillStrokePathEvenOddOperator
+            | SyntaxKind::BeginMarkedContentPropertyOperator
+            | SyntaxKind::BeginInlineImageOperator
+            | SyntaxKind::BeginMarkedContentOperator
+            | SyntaxKind::BeginTextOperator
+            | SyntaxKind::BeginCompatibilityOperator
+            | SyntaxKind::CurveToOperator
+            | SyntaxKind::ConcatMatrixOperator
+            | SyntaxKind::SetStrokeColorSpaceOperator
+            | SyntaxKind::SetNonStrokeColorSpaceOperator
+            | SyntaxKind::SetDashPatternOperator
+            | SyntaxKind::SetCharWidthOperator
+            | SyntaxKind::SetCacheDeviceOperator
+            | SyntaxKind::InvokeXObjectOperator
+            | SyntaxKind::DefineMarkedContentPropertyOperator
+            | SyntaxKind::EndInlineImageOperator
+            | SyntaxKind::EndMarkedContentOperator
+            | SyntaxKind::EndTextOperator
+            | SyntaxKind::EndCompatibilityOperator
+            | SyntaxKind::FillPathOperator
+            | SyntaxKind::FillPathDeprecatedOperator
+            | SyntaxKind::FillPathEvenOddOperator
+            | SyntaxKind::SetStrokeGrayOperator
+            | SyntaxKind::SetNonStrokeGrayOperator
+            | SyntaxKind::SetGraphicsStateParametersOperator
+            | SyntaxKind::CloseSubpathOperator
+            | SyntaxKind::SetFlatnessToleranceOperator
+            | SyntaxKind::BeginInlineImageDataOperator
+            | SyntaxKind::SetLineJoinOperator
+            | SyntaxKind::SetLineCapOperator
+            | SyntaxKind::SetStrokeCMYKColorOperator
+            | SyntaxKind::SetNonStrokeCMYKColorOperator
+            | SyntaxKind::LineToOperator
+            | SyntaxKind::MoveToOperator
+            | SyntaxKind::SetMiterLimitOperator
+            | SyntaxKind::DefineMarkedContentPointOperator
+            | SyntaxKind::EndPathOperator
+            | SyntaxKind::SaveGraphicsStateOperator
+            | SyntaxKind::RestoreGraphicsStateOperator
+            | SyntaxKind::RectangleOperator
+            | SyntaxKind::SetStrokeRGBColorOperator
+            | SyntaxKind::SetNonStrokeRGBColorOperator
+            | SyntaxKind::SetRenderingIntentOperator
+            | SyntaxKind::CloseStrokePathOperator
+            | SyntaxKind::StrokePathOperator
+            | SyntaxKind::SetStrokeColorOperator
+            | SyntaxKind::SetNonStrokeColorOperator
+            | SyntaxKind::SetStrokeColorICCSpecialOperator
+            | SyntaxKind::SetNonStrokeColorICCSpecialOperator
+            | SyntaxKind::ShadeFillOperator
+            | SyntaxKind::TextNextLineOperator
+            | SyntaxKind::SetCharSpacingOperator
+            | SyntaxKind::MoveTextPositionOperator
+            | SyntaxKind::MoveTextSetLeadingOperator
+            | SyntaxKind::SetTextFontOperator
+            | SyntaxKind::ShowTextOperator
+            | SyntaxKind::ShowTextAdjustedOperator
+            | SyntaxKind::SetTextLeadingOperator
+            | SyntaxKind::SetTextMatrixOperator
+            | SyntaxKind::SetTextRenderingModeOperator
+            | SyntaxKind::SetTextRiseOperator
+            | SyntaxKind::SetWordSpacingOperator
+            | SyntaxKind::SetHorizontalScalingOperator
+            | SyntaxKind::CurveToInitialReplicatedOperator
+            | SyntaxKind::SetLineWidthOperator
+            | SyntaxKind::ClipOperator
+            | SyntaxKind::EvenOddClipOperator
+            | SyntaxKind::CurveToFinalReplicatedOperator => "content stream operator",
+        }
+    }
 }
 
 impl From<SyntaxKind> for u8 {
@@ -317,3 +682,52 @@ impl TryFrom<u8> for SyntaxKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_display_name_when_token_and_expression_expect_shared_prose_name() {
+        assert_eq!(SyntaxKind::NameLiteralToken.display_name(), "name");
+        assert_eq!(SyntaxKind::NumericLiteralExpression.display_name(), "number literal");
+        assert_eq!(SyntaxKind::NumericLiteralToken.display_name(), "number literal");
+    }
+
+    #[test]
+    fn test_display_name_when_operator_expect_content_stream_operator() {
+        assert_eq!(SyntaxKind::ShowTextOperator.display_name(), "content stream operator");
+        assert_eq!(SyntaxKind::CloseFillStrokePathOperator.display_name(), "content stream operator");
+    }
+
+    #[test]
+    fn test_classification_when_kinds_of_each_group_expect_only_their_group() {
+        assert!(SyntaxKind::StreamKeyword.is_keyword());
+        assert!(SyntaxKind::NullKeyword.is_keyword());
+        assert!(SyntaxKind::NumericLiteralToken.is_number());
+        assert!(SyntaxKind::NumericLiteralToken.is_literal());
+        assert!(SyntaxKind::HexStringLiteralToken.is_literal());
+        assert!(SyntaxKind::WhitespaceTrivia.is_trivia());
+        assert!(SyntaxKind::CommentTrivia.is_trivia());
+        assert!(SyntaxKind::OpenDictToken.is_delimiter());
+
+        assert!(!SyntaxKind::ShowTextOperator.is_keyword());
+        assert!(!SyntaxKind::TrueKeyword.is_literal());
+        assert!(!SyntaxKind::NameLiteralToken.is_number());
+        assert!(!SyntaxKind::StringLiteralToken.is_delimiter());
+        assert!(!SyntaxKind::BadToken.is_trivia());
+    }
+
+    #[test]
+    fn test_name_when_every_kind_expect_debug_name_and_from_name_round_trip() {
+        let kinds: Vec<SyntaxKind> = (0..=u8::MAX).map_while(|value| SyntaxKind::try_from(value).ok()).collect();
+        assert_eq!(kinds.len(), KIND_NAMES.len());
+
+        for kind in kinds {
+            assert_eq!(kind.name(), format!("{kind:?}"));
+            assert_eq!(SyntaxKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(SyntaxKind::from_name("numericLiteralToken"), None);
+    }
+}